@@ -0,0 +1,20 @@
+use pierro::widget::{button::Button, text::Text, virtual_list::VirtualList};
+
+struct State {
+    assets: Vec<String>,
+    opened: Option<usize>
+}
+
+pub fn main() {
+    pierro::app::App::new(State {
+        assets: (0..10000).map(|i| format!("Asset {}", i + 1)).collect(),
+        opened: None
+    }, |state| {
+        let labels: Vec<String> = state.assets.iter().enumerate().map(|(idx, asset)| {
+            if state.opened == Some(idx) { format!("{} (open)", asset) } else { asset.clone() }
+        }).collect();
+        VirtualList::new(state.assets.len(), 30.0, move |idx| {
+            Button::new(Text::new(labels[idx].clone())).on_click(move |state: &mut State| state.opened = Some(idx))
+        })
+    }).run();
+}
@@ -0,0 +1,50 @@
+use pierro::{painter::{path::PathBuilder, RectBuilder}, pos, vec2, widget::{canvas_2d::Canvas2D, column::Column, text::Text}, Color, Pos, Rect};
+
+fn page() -> Rect {
+    Rect::min_size(Pos::ZERO, vec2(800.0, 600.0))
+}
+
+#[derive(Default)]
+struct State {
+    strokes: Vec<Vec<Pos>>
+}
+
+pub fn main() {
+    pierro::app::App::new(State::default(), |state| {
+        let strokes = state.strokes.clone();
+        Column::new(vec![
+            Text::new("Draw with the left button. Middle-drag or space-drag to pan, ctrl+scroll to zoom, F to fit."),
+            Canvas2D::new(move |painter, input| {
+                painter.rect(RectBuilder::new(page()).fill(Color::WHITE));
+
+                // Keep the grid a constant thickness on screen
+                let line_width = 1.0 / input.zoom;
+                for x in (0..=800).step_by(50) {
+                    let x = x as f32;
+                    painter.path(PathBuilder::line(pos(x, 0.0), pos(x, 600.0)).stroke(Color::from_hex(0xD0D4DCFF)).stroke_width(line_width));
+                }
+                for y in (0..=600).step_by(50) {
+                    let y = y as f32;
+                    painter.path(PathBuilder::line(pos(0.0, y), pos(800.0, y)).stroke(Color::from_hex(0xD0D4DCFF)).stroke_width(line_width));
+                }
+
+                for stroke in &strokes {
+                    painter.path(PathBuilder::polyline(stroke).stroke(Color::from_hex(0x202428FF)).stroke_width(3.0));
+                }
+
+                if let Some(pointer) = input.pointer {
+                    painter.path(PathBuilder::circle(pointer, 4.0 / input.zoom).stroke(Color::from_hex(0xE8804AFF)).stroke_width(line_width));
+                }
+            })
+            .content_bounds(page())
+            .zoom_range(0.25..=16.0)
+            .on_press(|state: &mut State, pos| state.strokes.push(vec![pos]))
+            .on_drag(|state: &mut State, pos| {
+                if let Some(stroke) = state.strokes.last_mut() {
+                    stroke.push(pos);
+                }
+            })
+            .build()
+        ])
+    }).run();
+}
@@ -0,0 +1,58 @@
+use pierro::{vec2, widget::{button::Button, column::Column, curve_editor::{Curve, CurveEditor, CurveKey, CurveKeyId, TangentMode}, row::Row, text::Text}, Color};
+
+struct State {
+    curves: Vec<Curve>,
+    selected: Vec<CurveKeyId>
+}
+
+fn set_mode(state: &mut State, mode: TangentMode) {
+    for id in &state.selected {
+        let curve = &mut state.curves[id.curve];
+        // Start from the handles the key shows now, so switching away from auto keeps the curve's shape
+        let (in_handle, out_handle) = curve.handles(id.key);
+        let key = &mut curve.keys[id.key];
+        *key = key.with_handles(in_handle, out_handle, mode);
+    }
+}
+
+fn mode_button(label: &str, mode: TangentMode) -> pierro::WidgetNode<State> {
+    Button::new(Text::new(label)).on_click(move |state: &mut State| set_mode(state, mode))
+}
+
+pub fn main() {
+    pierro::app::App::new(State {
+        curves: vec![
+            Curve::new(Color::from_hex(0xE05A5AFF), vec![
+                CurveKey::new(0.0, 0.0),
+                CurveKey::new(1.0, 2.0),
+                CurveKey::new(2.5, -1.0),
+                CurveKey::new(4.0, 0.5)
+            ]),
+            Curve::new(Color::from_hex(0x5AC85AFF), vec![
+                CurveKey::new(0.0, 1.0),
+                CurveKey::new(2.0, 1.0).with_handles(vec2(-0.5, 1.0), vec2(0.5, 1.0), TangentMode::Free),
+                CurveKey::new(4.0, -0.5)
+            ]),
+            Curve::new(Color::from_hex(0x5A8CE0FF), vec![
+                CurveKey::new(0.5, -1.5),
+                CurveKey::new(3.5, 1.5).with_handles(vec2(-1.0, 0.0), vec2(1.0, 0.0), TangentMode::Aligned)
+            ])
+        ],
+        selected: Vec::new()
+    }, |state| {
+        let values = state.curves.iter().map(|curve| format!("{:.2}", curve.evaluate(2.0))).collect::<Vec<_>>().join(", ");
+        Column::new(vec![
+            Row::new(vec![
+                Text::new(format!("{} keys selected", state.selected.len())),
+                mode_button("Aligned", TangentMode::Aligned),
+                mode_button("Free", TangentMode::Free),
+                mode_button("Auto", TangentMode::Auto),
+                Text::new(format!("Values at 2s: {}", values))
+            ]),
+            CurveEditor::new(state.curves.clone())
+                .on_set(|state: &mut State, id, key| state.curves[id.curve].keys[id.key] = key)
+                .on_select(|state: &mut State, selected| state.selected = selected)
+                .build()
+        ])
+    }).run();
+}
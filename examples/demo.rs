@@ -1,6 +1,6 @@
 
 use std::marker::PhantomData;
-use pierro::{color, painter::{Painter, RectBuilder}, vec2, widget::{button::Button, center::Center, column::Column, menu_bar::MenuBar, scroll_area::ScrollArea, slider::Slider, text::Text, LayoutContext, LayoutResult, Widget}, Color, Rect, Response, Vec2, WidgetNode, WidgetState};
+use pierro::{color, painter::{Painter, RectBuilder}, vec2, widget::{button::Button, center::Center, column::Column, menu::{MenuItem, SubMenu}, menu_bar::MenuBar, scroll_area::ScrollArea, slider::Slider, text::Text, LayoutContext, LayoutResult, Widget}, Color, Rect, Response, Vec2, WidgetNode, WidgetState};
 
 struct Companion<S> {
     color: Color,
@@ -30,7 +30,17 @@ pub fn main() {
     pierro::app::App::new(color(1.0, 0.5, 1.0, 1.0), |state| {
         let mut scroll_list = Vec::new();
         for i in 0..100 {
-            scroll_list.push(Text::new(format!("Hello World! {}", i + 1)));
+            scroll_list.push(Text::new(format!("Hello World! {}", i + 1)).context_menu(move |state: &Color| Column::new(vec![
+                Text::new(format!("Current color: {}", state.to_hex_string())),
+                MenuItem::new("Make it grey").on_click(move |state: &mut Color| {
+                    *state = color(i as f32 / 99.0, i as f32 / 99.0, i as f32 / 99.0, 1.0);
+                }),
+                SubMenu::new("Tint", Column::new(vec![
+                    MenuItem::new("Red").on_click(|state: &mut Color| state.r = 1.0),
+                    MenuItem::new("Green").on_click(|state: &mut Color| state.g = 1.0),
+                    MenuItem::new("Blue").on_click(|state: &mut Color| state.b = 1.0)
+                ]))
+            ])));
             scroll_list.push(WidgetNode::new(Companion {
                 color: color(i as f32 / 99.0, 0.0, 0.5, 1.0),
                 _marker: PhantomData,
@@ -43,7 +53,7 @@ pub fn main() {
                     Text::new("Make it red!")
                 ).on_click(|state: &mut Color| {
                     *state = color(0.9, 0.2, 0.2, 1.0);
-                }),
+                }).tooltip_text("Sets the colour to red"),
                 Button::new(
                     Text::new("Make it green!")
                 ).on_click(|state: &mut Color| {
@@ -0,0 +1,38 @@
+use pierro::{widget::{button::Button, column::Column, dock::{DockArea, DockLayout, DockNode}, text::Text}, Axis};
+
+struct State {
+    layout: DockLayout<String>,
+    saved: Option<String>
+}
+
+fn default_layout() -> DockLayout<String> {
+    DockLayout::new(DockNode::split(Axis::X, vec![
+        DockNode::tabs(vec!["Layers".to_owned(), "Library".to_owned()]),
+        DockNode::split(Axis::Y, vec![
+            DockNode::tabs(vec!["Scene".to_owned()]),
+            DockNode::tabs(vec!["Timeline".to_owned()])
+        ])
+    ]))
+}
+
+pub fn main() {
+    pierro::app::App::new(State {
+        layout: default_layout(),
+        saved: None
+    }, |state| {
+        DockArea::new(&state.layout, |panel| {
+            match panel.as_str() {
+                "Layers" => Column::new(vec![
+                    Button::new(Text::new("Save layout")).on_click(|state: &mut State| state.saved = Some(state.layout.save())),
+                    Button::new(Text::new("Load layout")).on_click(|state: &mut State| {
+                        if let Some(layout) = state.saved.as_ref().and_then(|saved| DockLayout::load(saved)) {
+                            state.layout = layout;
+                        }
+                    }),
+                    Button::new(Text::new("Reset layout")).on_click(|state: &mut State| state.layout = default_layout())
+                ]),
+                _ => Text::new(format!("{} panel", panel))
+            }
+        }).on_change(|state: &mut State, layout| state.layout = layout).build()
+    }).run();
+}
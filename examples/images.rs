@@ -0,0 +1,84 @@
+use pierro::{painter::image::ImageHandle, vec2, widget::{column::Column, image::{Image, ImageFit}, row::Row, slider::Slider, text::Text}, Color};
+
+const PREVIEW_SIZE: usize = 96;
+
+struct State {
+    frame: u32,
+    preview: ImageHandle,
+    gradient: ImageHandle,
+    panel: ImageHandle
+}
+
+// Draw a frame of a spinning dot into an RGBA buffer
+fn render_frame(pixels: &mut [u8], frame: u32) {
+    let angle = frame as f32 / 60.0 * std::f32::consts::TAU;
+    let center = PREVIEW_SIZE as f32 / 2.0;
+    let dot = (center + angle.cos() * 30.0, center + angle.sin() * 30.0);
+    for (idx, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+        let x = (idx % PREVIEW_SIZE) as f32;
+        let y = (idx / PREVIEW_SIZE) as f32;
+        let dist = ((x - dot.0).powi(2) + (y - dot.1).powi(2)).sqrt();
+        let shade = if dist < 10.0 { 255 } else { 40 };
+        pixel.copy_from_slice(&[shade, shade / 2 + 60, 200, 255]);
+    }
+}
+
+fn gradient() -> ImageHandle {
+    let pixels = (0..64 * 32).flat_map(|idx| {
+        let x = (idx % 64) as u8 * 4;
+        let y = (idx / 64) as u8 * 8;
+        [x, y, 255 - x, 255]
+    }).collect();
+    ImageHandle::from_rgba(64, 32, pixels).unwrap()
+}
+
+// A rounded frame with 8 pixel borders, for nine-slice scaling
+fn panel() -> ImageHandle {
+    let pixels = (0..24 * 24).flat_map(|idx| {
+        let x = (idx % 24) as f32 - 11.5;
+        let y = (idx / 24) as f32 - 11.5;
+        let corner = ((x.abs() - 4.0).max(0.0).powi(2) + (y.abs() - 4.0).max(0.0).powi(2)).sqrt();
+        if corner > 8.0 {
+            [0, 0, 0, 0]
+        } else if corner > 6.0 || x.abs().max(y.abs()) > 9.5 {
+            [230, 230, 230, 255]
+        } else {
+            [70, 70, 80, 255]
+        }
+    }).collect();
+    ImageHandle::from_rgba(24, 24, pixels).unwrap()
+}
+
+pub fn main() {
+    let preview = ImageHandle::from_rgba(PREVIEW_SIZE, PREVIEW_SIZE, vec![0; PREVIEW_SIZE * PREVIEW_SIZE * 4]).unwrap();
+    preview.update_pixels(|pixels| render_frame(pixels, 0));
+
+    pierro::app::App::new(State {
+        frame: 0,
+        preview,
+        gradient: gradient(),
+        panel: panel()
+    }, |state| {
+        let fits = [ImageFit::Contain, ImageFit::Cover, ImageFit::Stretch, ImageFit::None].into_iter().map(|fit| {
+            Image::new(state.gradient.clone()).fit(fit).size(vec2(80.0, 80.0)).build()
+        }).collect();
+        Column::new(vec![
+            Text::new("Rendered frame"),
+            Image::new(state.preview.clone()).build(),
+            Slider::new(state.frame, 0..=59)
+                .show_value(true)
+                .on_set(|state: &mut State, frame| {
+                    state.frame = frame;
+                    state.preview.update_pixels(|pixels| render_frame(pixels, frame));
+                })
+                .build(),
+            Text::new("Contain, cover, stretch and none"),
+            Row::new(fits),
+            Text::new("Nine-slice"),
+            Row::new(vec![
+                Image::new(state.panel.clone()).nine_slice(8.0, 8.0, 8.0, 8.0).size(vec2(200.0, 60.0)).build(),
+                Image::new(state.panel.clone()).nine_slice(8.0, 8.0, 8.0, 8.0).tint(Color::from_hex(0xFFB070FF)).size(vec2(60.0, 120.0)).build()
+            ])
+        ])
+    }).run();
+}
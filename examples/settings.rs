@@ -0,0 +1,82 @@
+use pierro::{widget::{color_picker::ColorButton, column::Column, combo_box::ComboBox, drag_value::DragValue, slider::Slider, text::Text, toggle::{Checkbox, RadioGroup, Toggle}}, Color};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Quality {
+    Low,
+    Medium,
+    High
+}
+
+struct State {
+    autosave: bool,
+    dark_mode: bool,
+    quality: Quality,
+    language: usize,
+    stroke_width: f32,
+    frame: u32,
+    zoom: f64,
+    accent: Color
+}
+
+const LANGUAGES: [&str; 12] = [
+    "Dutch", "English", "Finnish", "French", "German", "Greek",
+    "Italian", "Japanese", "Polish", "Portuguese", "Spanish", "Swedish"
+];
+
+pub fn main() {
+    pierro::app::App::new(State {
+        autosave: true,
+        dark_mode: false,
+        quality: Quality::Medium,
+        language: 1,
+        stroke_width: 2.0,
+        frame: 0,
+        zoom: 1.0,
+        accent: Color::from_hex(0x3D8FD9FF)
+    }, |state| {
+        Column::new(vec![
+            Checkbox::new(state.autosave, "Autosave")
+                .on_toggle(|state: &mut State, autosave| state.autosave = autosave)
+                .build(),
+            Toggle::new(state.dark_mode, "Dark mode")
+                .on_toggle(|state: &mut State, dark_mode| state.dark_mode = dark_mode)
+                .build(),
+            Text::new("Render quality"),
+            RadioGroup::new(state.quality)
+                .option(Quality::Low, "Low")
+                .option(Quality::Medium, "Medium")
+                .option(Quality::High, "High")
+                .on_select(|state: &mut State, quality| state.quality = quality)
+                .build(),
+            Text::new("Language"),
+            LANGUAGES.iter().fold(ComboBox::new(), |combo_box, language| combo_box.option(*language))
+                .selected(state.language)
+                .on_select(|state: &mut State, language| state.language = language)
+                .build(),
+            Text::new("Stroke width"),
+            DragValue::new(state.stroke_width)
+                .range(0.5..=50.0)
+                .speed(0.1)
+                .decimals(1)
+                .suffix(" px")
+                .on_set(|state: &mut State, stroke_width| state.stroke_width = stroke_width)
+                .build(),
+            Text::new("Frame"),
+            Slider::new(state.frame, 0..=240)
+                .step(1)
+                .show_value(true)
+                .on_set(|state: &mut State, frame| state.frame = frame)
+                .build(),
+            Text::new("Zoom"),
+            Slider::new(state.zoom, 0.1..=10.0)
+                .logarithmic(true)
+                .show_value(true)
+                .on_set(|state: &mut State, zoom| state.zoom = zoom)
+                .build(),
+            Text::new("Accent colour"),
+            ColorButton::new(state.accent)
+                .on_set(|state: &mut State, accent| state.accent = accent)
+                .build()
+        ])
+    }).run();
+}
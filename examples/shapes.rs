@@ -0,0 +1,74 @@
+use std::{f32::consts::PI, marker::PhantomData};
+
+use pierro::{painter::{fill::Fill, path::PathBuilder, Painter, RectBuilder, Shadow}, pos, vec2, widget::{LayoutContext, LayoutResult, Widget}, Color, Rect, Response, Vec2, WidgetNode, WidgetState};
+
+struct Shapes<S> {
+    _marker: PhantomData<S>
+}
+
+impl<S> Widget<S> for Shapes<S> {
+
+    type State = ();
+
+    fn layout(&self, max_size: Vec2, _ctx: &mut LayoutContext, _state: &mut WidgetState<S>) -> LayoutResult<S> {
+        LayoutResult::new(vec2(640.0, 420.0).min(max_size))
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, _resp: &Response, _state: &mut WidgetState<S>) {
+        let origin = rect.min();
+        let accent = Color::from_hex(0x3D8FD9FF);
+        let warm = Color::from_hex(0xE8804AFF);
+
+        // A card with a shadow, a gradient fill and different corner radii
+        let card = Rect::min_size(origin + vec2(20.0, 20.0), vec2(180.0, 120.0));
+        painter.rect(RectBuilder::new(card)
+            .fill(Fill::linear_gradient(card.top_left(), card.bottom_right(), accent, warm))
+            .stroke(Color::WHITE)
+            .stroke_width(2.0)
+            .corner_rounding(20.0, 4.0, 20.0, 4.0)
+            .shadow(Shadow {
+                offset: vec2(4.0, 6.0),
+                blur: 12.0,
+                spread: 0.0,
+                color: Color::BLACK.with_alpha(0.6)
+            }));
+
+        // Circles, ellipses and a radial gradient
+        let center = origin + vec2(300.0, 80.0);
+        painter.path(PathBuilder::circle(center, 50.0).fill(Fill::radial_gradient(center, 10.0, 50.0, Color::WHITE, accent)));
+        painter.path(PathBuilder::ellipse(origin + vec2(460.0, 80.0), vec2(80.0, 40.0)).stroke(warm).stroke_width(4.0));
+
+        // A zigzag polyline, and the same one dashed
+        let zigzag: Vec<_> = (0..9).map(|idx| origin + vec2(20.0 + idx as f32 * 25.0, if idx % 2 == 0 { 200.0 } else { 170.0 })).collect();
+        painter.path(PathBuilder::polyline(&zigzag).stroke(painter.theme.text).stroke_width(3.0));
+        let dashed: Vec<_> = zigzag.iter().map(|point| *point + vec2(0.0, 50.0)).collect();
+        painter.path(PathBuilder::polyline(&dashed).stroke(painter.theme.text).stroke_width(2.0).dashed(8.0, 5.0));
+
+        // Curves and arcs
+        painter.path(PathBuilder::new()
+            .move_to(origin + vec2(280.0, 250.0))
+            .cubic_to(origin + vec2(330.0, 150.0), origin + vec2(400.0, 350.0), origin + vec2(450.0, 250.0))
+            .quad_to(origin + vec2(500.0, 180.0), origin + vec2(600.0, 250.0))
+            .stroke(accent)
+            .stroke_width(3.0));
+        painter.path(PathBuilder::new()
+            .move_to(origin + vec2(100.0, 380.0))
+            .arc(origin + vec2(100.0, 380.0), 60.0, -PI, -PI / 3.0)
+            .close()
+            .fill(warm.with_alpha(0.5))
+            .stroke(warm));
+        painter.path(PathBuilder::circle(origin + vec2(300.0, 360.0), 40.0).stroke(accent).stroke_width(2.0).dashed(6.0, 6.0));
+        painter.path(PathBuilder::polygon(&[pos(0.0, -40.0), pos(38.0, -12.0), pos(24.0, 32.0), pos(-24.0, 32.0), pos(-38.0, -12.0)].map(|point| point + (origin + vec2(450.0, 360.0)).to_vec()))
+            .fill(accent)
+            .stroke(Color::WHITE));
+    }
+
+}
+
+pub fn main() {
+    pierro::app::App::new((), |_| {
+        WidgetNode::new(Shapes {
+            _marker: PhantomData
+        })
+    }).run();
+}
@@ -0,0 +1,43 @@
+use pierro::widget::{button::Button, column::Column, linear::Align, row::Row, tabs::Tabs, text::Text};
+
+struct State {
+    documents: Vec<String>,
+    selected: usize,
+    next_document: usize
+}
+
+pub fn main() {
+    pierro::app::App::new(State {
+        documents: vec!["Untitled 1".to_owned(), "Untitled 2".to_owned()],
+        selected: 0,
+        next_document: 3
+    }, |state| {
+        let mut tabs = Tabs::new()
+            .selected(state.selected)
+            .on_select(|state: &mut State, idx| state.selected = idx)
+            .on_close(|state: &mut State, idx| {
+                state.documents.remove(idx);
+                state.selected = state.selected.min(state.documents.len().saturating_sub(1));
+            })
+            .on_reorder(|state: &mut State, from, to| {
+                let document = state.documents.remove(from);
+                state.documents.insert(to, document);
+                state.selected = to;
+            });
+        for document in &state.documents {
+            tabs = tabs.tab(document.clone(), Text::new(format!("Contents of {}", document)));
+        }
+
+        Column::new(vec![
+            Row::builder(vec![
+                Text::new(format!("{} open", state.documents.len())),
+                Button::new(Text::new("New document")).on_click(|state: &mut State| {
+                    state.documents.push(format!("Untitled {}", state.next_document));
+                    state.next_document += 1;
+                    state.selected = state.documents.len() - 1;
+                })
+            ]).align(Align::Baseline).build(),
+            tabs.build()
+        ])
+    }).run();
+}
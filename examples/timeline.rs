@@ -0,0 +1,84 @@
+use std::ops::Range;
+
+use pierro::widget::{column::Column, text::Text, timeline::{Timeline, TimelineData, TimelineKey}};
+
+struct Layer {
+    name: String,
+    keyframes: Vec<i32>
+}
+
+struct Animation {
+    length: i32,
+    layers: Vec<Layer>
+}
+
+impl TimelineData for Animation {
+
+    fn frame_count(&self) -> i32 {
+        self.length
+    }
+
+    fn track_count(&self) -> usize {
+        self.layers.len()
+    }
+
+    fn track_name(&self, track: usize) -> String {
+        self.layers[track].name.clone()
+    }
+
+    fn keyframes(&self, track: usize) -> Vec<i32> {
+        self.layers[track].keyframes.clone()
+    }
+
+    // Each drawing is held until the next keyframe
+    fn spans(&self, track: usize) -> Vec<Range<i32>> {
+        let keyframes = &self.layers[track].keyframes;
+        keyframes.iter().enumerate().map(|(idx, frame)| *frame..keyframes.get(idx + 1).copied().unwrap_or(self.length)).collect()
+    }
+
+}
+
+struct State {
+    animation: Animation,
+    frame: i32,
+    selected: Vec<TimelineKey>
+}
+
+fn move_keyframes(animation: &mut Animation, moved: Vec<TimelineKey>, delta: i32) {
+    for key in &moved {
+        animation.layers[key.track].keyframes.retain(|frame| *frame != key.frame);
+    }
+    for key in moved {
+        let keyframes = &mut animation.layers[key.track].keyframes;
+        let frame = key.frame + delta;
+        if !keyframes.contains(&frame) {
+            keyframes.push(frame);
+        }
+        keyframes.sort();
+    }
+}
+
+pub fn main() {
+    let layers = (0..12).map(|idx| Layer {
+        name: format!("Layer {}", idx + 1),
+        keyframes: (0..8).map(|key| key * 12 + idx * 2).collect()
+    }).collect();
+
+    pierro::app::App::new(State {
+        animation: Animation {
+            length: 120,
+            layers
+        },
+        frame: 0,
+        selected: Vec::new()
+    }, |state| {
+        Column::new(vec![
+            Text::new(format!("Frame {}, {} keyframes selected", state.frame, state.selected.len())),
+            Timeline::new(&state.animation, state.frame)
+                .on_scrub(|state: &mut State, frame| state.frame = frame)
+                .on_select(|state: &mut State, selected| state.selected = selected)
+                .on_move(|state: &mut State, moved, delta| move_keyframes(&mut state.animation, moved, delta))
+                .build()
+        ])
+    }).run();
+}
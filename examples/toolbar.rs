@@ -0,0 +1,54 @@
+use pierro::{painter::icon::IconSet, widget::{button::Button, column::Column, icon::Icon, menu::MenuItem, menu_bar::MenuBar, row::Row, text::Text}, Color};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Tool {
+    Brush,
+    Eraser
+}
+
+struct State {
+    icons: IconSet,
+    tool: Tool,
+    playing: bool,
+    onion_skin: bool
+}
+
+pub fn main() {
+    pierro::app::App::new(State {
+        icons: IconSet::builtin(),
+        tool: Tool::Brush,
+        playing: false,
+        onion_skin: false
+    }, |state| {
+        let icon = |name| state.icons.get(name).expect("missing icon");
+        let tool_name = match state.tool {
+            Tool::Brush => "brush",
+            Tool::Eraser => "eraser"
+        };
+
+        let tools_menu = Column::new(vec![
+            MenuItem::with_icon(icon("brush"), "Brush").on_click(|state: &mut State| state.tool = Tool::Brush),
+            MenuItem::with_icon(icon("eraser"), "Eraser").on_click(|state: &mut State| state.tool = Tool::Eraser)
+        ]);
+
+        MenuBar::new().item("Tools", tools_menu).main_content(Column::new(vec![
+            Row::new(vec![
+                Button::icon(icon("brush"))
+                    .on_click(|state: &mut State| state.tool = Tool::Brush)
+                    .tooltip_text("Brush"),
+                Button::icon(icon("eraser"))
+                    .on_click(|state: &mut State| state.tool = Tool::Eraser)
+                    .tooltip_text("Eraser"),
+                Button::icon(icon(if state.playing { "pause" } else { "play" }))
+                    .on_click(|state: &mut State| state.playing = !state.playing)
+                    .tooltip_text(if state.playing { "Pause" } else { "Play" }),
+                Button::with_icon(icon("onion_skin"), if state.onion_skin { "Onion skin on" } else { "Onion skin off" })
+                    .on_click(|state: &mut State| state.onion_skin = !state.onion_skin)
+            ]),
+            Row::new(vec![
+                Icon::new(icon(tool_name)).size(32.0).color(Color::from_hex(0x3D8FD9FF)).build(),
+                Text::new(format!("Drawing with the {}", tool_name))
+            ])
+        ])).build()
+    }).run();
+}
@@ -0,0 +1,92 @@
+use pierro::widget::{row::Row, text::Text, tree_view::{TreeDrop, TreeItem, TreeView}};
+
+struct Layer {
+    id: usize,
+    name: String,
+    children: Vec<Layer>
+}
+
+struct State {
+    layers: Vec<Layer>,
+    selected: Option<usize>
+}
+
+fn layer(id: usize, name: &str, children: Vec<Layer>) -> Layer {
+    Layer {
+        id,
+        name: name.to_owned(),
+        children
+    }
+}
+
+fn tree_items(layers: &[Layer]) -> Vec<TreeItem<usize>> {
+    layers.iter().map(|layer| TreeItem::new(layer.id, layer.name.clone()).with_children(tree_items(&layer.children))).collect()
+}
+
+fn take_layer(layers: &mut Vec<Layer>, id: usize) -> Option<Layer> {
+    if let Some(idx) = layers.iter().position(|layer| layer.id == id) {
+        return Some(layers.remove(idx));
+    }
+    layers.iter_mut().find_map(|layer| take_layer(&mut layer.children, id))
+}
+
+// Returns the layer back if the target was not found
+fn insert_layer(layers: &mut Vec<Layer>, moved: Layer, target: &TreeDrop<usize>) -> Option<Layer> {
+    let target_id = match target {
+        TreeDrop::Before(id) | TreeDrop::After(id) | TreeDrop::Into(id) => *id
+    };
+    if let Some(idx) = layers.iter().position(|layer| layer.id == target_id) {
+        match target {
+            TreeDrop::Before(_) => layers.insert(idx, moved),
+            TreeDrop::After(_) => layers.insert(idx + 1, moved),
+            TreeDrop::Into(_) => layers[idx].children.push(moved)
+        }
+        return None;
+    }
+    let mut moved = moved;
+    for layer in layers.iter_mut() {
+        match insert_layer(&mut layer.children, moved, target) {
+            Some(returned) => moved = returned,
+            None => return None
+        }
+    }
+    Some(moved)
+}
+
+pub fn main() {
+    let mut next_id = 0;
+    let mut id = || {
+        next_id += 1;
+        next_id
+    };
+    let layers = vec![
+        layer(id(), "Background", vec![]),
+        layer(id(), "Characters", (0..2000).map(|i| layer(id(), &format!("Character {}", i + 1), vec![
+            layer(id(), "Line art", vec![]),
+            layer(id(), "Colors", vec![])
+        ])).collect()),
+        layer(id(), "Effects", vec![])
+    ];
+
+    pierro::app::App::new(State {
+        layers,
+        selected: None
+    }, |state| {
+        TreeView::new(tree_items(&state.layers))
+            .row_builder(|item| if item.children.is_empty() {
+                Text::new(item.label.clone())
+            } else {
+                Row::new(vec![Text::new(item.label.clone()), Text::new(format!("({})", item.children.len()))])
+            })
+            .selected(state.selected)
+            .on_select(|state: &mut State, id| state.selected = Some(id))
+            .on_move(|state: &mut State, id, target| {
+                if let Some(moved) = take_layer(&mut state.layers, id) {
+                    if let Some(moved) = insert_layer(&mut state.layers, moved, &target) {
+                        state.layers.push(moved);
+                    }
+                }
+            })
+            .build()
+    }).run();
+}
@@ -0,0 +1,48 @@
+use pierro::{widget::{button::Button, column::Column, dialog::Dialog, floating_window::{FloatingWindow, FloatingWindows}, modal::Modal, text::Text}, vec2};
+
+struct State {
+    show_tools: bool,
+    confirm_reset: bool,
+    counter: i32
+}
+
+pub fn main() {
+    pierro::app::App::new(State {
+        show_tools: true,
+        confirm_reset: false,
+        counter: 0
+    }, |state| {
+        let contents = Column::new(vec![
+            Text::new(format!("Counter: {}", state.counter)),
+            Button::new(Text::new("Show tools")).on_click(|state: &mut State| state.show_tools = true),
+            Button::new(Text::new("Reset...")).on_click(|state: &mut State| state.confirm_reset = true)
+        ]);
+
+        let mut windows = vec![
+            FloatingWindow::new("Counter", Column::new(vec![
+                Button::new(Text::new("Increment")).on_click(|state: &mut State| state.counter += 1)
+            ])).key(0).default_pos(vec2(50.0, 100.0))
+        ];
+        if state.show_tools {
+            windows.push(FloatingWindow::new("Tools", Text::new("Brush, eraser, fill"))
+                .key(1)
+                .default_size(vec2(200.0, 150.0))
+                .on_close(|state: &mut State| state.show_tools = false));
+        }
+
+        let dialog = if state.confirm_reset {
+            Some(Dialog::new("Reset counter?", Text::new("The counter will go back to zero."))
+                .button("Cancel", |state: &mut State| state.confirm_reset = false)
+                .button("Reset", |state: &mut State| {
+                    state.counter = 0;
+                    state.confirm_reset = false;
+                })
+                .on_close(|state: &mut State| state.confirm_reset = false)
+                .build())
+        } else {
+            None
+        };
+
+        Modal::new(FloatingWindows::new(contents, windows), dialog)
+    }).run();
+}
@@ -0,0 +1,95 @@
+use std::marker::PhantomData;
+
+use pierro::{painter::{path::PathBuilder, Painter, RectBuilder}, pos, vec2, widget::{LayoutContext, LayoutResult, Widget}, Color, Pos, Rect, Response, Transform, Vec2, WidgetNode, WidgetState};
+
+const ZOOM_SPEED: f32 = 0.002;
+
+#[derive(Default)]
+struct ViewState {
+    // Maps the content to the widget's top left corner
+    view: Option<Transform>,
+    drag_start: Option<Pos>,
+    selected: Option<usize>
+}
+
+// Content that pans when dragged and zooms towards the pointer when scrolled
+struct ZoomView<S> {
+    _marker: PhantomData<S>
+}
+
+const CIRCLES: [(Pos, f32); 4] = [
+    (pos(100.0, 100.0), 40.0),
+    (pos(260.0, 140.0), 60.0),
+    (pos(160.0, 300.0), 30.0),
+    (pos(400.0, 260.0), 80.0)
+];
+
+impl<S: 'static> Widget<S> for ZoomView<S> {
+
+    type State = ViewState;
+
+    fn layout(&self, max_size: Vec2, _ctx: &mut LayoutContext, _state: &mut WidgetState<S>) -> LayoutResult<S> {
+        LayoutResult::new(max_size.min(vec2(800.0, 600.0)))
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let state = Self::get(widget_state);
+        let view = *state.view.get_or_insert(Transform::IDENTITY);
+
+        if let Some(hover_pos) = resp.hover_pos() {
+            if resp.scroll().y != 0.0 {
+                let zoom = (1.0 + resp.scroll().y * ZOOM_SPEED).max(0.1);
+                // Keep the point under the pointer in place
+                state.view = Some(Transform::scale_around(hover_pos - rect.min().to_vec(), zoom) * view);
+                painter.request_redraw();
+            }
+        }
+        if resp.mouse_clicked() {
+            state.drag_start = resp.hover_pos();
+            widget_state.request_focus();
+        }
+        let state = Self::get(widget_state);
+        if let (Some(start), Some(hover_pos)) = (state.drag_start, resp.global_hover_pos()) {
+            state.view = Some(Transform::translate(hover_pos - start) * state.view.unwrap_or(view));
+            state.drag_start = Some(hover_pos);
+        }
+        if resp.global_mouse_released() {
+            state.drag_start = None;
+            widget_state.unfocus();
+        }
+        let state = Self::get(widget_state);
+
+        painter.push_clip_rect(rect);
+        painter.rect(RectBuilder::new(rect).fill(painter.theme.bg_dark));
+        painter.push_transform(Transform::translate(rect.min().to_vec()) * state.view.unwrap_or(view));
+
+        // Hit-test in the content's own space
+        if resp.mouse_clicked() {
+            let local = resp.hover_pos().map(|hover_pos| painter.to_local(hover_pos));
+            state.selected = local.and_then(|local| CIRCLES.iter().position(|(center, radius)| (local - *center).length() <= *radius));
+        }
+
+        for x in 0..=10 {
+            let x = x as f32 * 50.0;
+            painter.path(PathBuilder::line(pos(x, 0.0), pos(x, 500.0)).stroke(painter.theme.bg_light));
+            painter.path(PathBuilder::line(pos(0.0, x), pos(500.0, x)).stroke(painter.theme.bg_light));
+        }
+        for (idx, (center, radius)) in CIRCLES.iter().enumerate() {
+            let color = if state.selected == Some(idx) { Color::from_hex(0xE8804AFF) } else { Color::from_hex(0x3D8FD9FF) };
+            painter.path(PathBuilder::circle(*center, *radius).fill(color).stroke(Color::WHITE).stroke_width(2.0));
+            painter.text(&format!("{}", idx + 1), *center, Color::WHITE, painter.theme.font_size);
+        }
+
+        painter.pop_transform();
+        painter.pop_clip_rect();
+    }
+
+}
+
+pub fn main() {
+    pierro::app::App::new((), |_| {
+        WidgetNode::new(ZoomView {
+            _marker: PhantomData
+        }).sense_click(true)
+    }).run();
+}
@@ -1,8 +1,10 @@
 
+use std::time::Instant;
+
 use glutin::surface::GlSurface;
-use winit::{dpi::PhysicalPosition, event::{Event, WindowEvent}, event_loop::ControlFlow};
+use winit::{dpi::PhysicalPosition, event::{Event, KeyboardInput, WindowEvent}, event_loop::ControlFlow};
 
-use crate::{pos, vec2};
+use crate::{pos, vec2, widget::key::{Key, Modifiers}};
 
 use super::Runtime;
 
@@ -31,16 +33,34 @@ impl<S: 'static> Runtime<S> {
                 match button {
                     winit::event::MouseButton::Left => self.input.left_mouse_button.set(down),
                     winit::event::MouseButton::Right => self.input.right_mouse_button.set(down),
+                    winit::event::MouseButton::Middle => self.input.middle_mouse_button.set(down),
                     _ => {}
                 };
                 self.rerender_again = true;
             },
             WindowEvent::MouseWheel { delta, .. } => {
                 let line_scale = scl * 20.0;
-                self.input.scroll = match delta {
+                self.input.scroll += match delta {
                     winit::event::MouseScrollDelta::LineDelta(x, y) => vec2(x * line_scale, y * line_scale),
                     winit::event::MouseScrollDelta::PixelDelta(PhysicalPosition {x, y}) => vec2(x as f32 / scl, y as f32 / scl),
                 };
+                self.rerender_again = true;
+            },
+            WindowEvent::KeyboardInput { input: KeyboardInput { state, virtual_keycode: Some(key), .. }, .. } => {
+                if let Some(key) = Key::from_winit_key(key) {
+                    match state {
+                        winit::event::ElementState::Pressed => self.input.key_pressed(key),
+                        winit::event::ElementState::Released => self.input.key_released(key),
+                    }
+                    self.rerender_again = true;
+                }
+            },
+            WindowEvent::ReceivedCharacter(char) => {
+                self.input.type_char(char);
+                self.rerender_again = true;
+            },
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.input.modifiers = Modifiers::from_winit_modifiers(modifiers);
             },
             WindowEvent::CloseRequested => {
                 *control_flow = ControlFlow::Exit
@@ -64,6 +84,16 @@ impl<S: 'static> Runtime<S> {
         if self.rerender_again {
             self.window.request_redraw();
         }
+
+        // Wake up for frames widgets have scheduled, otherwise sleep until the next event
+        match self.redraw_time {
+            Some(redraw_time) if Instant::now() >= redraw_time => {
+                self.redraw_time = None;
+                self.window.request_redraw();
+            },
+            Some(redraw_time) => control_flow.set_wait_until(redraw_time),
+            None => control_flow.set_wait()
+        }
     }
 
 }
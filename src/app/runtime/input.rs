@@ -1,12 +1,26 @@
 
-use crate::{widget::response::EdgedInput, LayoutNode, Pos, Rect, Response, Vec2, WidgetState};
+use crate::{widget::{key::{Key, Modifiers}, response::EdgedInput}, LayoutNode, Pos, Rect, Response, Vec2, WidgetState};
+
+// Seconds between two presses for them to count as a double click
+const DOUBLE_CLICK_TIME: f32 = 0.4;
 
 // Contains all the raw input to the app 
 pub(super) struct Input {
     pub mouse_pos: Option<Pos>,
     pub left_mouse_button: EdgedInput,
     pub right_mouse_button: EdgedInput,
-    pub scroll: Vec2
+    pub middle_mouse_button: EdgedInput,
+    // When the left mouse button was last pressed, unless that press completed a double click
+    pub last_click_time: Option<f32>,
+    pub scroll: Vec2,
+    // Keys pressed since the last frame, including key repeats
+    pub keys_pressed: Vec<Key>,
+    pub keys_down: Vec<Key>,
+    // Text typed since the last frame
+    pub text: String,
+    pub modifiers: Modifiers,
+    // Seconds since the app started, sampled at the start of each frame
+    pub time: f32
 }
 
 impl Input {
@@ -16,37 +30,98 @@ impl Input {
             mouse_pos: None,
             left_mouse_button: EdgedInput::new(),
             right_mouse_button: EdgedInput::new(),
-            scroll: Vec2::ZERO
+            middle_mouse_button: EdgedInput::new(),
+            last_click_time: None,
+            scroll: Vec2::ZERO,
+            keys_pressed: Vec::new(),
+            keys_down: Vec::new(),
+            text: String::new(),
+            modifiers: Modifiers::default(),
+            time: 0.0
+        }
+    }
+
+    pub(super) fn key_pressed(&mut self, key: Key) {
+        self.keys_pressed.push(key);
+        if !self.keys_down.contains(&key) {
+            self.keys_down.push(key);
+        }
+    }
+
+    pub(super) fn key_released(&mut self, key: Key) {
+        self.keys_down.retain(|down| *down != key);
+    }
+
+    pub(super) fn type_char(&mut self, char: char) {
+        if !char.is_control() {
+            self.text.push(char);
         }
     }
 
+    fn double_clicked(&self) -> bool {
+        self.left_mouse_button.pressed() && self.last_click_time.is_some_and(|time| self.time - time <= DOUBLE_CLICK_TIME)
+    }
+
     fn make_widget_input(&self) -> WidgetInput {
         WidgetInput {
             hover_pos: self.mouse_pos,
             left_mouse_button: self.left_mouse_button,
             right_mouse_button: self.right_mouse_button,
+            middle_mouse_button: self.middle_mouse_button,
+            double_clicked: self.double_clicked(),
             scroll: self.scroll,
             global_hover_pos: self.mouse_pos,
+            layer_hover_pos: self.mouse_pos,
             global_left_mouse_button: self.left_mouse_button,
-            global_right_mouse_button: self.right_mouse_button 
+            global_right_mouse_button: self.right_mouse_button,
+            global_middle_mouse_button: self.middle_mouse_button,
+            keys_pressed: self.keys_pressed.clone(),
+            keys_down: self.keys_down.clone(),
+            text: self.text.clone(),
+            modifiers: self.modifiers,
+            time: self.time
         }
     }
 
     pub(super) fn distribute_input<'a, S, I>(&self, layer_roots: I) where I: Iterator<Item = &'a LayoutNode<'a, S>> + 'a, S: 'static {
         let mut widget_input = self.make_widget_input(); 
         for root in layer_roots {
+            widget_input.layer_hover_pos = widget_input.hover_pos;
             widget_input.distribute_to_node(root);
+            if root.blocks_input {
+                widget_input.block();
+            }
         }
     }
 
-    pub(super) fn distribute_input_to_focused<S>(&self, root_node: &LayoutNode<S>, state: &mut WidgetState<S>) {
+    // Layers are given from the top down with the path to their state, so modals block the layers below like in `distribute_input`
+    pub(super) fn distribute_input_to_focused<'a, S, I>(&self, layer_roots: I, state: &mut WidgetState<S>) where I: Iterator<Item = (&'a [usize], &'a LayoutNode<'a, S>)> + 'a, S: 'static {
         let mut widget_input = self.make_widget_input(); 
-        widget_input.distribute_to_focused_node(root_node, state);
+        for (path, root) in layer_roots {
+            let mut layer_state = &mut *state;
+            for id in path {
+                layer_state = layer_state.get_child(*id);
+            }
+            // Nothing counts as hovered while a widget is being dragged
+            widget_input.layer_hover_pos = None;
+            widget_input.distribute_to_focused_node(root, layer_state);
+            if root.blocks_input {
+                widget_input.block();
+            }
+        }
     }
 
     pub(super) fn update(&mut self) {
+        if self.left_mouse_button.pressed() {
+            // A third press starts a new double click rather than completing another one
+            self.last_click_time = if self.double_clicked() { None } else { Some(self.time) };
+        }
         self.left_mouse_button.update();
         self.right_mouse_button.update();
+        self.middle_mouse_button.update();
+        self.scroll = Vec2::ZERO;
+        self.keys_pressed.clear();
+        self.text.clear();
     }
 
 }
@@ -62,19 +137,50 @@ struct WidgetInput {
     hover_pos: Option<Pos>,
     left_mouse_button: EdgedInput, 
     right_mouse_button: EdgedInput, 
+    middle_mouse_button: EdgedInput,
+    double_clicked: bool,
     scroll: Vec2,
 
     global_hover_pos: Option<Pos>,
+    // The pointer as it reached the current layer, before any of its widgets took it
+    layer_hover_pos: Option<Pos>,
     global_left_mouse_button: EdgedInput,
-    global_right_mouse_button: EdgedInput
+    global_right_mouse_button: EdgedInput,
+    global_middle_mouse_button: EdgedInput,
+    keys_pressed: Vec<Key>,
+    keys_down: Vec<Key>,
+    text: String,
+    modifiers: Modifiers,
+    time: f32
 }
 
 impl WidgetInput {
 
-    fn distribute_global_input(&self, response: &mut Response) {
+    // Withhold input from the layers below a modal
+    fn block(&mut self) {
+        self.hover_pos = None;
+        self.scroll = Vec2::ZERO;
+        self.keys_pressed.clear();
+        self.keys_down.clear();
+        self.text.clear();
+        // Clicks on the modal shouldn't count as clicks elsewhere or end drags below it
+        self.global_left_mouse_button = EdgedInput::new();
+        self.global_right_mouse_button = EdgedInput::new();
+        self.global_middle_mouse_button = EdgedInput::new();
+    }
+
+    fn distribute_global_input<S>(&self, response: &mut Response, node: &LayoutNode<S>) {
         response.global_hover_pos = self.global_hover_pos; 
+        response.layer_hover_pos = self.layer_hover_pos;
+        response.popover_hovered = self.global_hover_pos.is_some_and(|pos| node.popovers_contain(pos));
         response.global_left_mouse_button = self.global_left_mouse_button;
         response.global_right_mouse_button = self.global_right_mouse_button;
+        response.global_middle_mouse_button = self.global_middle_mouse_button;
+        response.keys_pressed.clone_from(&self.keys_pressed);
+        response.keys_down.clone_from(&self.keys_down);
+        response.text_input.clone_from(&self.text);
+        response.modifiers = self.modifiers;
+        response.time = self.time;
     }
 
     fn distribute_active_input(&mut self, response: &mut Response, hover_pos: Pos, sensor_idx: Option<usize>) {
@@ -82,6 +188,8 @@ impl WidgetInput {
         response.hover_pos = Some(hover_pos);
         response.left_mouse_button = self.left_mouse_button; 
         response.right_mouse_button = self.right_mouse_button; 
+        response.middle_mouse_button = self.middle_mouse_button;
+        response.double_clicked = self.double_clicked;
         response.scroll = self.scroll;
 
         self.hover_pos = None;
@@ -90,7 +198,7 @@ impl WidgetInput {
 
     fn distribute_to_node<S>(&mut self, node: &LayoutNode<S>) {
         let response = &mut *node.response.borrow_mut();
-        self.distribute_global_input(response);
+        self.distribute_global_input(response, node);
 
         for (_, popover) in &node.popovers {
             self.distribute_to_node(popover);
@@ -105,7 +213,8 @@ impl WidgetInput {
             }
         }
 
-        for (_, child) in &node.children {
+        // Children drawn last are on top, so they get the first chance to take the input
+        for (_, child) in node.children.iter().rev() {
             self.distribute_to_node(child);
         }
 
@@ -116,16 +225,14 @@ impl WidgetInput {
         }
     }
 
+    // Popovers and modals are layers of their own, so they're reached through the layer roots
     fn distribute_to_focused_node<S>(&mut self, node: &LayoutNode<S>, state: &mut WidgetState<S>) {
-        for (_, popover) in &node.popovers {
-            self.distribute_to_focused_node(popover, state.get_child(popover.local_id));
-        }
         for (_, child) in &node.children {
             self.distribute_to_focused_node(child, state.get_child(child.local_id));
         }
 
         let response = &mut *node.response.borrow_mut();
-        self.distribute_global_input(response);
+        self.distribute_global_input(response, node);
         if state.focused {
             if let Some(hover_pos) = self.hover_pos {
                 self.distribute_active_input(response, hover_pos, None);
@@ -1,5 +1,5 @@
 
-use std::num::NonZeroU32;
+use std::{num::NonZeroU32, time::Instant};
 
 use femtovg::{renderer::OpenGl, Canvas};
 use glutin::{config::ConfigTemplateBuilder, context::{ContextAttributesBuilder, NotCurrentGlContextSurfaceAccessor, PossiblyCurrentContext}, display::{GetGlDisplay, GlDisplay}, surface::{Surface, WindowSurface}};
@@ -7,7 +7,7 @@ use glutin_winit::DisplayBuilder;
 use raw_window_handle::HasRawWindowHandle;
 use winit::{event_loop::EventLoop, window::{Window, WindowBuilder}};
 
-use crate::{state::WidgetState, WidgetNode};
+use crate::{painter::image::TextureCache, state::WidgetState, WidgetNode};
 use self::input::Input;
 
 use super::App;
@@ -24,13 +24,17 @@ struct Runtime<S> {
     input: Input,
 
     text_font: femtovg::FontId,
+    textures: TextureCache,
 
     ui: Box<dyn Fn(&S) -> WidgetNode<S>>,
     state: S,
     widget_state: WidgetState<S>,
     any_widget_focused: bool,
 
-    rerender_again: bool
+    start_time: Instant,
+    rerender_again: bool,
+    // When a widget asked for the next frame to be drawn
+    redraw_time: Option<Instant>
 }
 
 impl<S: 'static> App<S> {
@@ -88,13 +92,16 @@ impl<S: 'static> App<S> {
             input: Input::new(),
 
             text_font, 
+            textures: TextureCache::new(),
 
             ui: self.ui,
             state: self.init_state,
             widget_state: WidgetState::new(),
             any_widget_focused: false,
 
-            rerender_again: false
+            start_time: Instant::now(),
+            rerender_again: false,
+            redraw_time: None
         };
 
         event_loop.run(move |event, _target, control_flow| {
@@ -1,9 +1,9 @@
 
-use std::collections::VecDeque;
+use std::{collections::VecDeque, time::{Duration, Instant}};
 
 use glutin::surface::GlSurface;
 
-use crate::{painter::{Painter, RectBuilder, TextShaper}, pos, state::WidgetState, theme::Theme, vec2, widget::{LayoutContext, Message}, LayoutNode, Pos, Rect, Vec2};
+use crate::{painter::{Painter, RectBuilder, TextShaper}, pos, state::WidgetState, theme::Theme, vec2, widget::{menu::update_context_menu, LayoutContext, Message}, LayoutNode, Pos, Rect, Vec2};
 
 use super::Runtime;
 
@@ -29,6 +29,10 @@ fn calculate_node_rects<S>(node: &mut LayoutNode<S>, rect: Rect, window_size: Ve
         }
         calculate_node_rects(popover, rect, window_size);
     }
+    for modal in &mut node.modals {
+        let rect = Rect::min_size(Pos::ZERO, modal.size());
+        calculate_node_rects(modal, rect, window_size);
+    }
 }
 
 fn find_all_popovers<'a, 'ui, S>(node: &'a LayoutNode<'ui, S>, bfs: &mut VecDeque<(Vec<usize>, &'a LayoutNode<'ui, S>)>, id_path: &mut Vec<usize>) {
@@ -44,11 +48,26 @@ fn find_all_popovers<'a, 'ui, S>(node: &'a LayoutNode<'ui, S>, bfs: &mut VecDequ
     }
 }
 
-fn get_layer_roots<'a, 'ui, S>(node: &'a LayoutNode<'ui, S>) -> Vec<(Vec<usize>, &'a LayoutNode<'ui, S>)> {
-    let mut layer_roots = Vec::new();
+fn find_all_modals<'a, 'ui, S>(node: &'a LayoutNode<'ui, S>, modals: &mut Vec<(Vec<usize>, &'a LayoutNode<'ui, S>)>, id_path: &mut Vec<usize>) {
+    for modal in &node.modals {
+        id_path.push(modal.local_id);
+        modals.push((id_path.clone(), modal));
+        id_path.pop();
+    }
+    for (_, child) in &node.children {
+        id_path.push(child.local_id);
+        find_all_modals(child, modals, id_path);
+        id_path.pop();
+    }
+}
+
+// Add the layer rooted at a node and all the popover layers above it.
+// Modals opened from these layers are stacked on top of them afterwards.
+fn add_layer_roots<'a, 'ui, S>(path: Vec<usize>, node: &'a LayoutNode<'ui, S>, layer_roots: &mut Vec<(Vec<usize>, &'a LayoutNode<'ui, S>)>) {
+    let first_layer = layer_roots.len();
 
     let mut bfs = VecDeque::new();
-    bfs.push_back((vec![node.local_id], node));
+    bfs.push_back((path, node));
     while let Some((path, node)) = bfs.pop_front() {
         layer_roots.push((path.clone(), node));
 
@@ -56,65 +75,131 @@ fn get_layer_roots<'a, 'ui, S>(node: &'a LayoutNode<'ui, S>) -> Vec<(Vec<usize>,
         find_all_popovers(node, &mut bfs, &mut sub_path);
     }
 
+    let mut modals = Vec::new();
+    for (path, node) in &layer_roots[first_layer..] {
+        find_all_modals(node, &mut modals, &mut path.clone());
+    }
+    for (path, modal) in modals {
+        add_layer_roots(path, modal, layer_roots);
+    }
+}
+
+fn get_layer_roots<'a, 'ui, S>(node: &'a LayoutNode<'ui, S>) -> Vec<(Vec<usize>, &'a LayoutNode<'ui, S>)> {
+    let mut layer_roots = Vec::new();
+    add_layer_roots(vec![node.local_id], node, &mut layer_roots);
     layer_roots
 }
 
-fn render_node<'ui, S>(painter: &mut Painter, node: &'ui LayoutNode<S>, messages: &mut Vec<Message<S>>, state: &mut WidgetState<S>) {
+// Returns true if a message was sent from the node's subtree
+fn render_node<'ui, S>(painter: &mut Painter, node: &'ui LayoutNode<S>, messages: &mut Vec<Message<S>>, state: &mut WidgetState<S>, time: f32, keyboard_focus_held: bool) -> bool {
+    if keyboard_focus_held && !state.keyboard_focused {
+        node.response.borrow_mut().clear_keys();
+    }
     let response = &*node.response.borrow();
     node.widget.widget.draw(painter, node.rect, response, state);
 
+    if node.widget.tooltip.is_some() {
+        let delay = node.widget.tooltip_delay.unwrap_or(painter.theme.tooltip_delay);
+        state.tooltip.update(painter, node.rect, response, time, delay);
+    }
+    if node.widget.context_menu.is_some() {
+        update_context_menu(painter, node.rect, response, state);
+    }
+
+    let mut sent = !state.messages.is_empty();
     if response.mouse_clicked() {
         if let Some(msg) = &node.widget.click_message {
             messages.push((*msg).clone());
+            sent = true;
         }
     }
     messages.append(&mut state.messages);
 
     for (_, child_node) in &node.children {
-        render_node(painter, child_node, messages, state.get_child(child_node.local_id));
+        sent |= render_node(painter, child_node, messages, state.get_child(child_node.local_id), time, keyboard_focus_held);
+    }
+    node.widget.widget.post_draw(painter, node.rect, response, state);
+    sent
+}
+
+// Close the context menu a layer belongs to when anything in it sent a message
+fn close_fired_context_menu<S>(mut state: &mut WidgetState<S>, layer_path: &[usize]) {
+    for id in layer_path {
+        if state.context_menu.as_ref().is_some_and(|menu| menu.menu_id == Some(*id)) {
+            state.close_context_menu();
+            return;
+        }
+        state = state.get_child(*id);
     }
 }
 
-fn clear_state_focus<S>(state: &mut WidgetState<S>) {
-    state.focused = false;
+// Whether a widget holds a kind of focus, and whether it asked for it this frame
+type FocusFlags<S> = fn(&mut WidgetState<S>) -> (&mut bool, &mut bool);
+
+// The focus a widget takes while the pointer drags it
+fn pointer_focus<S>(state: &mut WidgetState<S>) -> (&mut bool, &mut bool) {
+    (&mut state.focused, &mut state.requested_focus)
+}
+
+fn keyboard_focus<S>(state: &mut WidgetState<S>) -> (&mut bool, &mut bool) {
+    (&mut state.keyboard_focused, &mut state.requested_keyboard_focus)
+}
+
+fn clear_state_focus<S>(state: &mut WidgetState<S>, flags: FocusFlags<S>) {
+    *flags(state).0 = false;
     for (_, child) in state.child_state.iter_mut() {
-        clear_state_focus(child);
+        clear_state_focus(child, flags);
     }
 }
 
-fn update_focus<S>(state: &mut WidgetState<S>) -> bool {
-    let res = if state.requested_focus {
-        state.focused = true;
+fn update_focus<S>(state: &mut WidgetState<S>, flags: FocusFlags<S>) -> bool {
+    let res = if *flags(state).1 {
+        *flags(state).0 = true;
         for (_, child) in state.child_state.iter_mut() {
-            clear_state_focus(child);
+            clear_state_focus(child, flags);
         }
         true
     } else {
         let mut focus_id = None;
         for (id, child) in state.child_state.iter_mut() {
-            if update_focus(child) {
+            if update_focus(child, flags) {
                 focus_id = Some(*id);
             }
         }
         if let Some(focus_id) = focus_id {
             for (id, child) in state.child_state.iter_mut() {
                 if *id != focus_id {
-                    clear_state_focus(child);
+                    clear_state_focus(child, flags);
                 } 
             }
         }
         focus_id.is_some()  
     };
-    state.requested_focus = false;
+    *flags(state).1 = false;
     res
 }
 
-fn any_focused<S>(state: &WidgetState<S>) -> bool {
+// Finds the path to the focused widget's state
+fn find_focus<S>(state: &WidgetState<S>, path: &mut Vec<usize>) -> bool {
     if state.focused {
         return true;
     }
-    for (_, child_state) in state.child_state.iter() {
-        if any_focused(child_state) {
+    for (id, child_state) in state.child_state.iter() {
+        path.push(*id);
+        if find_focus(child_state, path) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+fn any_focused<S>(state: &mut WidgetState<S>, flags: FocusFlags<S>) -> bool {
+    if *flags(state).0 {
+        return true;
+    }
+    for (_, child_state) in state.child_state.iter_mut() {
+        if any_focused(child_state, flags) {
             return true;
         }
     }
@@ -133,6 +218,7 @@ impl<S: 'static> Runtime<S> {
         let logical_window_size = vec2(width as f32 / scl, height as f32 / scl);
 
         let theme = Theme::dark(); 
+        self.input.time = self.start_time.elapsed().as_secs_f32();
 
         let fonts = [self.text_font];
 
@@ -144,6 +230,7 @@ impl<S: 'static> Runtime<S> {
         let mut layout_context = LayoutContext {
             text_shaper: &mut text_shaper,
             theme: &theme,
+            app_state: &self.state,
             curr_auto_id: 0,
             window_size: logical_window_size
         };
@@ -155,31 +242,55 @@ impl<S: 'static> Runtime<S> {
         // Get root nodes of each "layer"
         let layer_roots = get_layer_roots(&root_node);
 
+        // A modal takes the input from the layers below it, so a widget there loses focus, ending its drag
+        if let Some(modal_idx) = layer_roots.iter().rposition(|(_path, node)| node.blocks_input) {
+            let mut focus_path = Vec::new();
+            if find_focus(&self.widget_state, &mut focus_path) && !layer_roots[modal_idx..].iter().any(|(path, _node)| focus_path.starts_with(path)) {
+                clear_state_focus(&mut self.widget_state, pointer_focus);
+                self.any_widget_focused = false;
+            }
+        }
+
         // Distribute input
         if self.any_widget_focused {
-            self.input.distribute_input_to_focused(&root_node, &mut self.widget_state);
+            self.input.distribute_input_to_focused(layer_roots.iter().rev().map(|(path, node)| (path.as_slice(), *node)), &mut self.widget_state);
         } else {
             self.input.distribute_input(layer_roots.iter().rev().map(|(_path, node)| *node));
         }
 
+        // A click anywhere takes keyboard focus away. The clicked widget takes it back while drawing if it wants it.
+        if self.input.left_mouse_button.pressed() || self.input.right_mouse_button.pressed() {
+            clear_state_focus(&mut self.widget_state, keyboard_focus);
+        }
+        let keyboard_focus_held = any_focused(&mut self.widget_state, keyboard_focus);
+
         // Render widget tree
-        let mut painter = Painter::new(&mut self.canvas, &theme, &fonts, scl);
+        let mut painter = Painter::new(&mut self.canvas, &theme, &fonts, &mut self.textures, scl);
         let fullscreen_rect = Rect::min_size(Pos::ZERO, vec2(width as f32 / scl, height as f32 / scl));
         painter.push_clip_rect(fullscreen_rect);
         painter.rect(RectBuilder::new(fullscreen_rect).fill(theme.bg_dark));
         let mut messages = Vec::new();
+        let mut fired_layers = Vec::new();
         for (path, root) in layer_roots {
             let mut state = &mut self.widget_state;
-            for id in path {
-                state = state.get_child(id);
+            for id in &path {
+                state = state.get_child(*id);
+            }
+            if render_node(&mut painter, root, &mut messages, state, self.input.time, keyboard_focus_held) {
+                fired_layers.push(path);
             }
-            render_node(&mut painter, &root, &mut messages, state);
         }
         self.window.set_cursor_icon(painter.cursor.to_winit_cursor());
+        self.redraw_time = painter.redraw_after.map(|delay| Instant::now() + Duration::from_secs_f32(delay));
+
+        for path in fired_layers {
+            close_fired_context_menu(&mut self.widget_state, &path);
+        }
 
         // Update focus
-        update_focus(&mut self.widget_state);
-        self.any_widget_focused = any_focused(&self.widget_state);
+        update_focus(&mut self.widget_state, pointer_focus);
+        self.any_widget_focused = any_focused(&mut self.widget_state, pointer_focus);
+        update_focus(&mut self.widget_state, keyboard_focus);
 
         // Apply messages
         for msg in messages {
@@ -187,6 +298,8 @@ impl<S: 'static> Runtime<S> {
         }
 
         self.canvas.flush();
+        // Only free textures once the frame that might use them is drawn
+        self.textures.free_unused(&mut self.canvas);
         self.surface.swap_buffers(&self.gl_ctx).expect("Could not swap buffers");
         self.input.update();
 
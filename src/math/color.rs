@@ -1,5 +1,9 @@
 
-#[derive(Clone, Copy)]
+use std::ops::{Add, Div, Mul, Sub};
+
+// A color with sRGB channels, as in hex codes and as it is drawn.
+// Lerping and blending work on these channels directly. Use the linear conversions for gamma-correct blending.
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Color {
     pub r: f32,
     pub g: f32,
@@ -16,10 +20,39 @@ pub const fn color(r: f32, g: f32, b: f32, a: f32) -> Color {
     }
 }
 
+pub fn srgb_to_linear(channel: f32) -> f32 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn linear_to_srgb(channel: f32) -> f32 {
+    if channel <= 0.0031308 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    }
+}
+
 impl Color {
 
     pub const BLACK: Color = color(0.0, 0.0, 0.0, 1.0);
+    pub const WHITE: Color = color(1.0, 1.0, 1.0, 1.0);
+    pub const TRANSPARENT: Color = color(0.0, 0.0, 0.0, 0.0);
+
+    // From linear channels, like the result of gamma-correct blending
+    pub fn from_linear(r: f32, g: f32, b: f32, a: f32) -> Self {
+        color(linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b), a)
+    }
+
+    // The red, green, blue and alpha channels decoded to linear light
+    pub fn to_linear(&self) -> (f32, f32, f32, f32) {
+        (srgb_to_linear(self.r), srgb_to_linear(self.g), srgb_to_linear(self.b), self.a)
+    }
 
+    // A color packed as 0xRRGGBBAA
     pub fn from_hex(hex: u32) -> Color {
         let bytes = hex.to_be_bytes();
         color(
@@ -30,6 +63,42 @@ impl Color {
         )
     }
 
+    // Parses "#rgb", "#rgba", "#rrggbb" or "#rrggbbaa". The '#' is optional.
+    pub fn from_hex_str(hex: &str) -> Option<Color> {
+        let digits = hex.trim().trim_start_matches('#');
+        if !digits.chars().all(|char| char.is_ascii_hexdigit()) {
+            return None;
+        }
+        let value = u32::from_str_radix(digits, 16).ok()?;
+        // Short forms repeat each digit
+        let expand = |value: u32, count: u32| (0..count).rev().fold(0, |packed, idx| {
+            let digit = (value >> (idx * 4)) & 0xF;
+            (packed << 8) | (digit << 4) | digit
+        });
+        Some(Self::from_hex(match digits.len() {
+            3 => (expand(value, 3) << 8) | 0xFF,
+            4 => expand(value, 4),
+            6 => (value << 8) | 0xFF,
+            8 => value,
+            _ => return None
+        }))
+    }
+
+    // Formats as "#rrggbb", or "#rrggbbaa" if the color is not opaque
+    pub fn to_hex_string(&self) -> String {
+        let Color { r, g, b, a } = *self;
+        let byte = |channel: f32| (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+        if byte(a) == 255 {
+            format!("#{:02x}{:02x}{:02x}", byte(r), byte(g), byte(b))
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", byte(r), byte(g), byte(b), byte(a))
+        }
+    }
+
+    pub fn with_alpha(&self, a: f32) -> Self {
+        Self { a, ..*self }
+    }
+
     pub fn lerp(&self, other: Color, t: f32) -> Self {
         Self {
             r: self.r * (1.0 - t) + other.r * t,
@@ -39,8 +108,148 @@ impl Color {
         }
     }
 
+    // Lerp in linear light, which keeps mixes of bright colors from looking too dark
+    pub fn lerp_linear(&self, other: Color, t: f32) -> Self {
+        let (r0, g0, b0, a0) = self.to_linear();
+        let (r1, g1, b1, a1) = other.to_linear();
+        let mix = |from: f32, to: f32| from * (1.0 - t) + to * t;
+        Self::from_linear(mix(r0, r1), mix(g0, g1), mix(b0, b1), mix(a0, a1))
+    }
+
+    // Mix towards black, keeping the alpha
     pub fn darken(&self, t: f32) -> Self {
-        self.lerp(Self::BLACK, t)
+        self.lerp(Self::BLACK.with_alpha(self.a), t)
+    }
+
+    // Mix towards white, keeping the alpha
+    pub fn lighten(&self, t: f32) -> Self {
+        self.lerp(Self::WHITE.with_alpha(self.a), t)
+    }
+
+    // Add to the HSL saturation. Negative amounts desaturate.
+    pub fn saturate(&self, amount: f32) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h, (s + amount).clamp(0.0, 1.0), l, self.a)
+    }
+
+    // Multiply the color channels by alpha, as blending with `a + b * (1 - a.a)` expects
+    pub fn premultiply(&self) -> Self {
+        color(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+
+    pub fn unpremultiply(&self) -> Self {
+        if self.a <= 0.0 {
+            return Self::TRANSPARENT;
+        }
+        color(self.r / self.a, self.g / self.a, self.b / self.a, self.a)
+    }
+
+    // Hue, saturation and value all range from 0 to 1
+    pub fn from_hsv(h: f32, s: f32, v: f32, a: f32) -> Self {
+        let h = h.rem_euclid(1.0) * 6.0;
+        let chroma = v * s;
+        let (r, g, b) = hue_to_rgb(h, chroma);
+        let m = v - chroma;
+        color(r + m, g + m, b + m, a)
     }
 
+    // Returns hue, saturation and value from 0 to 1. Grays have a hue of 0.
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let Color { r, g, b, .. } = *self;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+        let s = if max > 0.0 { chroma / max } else { 0.0 };
+        (hue(r, g, b, max, chroma), s, max)
+    }
+
+    // Hue, saturation and lightness all range from 0 to 1
+    pub fn from_hsl(h: f32, s: f32, l: f32, a: f32) -> Self {
+        let h = h.rem_euclid(1.0) * 6.0;
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let (r, g, b) = hue_to_rgb(h, chroma);
+        let m = l - chroma / 2.0;
+        color(r + m, g + m, b + m, a)
+    }
+
+    // Returns hue, saturation and lightness from 0 to 1. Grays have a hue of 0.
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let Color { r, g, b, .. } = *self;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+        let l = (max + min) / 2.0;
+        let s = if l > 0.0 && l < 1.0 { chroma / (1.0 - (2.0 * l - 1.0).abs()) } else { 0.0 };
+        (hue(r, g, b, max, chroma), s, l)
+    }
+
+}
+
+// The hue from 0 to 1 of channels with the given maximum and chroma
+fn hue(r: f32, g: f32, b: f32, max: f32, chroma: f32) -> f32 {
+    if chroma <= 0.0 {
+        return 0.0;
+    }
+    let sector = if max == r {
+        ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / chroma + 2.0
+    } else {
+        (r - g) / chroma + 4.0
+    };
+    sector / 6.0
+}
+
+// The red, green and blue parts of a color with the given chroma, for a hue from 0 to 6
+fn hue_to_rgb(h: f32, chroma: f32) -> (f32, f32, f32) {
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x)
+    }
+}
+
+impl Add for Color {
+    type Output = Color;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        color(self.r + rhs.r, self.g + rhs.g, self.b + rhs.b, self.a + rhs.a)
+    }
+}
+
+impl Sub for Color {
+    type Output = Color;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        color(self.r - rhs.r, self.g - rhs.g, self.b - rhs.b, self.a - rhs.a)
+    }
+}
+
+impl Mul<f32> for Color {
+    type Output = Color;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        color(self.r * rhs, self.g * rhs, self.b * rhs, self.a * rhs)
+    }
+}
+
+// Multiplies channel by channel, like tinting
+impl Mul for Color {
+    type Output = Color;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        color(self.r * rhs.r, self.g * rhs.g, self.b * rhs.b, self.a * rhs.a)
+    }
+}
+
+impl Div<f32> for Color {
+    type Output = Color;
+
+    fn div(self, rhs: f32) -> Self::Output {
+        color(self.r / rhs, self.g / rhs, self.b / rhs, self.a / rhs)
+    }
 }
@@ -1,4 +1,6 @@
 
 pub mod vec;
 pub mod rect;
+pub mod transform;
 pub mod color;
+pub mod num;
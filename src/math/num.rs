@@ -0,0 +1,57 @@
+
+// A primitive number that widgets like drag values and sliders can edit.
+// Values are converted through f64, which holds every value of the smaller types exactly.
+pub trait Numeric: Copy + PartialOrd + 'static {
+
+    // Whether the type only holds whole numbers
+    const INTEGRAL: bool;
+
+    fn to_f64(self) -> f64;
+
+    // Rounds for integer types and saturates at the type's limits
+    fn from_f64(value: f64) -> Self;
+
+}
+
+macro_rules! impl_numeric_int {
+    ($($ty: ty),*) => {
+        $(
+            impl Numeric for $ty {
+
+                const INTEGRAL: bool = true;
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(value: f64) -> Self {
+                    value.round() as $ty
+                }
+
+            }
+        )*
+    };
+}
+
+macro_rules! impl_numeric_float {
+    ($($ty: ty),*) => {
+        $(
+            impl Numeric for $ty {
+
+                const INTEGRAL: bool = false;
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(value: f64) -> Self {
+                    value as $ty
+                }
+
+            }
+        )*
+    };
+}
+
+impl_numeric_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+impl_numeric_float!(f32, f64);
@@ -85,6 +85,18 @@ impl Rect {
         pos.x >= self.min.x && pos.x <= self.max.x && pos.y >= self.min.y && pos.y <= self.max.y 
     }
 
+    // The overlap of two rects. Empty if they do not overlap.
+    pub fn intersect(&self, other: Rect) -> Self {
+        let min = pos(self.min.x.max(other.min.x), self.min.y.max(other.min.y));
+        let max = pos(self.max.x.min(other.max.x), self.max.y.min(other.max.y));
+        Self::new(min, pos(max.x.max(min.x), max.y.max(min.y)))
+    }
+
+    // Grow outwards on every side. Negative amounts shrink.
+    pub fn expand(&self, amount: f32) -> Self {
+        Self::new(self.min - Vec2::splat(amount), self.max + Vec2::splat(amount))
+    }
+
     pub fn shift(&self, offset: Vec2) -> Self {
         Self::new(self.min + offset, self.max + offset)
     }
@@ -0,0 +1,130 @@
+
+use std::ops::Mul;
+
+use super::{rect::Rect, vec::{pos, vec2, Pos, Vec2}};
+
+// An affine transform: a linear part followed by a translation.
+// Maps (x, y) to (a * x + c * y + e, b * x + d * y + f).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32
+}
+
+impl Transform {
+
+    pub const IDENTITY: Self = Self {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        e: 0.0,
+        f: 0.0
+    };
+
+    pub fn translate(offset: Vec2) -> Self {
+        Self {
+            e: offset.x,
+            f: offset.y,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn scale(scale: f32) -> Self {
+        Self::scale_xy(vec2(scale, scale))
+    }
+
+    pub fn scale_xy(scale: Vec2) -> Self {
+        Self {
+            a: scale.x,
+            d: scale.y,
+            ..Self::IDENTITY
+        }
+    }
+
+    // Rotate by an angle in radians. Positive angles turn clockwise, since y points down.
+    pub fn rotate(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            e: 0.0,
+            f: 0.0
+        }
+    }
+
+    // Scale around a point rather than the origin, like when zooming towards the pointer
+    pub fn scale_around(center: Pos, scale: f32) -> Self {
+        Self::translate(center.to_vec()) * Self::scale(scale) * Self::translate(vec2(-center.x, -center.y))
+    }
+
+    pub fn rotate_around(center: Pos, angle: f32) -> Self {
+        Self::translate(center.to_vec()) * Self::rotate(angle) * Self::translate(vec2(-center.x, -center.y))
+    }
+
+    pub fn apply(&self, point: Pos) -> Pos {
+        pos(self.a * point.x + self.c * point.y + self.e, self.b * point.x + self.d * point.y + self.f)
+    }
+
+    // Transform a direction or size, ignoring the translation
+    pub fn apply_vec(&self, v: Vec2) -> Vec2 {
+        vec2(self.a * v.x + self.c * v.y, self.b * v.x + self.d * v.y)
+    }
+
+    // The smallest axis-aligned rect containing the transformed corners of a rect
+    pub fn apply_rect(&self, rect: Rect) -> Rect {
+        let corners = [rect.top_left(), rect.top_right(), rect.bottom_left(), rect.bottom_right()].map(|corner| self.apply(corner));
+        let min = corners.iter().fold(pos(f32::INFINITY, f32::INFINITY), |min, corner| pos(min.x.min(corner.x), min.y.min(corner.y)));
+        let max = corners.iter().fold(pos(f32::NEG_INFINITY, f32::NEG_INFINITY), |max, corner| pos(max.x.max(corner.x), max.y.max(corner.y)));
+        Rect::new(min, max)
+    }
+
+    // How much lengths grow on average, which is how stroke widths are scaled
+    pub fn average_scale(&self) -> f32 {
+        (vec2(self.a, self.b).length() + vec2(self.c, self.d).length()) / 2.0
+    }
+
+    // The transform that undoes this one. None if it flattens everything onto a line or point.
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.a * self.d - self.b * self.c;
+        if det.abs() <= f32::EPSILON {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+        let a = self.d * inv_det;
+        let b = -self.b * inv_det;
+        let c = -self.c * inv_det;
+        let d = self.a * inv_det;
+        Some(Self {
+            a,
+            b,
+            c,
+            d,
+            e: -(a * self.e + c * self.f),
+            f: -(b * self.e + d * self.f)
+        })
+    }
+
+}
+
+// `a * b` applies `b` first, then `a`
+impl Mul for Transform {
+    type Output = Transform;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self {
+            a: self.a * rhs.a + self.c * rhs.b,
+            b: self.b * rhs.a + self.d * rhs.b,
+            c: self.a * rhs.c + self.c * rhs.d,
+            d: self.b * rhs.c + self.d * rhs.d,
+            e: self.a * rhs.e + self.c * rhs.f + self.e,
+            f: self.b * rhs.e + self.d * rhs.f + self.f
+        }
+    }
+}
@@ -24,7 +24,7 @@ impl Axis {
 
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Vec2 {
     pub x: f32,
     pub y: f32
@@ -134,7 +134,7 @@ impl Div<f32> for Vec2 {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Pos(Vec2);
 
 pub const fn pos(x: f32, y: f32) -> Pos {
@@ -4,7 +4,10 @@ use winit::window::CursorIcon;
 pub enum Cursor {
     Default,
     RowResize,
-    ColResize
+    ColResize,
+    NwseResize,
+    NeswResize,
+    Move
 }
 
 impl Cursor {
@@ -14,6 +17,9 @@ impl Cursor {
             Cursor::Default => CursorIcon::Default,
             Cursor::RowResize => CursorIcon::RowResize,
             Cursor::ColResize => CursorIcon::ColResize,
+            Cursor::NwseResize => CursorIcon::NwseResize,
+            Cursor::NeswResize => CursorIcon::NeswResize,
+            Cursor::Move => CursorIcon::Move,
         }
     }
 
@@ -0,0 +1,71 @@
+
+use femtovg::Paint;
+
+use crate::{Color, Pos};
+
+use super::to_color;
+
+// What the inside of a shape is painted with.
+// Gradient stops are offsets from 0 to 1 along the gradient, paired with the color there.
+#[derive(Clone)]
+pub enum Fill {
+    Solid(Color),
+    LinearGradient {
+        start: Pos,
+        end: Pos,
+        stops: Vec<(f32, Color)>
+    },
+    RadialGradient {
+        center: Pos,
+        inner_radius: f32,
+        outer_radius: f32,
+        stops: Vec<(f32, Color)>
+    }
+}
+
+impl Fill {
+
+    pub fn linear_gradient(start: Pos, end: Pos, start_color: Color, end_color: Color) -> Self {
+        Self::LinearGradient {
+            start,
+            end,
+            stops: vec![(0.0, start_color), (1.0, end_color)]
+        }
+    }
+
+    // Blends from the inner color inside `inner_radius` to the outer color beyond `outer_radius`
+    pub fn radial_gradient(center: Pos, inner_radius: f32, outer_radius: f32, inner_color: Color, outer_color: Color) -> Self {
+        Self::RadialGradient {
+            center,
+            inner_radius,
+            outer_radius,
+            stops: vec![(0.0, inner_color), (1.0, outer_color)]
+        }
+    }
+
+    pub(crate) fn to_paint(&self, scl: f32) -> Paint {
+        let stops = |stops: &[(f32, Color)]| stops.iter().map(|(offset, color)| (*offset, to_color(*color))).collect::<Vec<_>>();
+        match self {
+            Fill::Solid(color) => Paint::color(to_color(*color)),
+            Fill::LinearGradient { start, end, stops: gradient_stops } => Paint::linear_gradient_stops(
+                start.x * scl, start.y * scl,
+                end.x * scl, end.y * scl,
+                stops(gradient_stops)
+            ),
+            Fill::RadialGradient { center, inner_radius, outer_radius, stops: gradient_stops } => Paint::radial_gradient_stops(
+                center.x * scl, center.y * scl,
+                inner_radius * scl, outer_radius * scl,
+                stops(gradient_stops)
+            )
+        }
+    }
+
+}
+
+impl From<Color> for Fill {
+
+    fn from(color: Color) -> Self {
+        Self::Solid(color)
+    }
+
+}
@@ -0,0 +1,501 @@
+
+use std::{collections::HashMap, f32::consts::{FRAC_PI_2, PI, TAU}, rc::Rc};
+
+use femtovg::{FillRule, LineCap, LineJoin};
+
+use crate::{pos, vec2, Pos, Rect, Vec2};
+
+use super::path::{ellipse_arc, ellipse_segments, Segment};
+
+#[derive(Debug)]
+pub enum IconError {
+    Xml(roxmltree::Error),
+    // The SVG has neither a view box nor a width and height
+    MissingSize,
+    // Path data that could not be parsed, with the offending text
+    BadPathData(String)
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Stroke {
+    pub(crate) width: f32,
+    pub(crate) cap: LineCap,
+    pub(crate) join: LineJoin
+}
+
+pub(crate) struct IconPath {
+    pub(crate) segments: Vec<Segment>,
+    pub(crate) fill: Option<FillRule>,
+    pub(crate) stroke: Option<Stroke>
+}
+
+// A vector icon, drawn in a single color so it can be tinted to match the theme.
+// Being made of paths rather than pixels, it stays sharp at any size or scale factor.
+#[derive(Clone)]
+pub struct IconShape {
+    view_box: Rect,
+    paths: Rc<Vec<IconPath>>
+}
+
+impl IconShape {
+
+    // Load the shapes of an SVG document. Every fill and stroke is drawn in the icon's tint,
+    // whatever color the SVG gives it. Gradients, transforms and opacity are not supported.
+    pub fn from_svg(svg: &str) -> Result<Self, IconError> {
+        let doc = roxmltree::Document::parse(svg).map_err(IconError::Xml)?;
+        let root = doc.root_element();
+        let view_box = match root.attribute("viewBox").map(parse_numbers) {
+            Some(numbers) if numbers.len() == 4 => Rect::min_size(pos(numbers[0], numbers[1]), vec2(numbers[2], numbers[3])),
+            _ => {
+                let length = |name| root.attribute(name).and_then(|value: &str| value.trim_end_matches("px").parse::<f32>().ok());
+                match (length("width"), length("height")) {
+                    (Some(width), Some(height)) => Rect::min_size(Pos::ZERO, vec2(width, height)),
+                    _ => return Err(IconError::MissingSize)
+                }
+            }
+        };
+
+        let mut paths = Vec::new();
+        add_svg_shapes(root, SvgStyle::default().apply(root), &mut paths)?;
+        Ok(Self {
+            view_box,
+            paths: Rc::new(paths)
+        })
+    }
+
+    // A filled icon from SVG path data, like the `d` attribute of a `<path>`
+    pub fn from_path_data(size: Vec2, data: &str) -> Result<Self, IconError> {
+        Ok(Self {
+            view_box: Rect::min_size(Pos::ZERO, size),
+            paths: Rc::new(vec![IconPath {
+                segments: parse_path_data(data)?,
+                fill: Some(FillRule::NonZero),
+                stroke: None
+            }])
+        })
+    }
+
+    pub fn view_box(&self) -> Rect {
+        self.view_box
+    }
+
+    pub(crate) fn paths(&self) -> &[IconPath] {
+        &self.paths
+    }
+
+}
+
+// A collection of icons looked up by name
+#[derive(Clone, Default)]
+pub struct IconSet {
+    icons: HashMap<String, IconShape>
+}
+
+impl IconSet {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The icons that come with the library: "brush", "eraser", "play", "pause" and "onion_skin"
+    pub fn builtin() -> Self {
+        let mut icons = Self::new();
+        for (name, svg) in [
+            ("brush", include_str!("../../res/icons/brush.svg")),
+            ("eraser", include_str!("../../res/icons/eraser.svg")),
+            ("play", include_str!("../../res/icons/play.svg")),
+            ("pause", include_str!("../../res/icons/pause.svg")),
+            ("onion_skin", include_str!("../../res/icons/onion_skin.svg"))
+        ] {
+            icons.load_svg(name, svg).expect("builtin icon should be valid.");
+        }
+        icons
+    }
+
+    pub fn insert<T: Into<String>>(&mut self, name: T, icon: IconShape) {
+        self.icons.insert(name.into(), icon);
+    }
+
+    pub fn load_svg<T: Into<String>>(&mut self, name: T, svg: &str) -> Result<(), IconError> {
+        self.insert(name, IconShape::from_svg(svg)?);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<IconShape> {
+        self.icons.get(name).cloned()
+    }
+
+}
+
+// Presentation attributes, inherited from parent elements
+#[derive(Clone, Copy)]
+struct SvgStyle {
+    fill: bool,
+    fill_rule: FillRule,
+    stroke: bool,
+    stroke_width: f32,
+    cap: LineCap,
+    join: LineJoin
+}
+
+impl Default for SvgStyle {
+
+    fn default() -> Self {
+        Self {
+            fill: true,
+            fill_rule: FillRule::NonZero,
+            stroke: false,
+            stroke_width: 1.0,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter
+        }
+    }
+
+}
+
+impl SvgStyle {
+
+    fn apply(mut self, node: roxmltree::Node) -> Self {
+        // Properties in the style attribute take precedence over plain attributes
+        let style = node.attribute("style").unwrap_or("");
+        let property = |name: &str| style.split(';')
+            .filter_map(|declaration| declaration.split_once(':'))
+            .find(|(key, _)| key.trim() == name)
+            .map(|(_, value)| value.trim())
+            .or_else(|| node.attribute(name));
+
+        if let Some(fill) = property("fill") {
+            self.fill = fill != "none";
+        }
+        if let Some(fill_rule) = property("fill-rule") {
+            self.fill_rule = if fill_rule == "evenodd" { FillRule::EvenOdd } else { FillRule::NonZero };
+        }
+        if let Some(stroke) = property("stroke") {
+            self.stroke = stroke != "none";
+        }
+        if let Some(width) = property("stroke-width").and_then(|width| width.trim_end_matches("px").parse().ok()) {
+            self.stroke_width = width;
+        }
+        if let Some(cap) = property("stroke-linecap") {
+            self.cap = match cap {
+                "round" => LineCap::Round,
+                "square" => LineCap::Square,
+                _ => LineCap::Butt
+            };
+        }
+        if let Some(join) = property("stroke-linejoin") {
+            self.join = match join {
+                "round" => LineJoin::Round,
+                "bevel" => LineJoin::Bevel,
+                _ => LineJoin::Miter
+            };
+        }
+        self
+    }
+
+}
+
+fn add_svg_shapes(parent: roxmltree::Node, style: SvgStyle, paths: &mut Vec<IconPath>) -> Result<(), IconError> {
+    for node in parent.children().filter(|node| node.is_element()) {
+        let style = style.apply(node);
+        let number = |name| node.attribute(name).and_then(|value: &str| value.trim_end_matches("px").parse::<f32>().ok()).unwrap_or(0.0);
+
+        let segments = match node.tag_name().name() {
+            "g" => {
+                add_svg_shapes(node, style, paths)?;
+                continue;
+            },
+            "path" => parse_path_data(node.attribute("d").unwrap_or(""))?,
+            "rect" => {
+                // A missing corner radius on one axis matches the other
+                let rx = node.attribute("rx").map(|_| number("rx"));
+                let ry = node.attribute("ry").map(|_| number("ry"));
+                let radii = vec2(rx.or(ry).unwrap_or(0.0), ry.or(rx).unwrap_or(0.0));
+                rect_segments(Rect::min_size(pos(number("x"), number("y")), vec2(number("width"), number("height"))), radii)
+            },
+            "circle" => ellipse_segments(pos(number("cx"), number("cy")), Vec2::splat(number("r"))),
+            "ellipse" => ellipse_segments(pos(number("cx"), number("cy")), vec2(number("rx"), number("ry"))),
+            "line" => vec![Segment::MoveTo(pos(number("x1"), number("y1"))), Segment::LineTo(pos(number("x2"), number("y2")))],
+            tag @ ("polyline" | "polygon") => {
+                let points = parse_numbers(node.attribute("points").unwrap_or(""));
+                let mut segments: Vec<Segment> = points.chunks_exact(2).enumerate().map(|(idx, point)| {
+                    let point = pos(point[0], point[1]);
+                    if idx == 0 { Segment::MoveTo(point) } else { Segment::LineTo(point) }
+                }).collect();
+                if tag == "polygon" {
+                    segments.push(Segment::Close);
+                }
+                segments
+            },
+            // Titles, definitions and anything else that isn't drawn
+            _ => continue
+        };
+
+        paths.push(IconPath {
+            segments,
+            fill: style.fill.then_some(style.fill_rule),
+            stroke: style.stroke.then_some(Stroke {
+                width: style.stroke_width,
+                cap: style.cap,
+                join: style.join
+            })
+        });
+    }
+    Ok(())
+}
+
+fn parse_numbers(text: &str) -> Vec<f32> {
+    let mut parser = PathParser::new(text);
+    let mut numbers = Vec::new();
+    while let Some(number) = parser.number() {
+        numbers.push(number);
+    }
+    numbers
+}
+
+struct PathParser<'a> {
+    text: &'a [u8],
+    idx: usize
+}
+
+impl<'a> PathParser<'a> {
+
+    fn new(text: &'a str) -> Self {
+        Self {
+            text: text.as_bytes(),
+            idx: 0
+        }
+    }
+
+    fn skip_separators(&mut self) {
+        while self.idx < self.text.len() && (self.text[self.idx].is_ascii_whitespace() || self.text[self.idx] == b',') {
+            self.idx += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_separators();
+        self.text.get(self.idx).copied()
+    }
+
+    fn command(&mut self) -> Option<u8> {
+        let char = self.peek().filter(u8::is_ascii_alphabetic)?;
+        self.idx += 1;
+        Some(char)
+    }
+
+    // Numbers may run together, like "1.5.5" for 1.5 and 0.5 or "2-3" for 2 and -3
+    fn number(&mut self) -> Option<f32> {
+        self.peek()?;
+        let start = self.idx;
+        let mut end = start;
+        if matches!(self.text.get(end), Some(b'+' | b'-')) {
+            end += 1;
+        }
+        let mut seen_dot = false;
+        while let Some(&char) = self.text.get(end) {
+            if char.is_ascii_digit() {
+                end += 1;
+            } else if char == b'.' && !seen_dot {
+                seen_dot = true;
+                end += 1;
+            } else {
+                break;
+            }
+        }
+        if matches!(self.text.get(end), Some(b'e' | b'E')) {
+            let mut exp_end = end + 1;
+            if matches!(self.text.get(exp_end), Some(b'+' | b'-')) {
+                exp_end += 1;
+            }
+            if self.text.get(exp_end).is_some_and(u8::is_ascii_digit) {
+                end = exp_end;
+                while self.text.get(end).is_some_and(u8::is_ascii_digit) {
+                    end += 1;
+                }
+            }
+        }
+        let number = std::str::from_utf8(&self.text[start..end]).ok()?.parse().ok()?;
+        self.idx = end;
+        Some(number)
+    }
+
+    fn point(&mut self) -> Option<Pos> {
+        Some(pos(self.number()?, self.number()?))
+    }
+
+    // Arc flags are a single digit that may run into the next number
+    fn flag(&mut self) -> Option<bool> {
+        let flag = match self.peek()? {
+            b'0' => false,
+            b'1' => true,
+            _ => return None
+        };
+        self.idx += 1;
+        Some(flag)
+    }
+
+}
+
+fn parse_path_data(data: &str) -> Result<Vec<Segment>, IconError> {
+    let error = || IconError::BadPathData(data.to_owned());
+    let mut parser = PathParser::new(data);
+    let mut segments = Vec::new();
+
+    let mut command = None;
+    let mut curr = Pos::ZERO;
+    let mut subpath_start = Pos::ZERO;
+    // The last control points of curves, for the smooth curve commands to reflect
+    let mut last_cubic_ctrl = None;
+    let mut last_quad_ctrl = None;
+
+    while let Some(next) = parser.peek() {
+        if next.is_ascii_alphabetic() {
+            command = parser.command();
+        } else if matches!(command, None | Some(b'Z' | b'z')) {
+            return Err(error());
+        }
+        let Some(cmd) = command else { return Err(error()); };
+        let offset = if cmd.is_ascii_lowercase() { curr.to_vec() } else { Vec2::ZERO };
+        let point = |parser: &mut PathParser| parser.point().map(|point| point + offset).ok_or_else(error);
+
+        let mut cubic_ctrl = None;
+        let mut quad_ctrl = None;
+        match cmd.to_ascii_uppercase() {
+            b'M' => {
+                curr = point(&mut parser)?;
+                subpath_start = curr;
+                segments.push(Segment::MoveTo(curr));
+                // Further coordinates after a move are lines
+                command = Some(if cmd == b'm' { b'l' } else { b'L' });
+            },
+            b'L' => {
+                curr = point(&mut parser)?;
+                segments.push(Segment::LineTo(curr));
+            },
+            b'H' => {
+                curr.x = parser.number().ok_or_else(error)? + offset.x;
+                segments.push(Segment::LineTo(curr));
+            },
+            b'V' => {
+                curr.y = parser.number().ok_or_else(error)? + offset.y;
+                segments.push(Segment::LineTo(curr));
+            },
+            b'C' => {
+                let c1 = point(&mut parser)?;
+                let c2 = point(&mut parser)?;
+                curr = point(&mut parser)?;
+                segments.push(Segment::CubicTo(c1, c2, curr));
+                cubic_ctrl = Some(c2);
+            },
+            b'S' => {
+                let c1 = last_cubic_ctrl.map_or(curr, |ctrl: Pos| curr + (curr - ctrl));
+                let c2 = point(&mut parser)?;
+                curr = point(&mut parser)?;
+                segments.push(Segment::CubicTo(c1, c2, curr));
+                cubic_ctrl = Some(c2);
+            },
+            b'Q' => {
+                let ctrl = point(&mut parser)?;
+                curr = point(&mut parser)?;
+                segments.push(Segment::QuadTo(ctrl, curr));
+                quad_ctrl = Some(ctrl);
+            },
+            b'T' => {
+                let ctrl = last_quad_ctrl.map_or(curr, |ctrl: Pos| curr + (curr - ctrl));
+                curr = point(&mut parser)?;
+                segments.push(Segment::QuadTo(ctrl, curr));
+                quad_ctrl = Some(ctrl);
+            },
+            b'A' => {
+                let radii = vec2(parser.number().ok_or_else(error)?, parser.number().ok_or_else(error)?);
+                let rotation = parser.number().ok_or_else(error)?;
+                let large_arc = parser.flag().ok_or_else(error)?;
+                let sweep = parser.flag().ok_or_else(error)?;
+                let to = point(&mut parser)?;
+                arc_segments(curr, radii, rotation, large_arc, sweep, to, &mut segments);
+                curr = to;
+            },
+            b'Z' => {
+                segments.push(Segment::Close);
+                curr = subpath_start;
+            },
+            _ => return Err(error())
+        }
+        last_cubic_ctrl = cubic_ctrl;
+        last_quad_ctrl = quad_ctrl;
+    }
+
+    Ok(segments)
+}
+
+// Convert an SVG arc between two points to its center and angles, as in the SVG spec's implementation notes
+fn arc_segments(from: Pos, radii: Vec2, rotation: f32, large_arc: bool, sweep: bool, to: Pos, segments: &mut Vec<Segment>) {
+    if from == to {
+        return;
+    }
+    let mut rx = radii.x.abs();
+    let mut ry = radii.y.abs();
+    if rx == 0.0 || ry == 0.0 {
+        segments.push(Segment::LineTo(to));
+        return;
+    }
+
+    let rotation = rotation.to_radians();
+    let (sin, cos) = rotation.sin_cos();
+    let half = (from - to) / 2.0;
+    let x1 = cos * half.x + sin * half.y;
+    let y1 = -sin * half.x + cos * half.y;
+
+    // Grow radii that are too small to reach between the points
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+    let coef = sign * (numerator / denominator).max(0.0).sqrt();
+    let cx1 = coef * rx * y1 / ry;
+    let cy1 = -coef * ry * x1 / rx;
+    let mid = pos((from.x + to.x) / 2.0, (from.y + to.y) / 2.0);
+    let center = mid + vec2(cos * cx1 - sin * cy1, sin * cx1 + cos * cy1);
+
+    let angle = |x: f32, y: f32| y.atan2(x);
+    let start = angle((x1 - cx1) / rx, (y1 - cy1) / ry);
+    let end = angle((-x1 - cx1) / rx, (-y1 - cy1) / ry);
+    let mut delta = end - start;
+    if sweep && delta < 0.0 {
+        delta += TAU;
+    } else if !sweep && delta > 0.0 {
+        delta -= TAU;
+    }
+    ellipse_arc(center, vec2(rx, ry), rotation, start, delta, segments);
+}
+
+fn rect_segments(rect: Rect, radii: Vec2) -> Vec<Segment> {
+    let radii = radii.max(Vec2::ZERO).min(rect.size() / 2.0);
+    if radii.x == 0.0 || radii.y == 0.0 {
+        return vec![
+            Segment::MoveTo(rect.top_left()),
+            Segment::LineTo(rect.top_right()),
+            Segment::LineTo(rect.bottom_right()),
+            Segment::LineTo(rect.bottom_left()),
+            Segment::Close
+        ];
+    }
+
+    let mut segments = vec![Segment::MoveTo(rect.top_left() + vec2(radii.x, 0.0))];
+    segments.push(Segment::LineTo(rect.top_right() - vec2(radii.x, 0.0)));
+    ellipse_arc(rect.top_right() + vec2(-radii.x, radii.y), radii, 0.0, -FRAC_PI_2, FRAC_PI_2, &mut segments);
+    segments.push(Segment::LineTo(rect.bottom_right() - vec2(0.0, radii.y)));
+    ellipse_arc(rect.bottom_right() - radii, radii, 0.0, 0.0, FRAC_PI_2, &mut segments);
+    segments.push(Segment::LineTo(rect.bottom_left() + vec2(radii.x, 0.0)));
+    ellipse_arc(rect.bottom_left() + vec2(radii.x, -radii.y), radii, 0.0, FRAC_PI_2, FRAC_PI_2, &mut segments);
+    segments.push(Segment::LineTo(rect.top_left() + vec2(0.0, radii.y)));
+    ellipse_arc(rect.top_left() + radii, radii, 0.0, PI, FRAC_PI_2, &mut segments);
+    segments.push(Segment::Close);
+    segments
+}
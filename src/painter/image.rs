@@ -0,0 +1,156 @@
+
+use std::{cell::{Cell, RefCell}, collections::HashMap, rc::{Rc, Weak}, sync::atomic::{AtomicUsize, Ordering}};
+
+use femtovg::{imgref::ImgRef, renderer::OpenGl, rgb::RGBA8, Canvas, ImageFlags, ImageId};
+
+use crate::{vec2, Vec2};
+
+static NEXT_IMAGE_ID: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug)]
+pub enum ImageError {
+    Decode(png::DecodingError),
+    // The pixel buffer doesn't hold width * height RGBA pixels
+    WrongSize
+}
+
+struct ImageData {
+    id: usize,
+    width: usize,
+    height: usize,
+    // RGBA pixels, 4 bytes each, row by row
+    pixels: RefCell<Vec<u8>>,
+    // Bumped whenever the pixels change, so the texture knows to update
+    version: Cell<u64>
+}
+
+// Pixel data that can be drawn by the painter.
+// Cloning the handle shares the image, and its texture is freed once every handle is dropped.
+#[derive(Clone)]
+pub struct ImageHandle {
+    data: Rc<ImageData>
+}
+
+impl ImageHandle {
+
+    pub fn from_rgba(width: usize, height: usize, pixels: Vec<u8>) -> Result<Self, ImageError> {
+        if pixels.len() != width * height * 4 {
+            return Err(ImageError::WrongSize);
+        }
+        Ok(Self {
+            data: Rc::new(ImageData {
+                id: NEXT_IMAGE_ID.fetch_add(1, Ordering::Relaxed),
+                width,
+                height,
+                pixels: RefCell::new(pixels),
+                version: Cell::new(0)
+            })
+        })
+    }
+
+    pub fn from_png(bytes: &[u8]) -> Result<Self, ImageError> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder.read_info().map_err(ImageError::Decode)?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).map_err(ImageError::Decode)?;
+        let buffer = &buffer[..info.buffer_size()];
+
+        let pixels = match info.color_type {
+            png::ColorType::Rgba => buffer.to_vec(),
+            png::ColorType::Rgb => buffer.chunks_exact(3).flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255]).collect(),
+            png::ColorType::GrayscaleAlpha => buffer.chunks_exact(2).flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]]).collect(),
+            // Indexed images are expanded to RGB(A) by the decoder
+            png::ColorType::Grayscale | png::ColorType::Indexed => buffer.iter().flat_map(|g| [*g, *g, *g, 255]).collect()
+        };
+        Self::from_rgba(info.width as usize, info.height as usize, pixels)
+    }
+
+    pub fn width(&self) -> usize {
+        self.data.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.data.height
+    }
+
+    // Size in points, one for each pixel
+    pub fn size(&self) -> Vec2 {
+        vec2(self.data.width as f32, self.data.height as f32)
+    }
+
+    // Replace the pixels, keeping the size. The texture updates the next time the image is drawn.
+    pub fn set_pixels(&self, pixels: &[u8]) -> Result<(), ImageError> {
+        if pixels.len() != self.data.width * self.data.height * 4 {
+            return Err(ImageError::WrongSize);
+        }
+        self.data.pixels.borrow_mut().copy_from_slice(pixels);
+        self.data.version.set(self.data.version.get() + 1);
+        Ok(())
+    }
+
+    // Edit the pixels in place, like when drawing a new animation frame into them
+    pub fn update_pixels<F>(&self, update: F) where F: FnOnce(&mut [u8]) {
+        update(&mut self.data.pixels.borrow_mut());
+        self.data.version.set(self.data.version.get() + 1);
+    }
+
+}
+
+struct Texture {
+    image: ImageId,
+    version: u64,
+    // Lets the cache notice when every handle to the image is gone
+    data: Weak<ImageData>
+}
+
+// Uploads images to the GPU the first time they are drawn and keeps the textures between frames
+pub(crate) struct TextureCache {
+    textures: HashMap<usize, Texture>
+}
+
+impl TextureCache {
+
+    pub(crate) fn new() -> Self {
+        Self {
+            textures: HashMap::new()
+        }
+    }
+
+    pub(crate) fn texture(&mut self, canvas: &mut Canvas<OpenGl>, handle: &ImageHandle) -> Option<ImageId> {
+        let data = &handle.data;
+        if let Some(texture) = self.textures.get_mut(&data.id) {
+            if texture.version != data.version.get() {
+                let pixels = bytes_to_rgba(&data.pixels.borrow());
+                canvas.update_image(texture.image, ImgRef::new(&pixels, data.width, data.height), 0, 0).ok()?;
+                texture.version = data.version.get();
+            }
+            return Some(texture.image);
+        }
+
+        let pixels = bytes_to_rgba(&data.pixels.borrow());
+        let image = canvas.create_image(ImgRef::new(&pixels, data.width, data.height), ImageFlags::empty()).ok()?;
+        self.textures.insert(data.id, Texture {
+            image,
+            version: data.version.get(),
+            data: Rc::downgrade(data)
+        });
+        Some(image)
+    }
+
+    // Free the textures of images that can no longer be drawn
+    pub(crate) fn free_unused(&mut self, canvas: &mut Canvas<OpenGl>) {
+        self.textures.retain(|_, texture| {
+            let alive = texture.data.strong_count() > 0;
+            if !alive {
+                canvas.delete_image(texture.image);
+            }
+            alive
+        });
+    }
+
+}
+
+fn bytes_to_rgba(bytes: &[u8]) -> Vec<RGBA8> {
+    bytes.chunks_exact(4).map(|rgba| RGBA8::new(rgba[0], rgba[1], rgba[2], rgba[3])).collect()
+}
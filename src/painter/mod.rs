@@ -1,13 +1,17 @@
 
 use std::{cell::RefCell, ops::Range};
 
-use femtovg::{renderer::OpenGl, Canvas, FontId, Paint, Path};
+use femtovg::{renderer::OpenGl, Canvas, FontId, Paint, Path, Solidity, Transform2D};
 
-use crate::{theme::Theme, vec2, Color, Pos, Rect, Vec2};
+use crate::{theme::Theme, pos, vec2, Color, Pos, Rect, Transform, Vec2};
 
-use self::cursor::Cursor;
+use self::{cursor::Cursor, fill::Fill, icon::IconShape, image::{ImageHandle, TextureCache}, path::{dash_segments, to_femtovg_path, PathBuilder}};
 
 pub mod cursor;
+pub mod fill;
+pub mod path;
+pub mod image;
+pub mod icon;
 
 pub struct TextShaper<'a> {
     canvas: RefCell<&'a mut Canvas<OpenGl>>,
@@ -54,9 +58,15 @@ pub struct Painter<'a> {
     canvas: &'a mut Canvas<OpenGl>,
     pub theme: &'a Theme,
     text_paint: Paint,
+    textures: &'a mut TextureCache,
     scl: f32,
+    // Clip rects in screen space
     clip_rects: Vec<Rect>,
-    pub cursor: Cursor
+    // Each pushed transform combined with the ones below it
+    transforms: Vec<Transform>,
+    pub cursor: Cursor,
+    // Seconds until the next frame should be drawn, even if no input arrives
+    pub(crate) redraw_after: Option<f32>
 }
 
 fn to_color(color: Color) -> femtovg::Color {
@@ -68,11 +78,25 @@ fn to_color(color: Color) -> femtovg::Color {
     }
 }
 
+// A blurred copy of a rect's outline drawn behind it
+#[derive(Clone, Copy)]
+pub struct Shadow {
+    pub offset: Vec2,
+    // Width of the fade from the shadow's color to transparent
+    pub blur: f32,
+    // How much larger than the rect the shadow is
+    pub spread: f32,
+    pub color: Color
+}
+
 pub struct RectBuilder {
     rect: Rect,
-    fill: Option<Color>,
+    fill: Option<Fill>,
     stroke: Option<Color>,
-    rounding: f32
+    stroke_width: f32,
+    // Corner radii, clockwise from the top left
+    rounding: [f32; 4],
+    shadow: Option<Shadow>
 }
 
 impl RectBuilder {
@@ -82,12 +106,14 @@ impl RectBuilder {
             rect,
             fill: None,
             stroke: None,
-            rounding: 0.0
+            stroke_width: 1.0,
+            rounding: [0.0; 4],
+            shadow: None
         }
     }
 
-    pub fn fill(mut self, fill: Color) -> Self {
-        self.fill = Some(fill);
+    pub fn fill<F: Into<Fill>>(mut self, fill: F) -> Self {
+        self.fill = Some(fill.into());
         self
     }
 
@@ -96,8 +122,23 @@ impl RectBuilder {
         self
     }
 
+    pub fn stroke_width(mut self, width: f32) -> Self {
+        self.stroke_width = width;
+        self
+    }
+
     pub fn rounding(mut self, rounding: f32) -> Self {
-        self.rounding = rounding;
+        self.rounding = [rounding; 4];
+        self
+    }
+
+    pub fn corner_rounding(mut self, top_left: f32, top_right: f32, bottom_right: f32, bottom_left: f32) -> Self {
+        self.rounding = [top_left, top_right, bottom_right, bottom_left];
+        self
+    }
+
+    pub fn shadow(mut self, shadow: Shadow) -> Self {
+        self.shadow = Some(shadow);
         self
     }
 
@@ -105,27 +146,77 @@ impl RectBuilder {
 
 impl<'a> Painter<'a> {
 
-    pub(crate) fn new(canvas: &'a mut Canvas<OpenGl>, theme: &'a Theme, fonts: &[FontId], scl: f32) -> Self {
+    pub(crate) fn new(canvas: &'a mut Canvas<OpenGl>, theme: &'a Theme, fonts: &[FontId], textures: &'a mut TextureCache, scl: f32) -> Self {
         let mut text_paint = Paint::color(femtovg::Color::white());
         text_paint.set_font(fonts);
         Self {
             canvas,
             theme,
             text_paint,
+            textures,
             scl,
             clip_rects: Vec::new(),
-            cursor: Cursor::Default
+            transforms: Vec::new(),
+            cursor: Cursor::Default,
+            redraw_after: None
         }
     }
 
+    fn add_rounded_rect(&self, path: &mut Path, rect: Rect, rounding: [f32; 4]) {
+        let [top_left, top_right, bottom_right, bottom_left] = rounding.map(|radius| radius * self.scl);
+        path.rounded_rect_varying(rect.left() * self.scl, rect.top() * self.scl, rect.width() * self.scl, rect.height() * self.scl, top_left, top_right, bottom_right, bottom_left);
+    }
+
     pub fn rect(&mut self, rect: RectBuilder) {
+        if let Some(shadow) = rect.shadow {
+            self.shadow(rect.rect, rect.rounding, shadow);
+        }
+
         let mut path = Path::new();
-        path.rounded_rect(rect.rect.left() * self.scl, rect.rect.top() * self.scl, rect.rect.width() * self.scl, rect.rect.height() * self.scl, rect.rounding * self.scl);
+        self.add_rounded_rect(&mut path, rect.rect, rect.rounding);
         if let Some(fill) = rect.fill {
-            self.canvas.fill_path(&path, &Paint::color(to_color(fill)));
+            self.canvas.fill_path(&path, &fill.to_paint(self.scl));
         }
         if let Some(stroke) = rect.stroke {
-            self.canvas.stroke_path(&path, &Paint::color(to_color(stroke)).with_line_width(self.scl));
+            self.canvas.stroke_path(&path, &Paint::color(to_color(stroke)).with_line_width(rect.stroke_width * self.scl));
+        }
+    }
+
+    fn shadow(&mut self, rect: Rect, rounding: [f32; 4], shadow: Shadow) {
+        let shadow_rect = rect.shift(shadow.offset).expand(shadow.spread);
+        // Box gradients have a single corner radius
+        let radius = rounding.iter().copied().fold(0.0, f32::max) + shadow.spread;
+        let paint = Paint::box_gradient(
+            shadow_rect.left() * self.scl, shadow_rect.top() * self.scl,
+            shadow_rect.width() * self.scl, shadow_rect.height() * self.scl,
+            radius.max(0.0) * self.scl,
+            shadow.blur * self.scl,
+            to_color(shadow.color),
+            to_color(Color::TRANSPARENT)
+        );
+
+        // Cut the rect itself out, so the shadow doesn't show through a translucent fill
+        let outer = shadow_rect.expand(shadow.blur);
+        let mut path = Path::new();
+        path.rect(outer.left() * self.scl, outer.top() * self.scl, outer.width() * self.scl, outer.height() * self.scl);
+        self.add_rounded_rect(&mut path, rect, rounding);
+        path.solidity(Solidity::Hole);
+        self.canvas.fill_path(&path, &paint);
+    }
+
+    pub fn path(&mut self, path: PathBuilder) {
+        let scl = self.scl;
+        let to_device = |point: Pos| (point.x * scl, point.y * scl);
+        let femtovg_path = to_femtovg_path(&path.segments, to_device);
+        if let Some(fill) = &path.fill {
+            self.canvas.fill_path(&femtovg_path, &fill.to_paint(scl));
+        }
+        if let Some(stroke) = path.stroke {
+            let paint = Paint::color(to_color(stroke)).with_line_width(path.stroke_width * scl);
+            match path.dashes {
+                Some((dash, gap)) => self.canvas.stroke_path(&to_femtovg_path(&dash_segments(&path.segments, dash, gap), to_device), &paint),
+                None => self.canvas.stroke_path(&femtovg_path, &paint)
+            }
         }
     }
 
@@ -142,11 +233,93 @@ impl<'a> Painter<'a> {
         let _ = self.canvas.fill_text(pos.x * self.scl, pos.y * self.scl, text, &self.text_paint);
     } 
 
+    // Draw an image stretched over a rect, with its colors multiplied by the tint
+    pub fn image(&mut self, rect: Rect, image: &ImageHandle, tint: Color) {
+        let src = Rect::min_size(Pos::ZERO, image.size());
+        self.image_region(rect, image, src, tint);
+    }
+
+    // Draw the part of an image within `src`, in pixels, stretched over a rect
+    pub fn image_region(&mut self, rect: Rect, image: &ImageHandle, src: Rect, tint: Color) {
+        if src.width() <= 0.0 || src.height() <= 0.0 || rect.width() <= 0.0 || rect.height() <= 0.0 {
+            return;
+        }
+        let Some(texture) = self.textures.texture(self.canvas, image) else { return; };
+
+        // Position the whole image so that the region lands on the rect
+        let scale_x = rect.width() / src.width();
+        let scale_y = rect.height() / src.height();
+        let image_min = pos(rect.left() - src.left() * scale_x, rect.top() - src.top() * scale_y);
+        let image_size = vec2(image.size().x * scale_x, image.size().y * scale_y);
+        let paint = Paint::image_tint(
+            texture,
+            image_min.x * self.scl, image_min.y * self.scl,
+            image_size.x * self.scl, image_size.y * self.scl,
+            0.0,
+            to_color(tint)
+        );
+
+        let mut path = Path::new();
+        path.rect(rect.left() * self.scl, rect.top() * self.scl, rect.width() * self.scl, rect.height() * self.scl);
+        self.canvas.fill_path(&path, &paint);
+    }
+
+    // Draw an icon as large as fits in a rect, centered, in a single color
+    pub fn icon(&mut self, rect: Rect, icon: &IconShape, color: Color) {
+        let view_box = icon.view_box();
+        if view_box.width() <= 0.0 || view_box.height() <= 0.0 {
+            return;
+        }
+        let fit = (rect.width() / view_box.width()).min(rect.height() / view_box.height());
+        let size = view_box.size() * fit;
+        let min = rect.center() - size / 2.0;
+
+        // Snap to whole device pixels so straight edges stay crisp at any scale factor
+        let origin = vec2((min.x * self.scl).round(), (min.y * self.scl).round());
+        let scale = (size.x * self.scl).round() / view_box.width();
+        let to_device = |point: Pos| (origin.x + (point.x - view_box.left()) * scale, origin.y + (point.y - view_box.top()) * scale);
+
+        for icon_path in icon.paths() {
+            let path = to_femtovg_path(&icon_path.segments, to_device);
+            if let Some(fill_rule) = icon_path.fill {
+                let mut paint = Paint::color(to_color(color));
+                paint.set_fill_rule(fill_rule);
+                self.canvas.fill_path(&path, &paint);
+            }
+            if let Some(stroke) = icon_path.stroke {
+                let mut paint = Paint::color(to_color(color)).with_line_width(stroke.width * scale);
+                paint.set_line_cap(stroke.cap);
+                paint.set_line_join(stroke.join);
+                self.canvas.stroke_path(&path, &paint);
+            }
+        }
+    }
+
+    pub fn request_redraw(&mut self) {
+        self.request_redraw_after(0.0);
+    }
+
+    // Schedule another frame in `delay` seconds. Useful for timers and animations.
+    pub fn request_redraw_after(&mut self, delay: f32) {
+        let delay = delay.max(0.0);
+        self.redraw_after = Some(self.redraw_after.map_or(delay, |curr| curr.min(delay)));
+    }
+
     fn set_clip_rect(&mut self, rect: Rect) {
+        // The scissor is set without the current transform, since clip rects are kept in screen space
+        self.canvas.reset_transform();
         self.canvas.scissor(rect.left() * self.scl, rect.top() * self.scl, rect.width() * self.scl, rect.height() * self.scl);
+        self.apply_transform();
     }
 
+    // Restrict drawing to a rect, within the current clip rect.
+    // Under a rotation, this clips to the bounding box of the rotated rect.
     pub fn push_clip_rect(&mut self, rect: Rect) {
+        let rect = self.transform().apply_rect(rect);
+        let rect = match self.clip_rects.last() {
+            Some(curr) => curr.intersect(rect),
+            None => rect
+        };
         self.clip_rects.push(rect);
         self.set_clip_rect(rect);
     }
@@ -157,4 +330,36 @@ impl<'a> Painter<'a> {
         self.set_clip_rect(rect);
     }
 
+    // Maps positions in the space widgets draw in to the screen
+    pub fn transform(&self) -> Transform {
+        self.transforms.last().copied().unwrap_or(Transform::IDENTITY)
+    }
+
+    fn apply_transform(&mut self) {
+        // Everything is drawn in device pixels, so apply the transform between scaling down and back up
+        let t = Transform::scale(self.scl) * self.transform() * Transform::scale(1.0 / self.scl);
+        self.canvas.reset_transform();
+        self.canvas.set_transform(&Transform2D([t.a, t.b, t.c, t.d, t.e, t.f]));
+    }
+
+    // Apply a transform to everything drawn until it is popped, on top of the current transform
+    pub fn push_transform(&mut self, transform: Transform) {
+        self.transforms.push(self.transform() * transform);
+        self.apply_transform();
+    }
+
+    pub fn pop_transform(&mut self) {
+        self.transforms.pop().expect("popped too many times.");
+        self.apply_transform();
+    }
+
+    // Where a position on the screen, like the pointer's, lands in the current transform's space
+    pub fn to_local(&self, screen_pos: Pos) -> Pos {
+        self.transform().inverse().map_or(screen_pos, |inverse| inverse.apply(screen_pos))
+    }
+
+    pub fn to_screen(&self, local_pos: Pos) -> Pos {
+        self.transform().apply(local_pos)
+    }
+
 }
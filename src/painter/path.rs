@@ -0,0 +1,283 @@
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use crate::{vec2, Color, Pos, Vec2};
+
+use super::fill::Fill;
+
+// Number of straight lines a curve is split into when dashed
+const CURVE_STEPS: usize = 16;
+
+#[derive(Clone, Copy)]
+pub(crate) enum Segment {
+    MoveTo(Pos),
+    LineTo(Pos),
+    CubicTo(Pos, Pos, Pos),
+    QuadTo(Pos, Pos),
+    Close
+}
+
+// Approximate part of an ellipse with cubic curves, no more than a quarter turn each
+pub(crate) fn ellipse_arc(center: Pos, radii: Vec2, rotation: f32, start: f32, sweep: f32, segments: &mut Vec<Segment>) {
+    let (sin, cos) = rotation.sin_cos();
+    let rotate = |v: Vec2| vec2(v.x * cos - v.y * sin, v.x * sin + v.y * cos);
+    let point = |angle: f32| center + rotate(vec2(radii.x * angle.cos(), radii.y * angle.sin()));
+    let tangent = |angle: f32| rotate(vec2(-radii.x * angle.sin(), radii.y * angle.cos()));
+
+    // The small allowance keeps rounding error from adding a sliver of a segment to exact quarter turns
+    let count = (sweep.abs() / FRAC_PI_2 - 0.001).ceil().max(1.0) as usize;
+    let step = sweep / count as f32;
+    let handle = 4.0 / 3.0 * (step / 4.0).tan();
+    for idx in 0..count {
+        let a = start + step * idx as f32;
+        let b = a + step;
+        segments.push(Segment::CubicTo(point(a) + tangent(a) * handle, point(b) - tangent(b) * handle, point(b)));
+    }
+}
+
+pub(crate) fn ellipse_segments(center: Pos, radii: Vec2) -> Vec<Segment> {
+    let mut segments = vec![Segment::MoveTo(center + vec2(radii.x, 0.0))];
+    ellipse_arc(center, radii, 0.0, 0.0, TAU, &mut segments);
+    segments.push(Segment::Close);
+    segments
+}
+
+// Build a femtovg path, mapping each point to device pixels
+pub(crate) fn to_femtovg_path<F>(segments: &[Segment], to_device: F) -> femtovg::Path where F: Fn(Pos) -> (f32, f32) {
+    let mut path = femtovg::Path::new();
+    for segment in segments {
+        match *segment {
+            Segment::MoveTo(point) => {
+                let (x, y) = to_device(point);
+                path.move_to(x, y);
+            },
+            Segment::LineTo(point) => {
+                let (x, y) = to_device(point);
+                path.line_to(x, y);
+            },
+            Segment::CubicTo(c1, c2, point) => {
+                let ((c1x, c1y), (c2x, c2y), (x, y)) = (to_device(c1), to_device(c2), to_device(point));
+                path.bezier_to(c1x, c1y, c2x, c2y, x, y);
+            },
+            Segment::QuadTo(ctrl, point) => {
+                let ((cx, cy), (x, y)) = (to_device(ctrl), to_device(point));
+                path.quad_to(cx, cy, x, y);
+            },
+            Segment::Close => path.close()
+        }
+    }
+    path
+}
+
+fn lerp(a: Pos, b: Pos, t: f32) -> Pos {
+    a + (b - a) * t
+}
+
+// Split every subpath into a list of points joined by straight lines
+fn flatten(segments: &[Segment]) -> Vec<Vec<Pos>> {
+    let mut polylines = Vec::new();
+    let mut curr: Vec<Pos> = Vec::new();
+    for segment in segments {
+        let from = curr.last().copied();
+        match *segment {
+            Segment::MoveTo(point) => {
+                if curr.len() > 1 {
+                    polylines.push(std::mem::take(&mut curr));
+                }
+                curr = vec![point];
+            },
+            Segment::LineTo(point) => curr.push(point),
+            Segment::QuadTo(ctrl, point) => {
+                let from = from.unwrap_or(ctrl);
+                curr.extend((1..=CURVE_STEPS).map(|step| {
+                    let t = step as f32 / CURVE_STEPS as f32;
+                    lerp(lerp(from, ctrl, t), lerp(ctrl, point, t), t)
+                }));
+            },
+            Segment::CubicTo(c1, c2, point) => {
+                let from = from.unwrap_or(c1);
+                curr.extend((1..=CURVE_STEPS).map(|step| {
+                    let t = step as f32 / CURVE_STEPS as f32;
+                    let (a, b, c) = (lerp(from, c1, t), lerp(c1, c2, t), lerp(c2, point, t));
+                    lerp(lerp(a, b, t), lerp(b, c, t), t)
+                }));
+            },
+            Segment::Close => {
+                if let Some(&first) = curr.first() {
+                    curr.push(first);
+                    polylines.push(std::mem::take(&mut curr));
+                    curr = vec![first];
+                }
+            }
+        }
+    }
+    if curr.len() > 1 {
+        polylines.push(curr);
+    }
+    polylines
+}
+
+// The visible pieces of a dashed stroke along the segments. Each subpath starts with a dash.
+pub(crate) fn dash_segments(segments: &[Segment], dash: f32, gap: f32) -> Vec<Segment> {
+    if dash <= 0.0 {
+        return Vec::new();
+    }
+    if gap <= 0.0 {
+        return segments.to_vec();
+    }
+
+    let mut dashed = Vec::new();
+    for points in flatten(segments) {
+        let mut on = true;
+        let mut left = dash;
+        dashed.push(Segment::MoveTo(points[0]));
+        for pair in points.windows(2) {
+            let (mut a, b) = (pair[0], pair[1]);
+            let mut length = (b - a).length();
+            while length > left {
+                let split = lerp(a, b, left / length);
+                dashed.push(if on { Segment::LineTo(split) } else { Segment::MoveTo(split) });
+                length -= left;
+                a = split;
+                on = !on;
+                left = if on { dash } else { gap };
+            }
+            left -= length;
+            if on {
+                dashed.push(Segment::LineTo(b));
+            }
+        }
+    }
+    dashed
+}
+
+// An arbitrary shape made of lines and curves, filled and/or stroked
+pub struct PathBuilder {
+    pub(crate) segments: Vec<Segment>,
+    // Where the next segment starts, once the path has been started
+    curr: Option<Pos>,
+    pub(crate) fill: Option<Fill>,
+    pub(crate) stroke: Option<Color>,
+    pub(crate) stroke_width: f32,
+    // Lengths of the dashes and the gaps between them
+    pub(crate) dashes: Option<(f32, f32)>
+}
+
+impl Default for PathBuilder {
+
+    fn default() -> Self {
+        Self::new()
+    }
+
+}
+
+impl PathBuilder {
+
+    pub fn new() -> Self {
+        Self {
+            segments: Vec::new(),
+            curr: None,
+            fill: None,
+            stroke: None,
+            stroke_width: 1.0,
+            dashes: None
+        }
+    }
+
+    pub fn line(a: Pos, b: Pos) -> Self {
+        Self::new().move_to(a).line_to(b)
+    }
+
+    // Points joined by straight lines
+    pub fn polyline(points: &[Pos]) -> Self {
+        let mut path = Self::new();
+        for (idx, point) in points.iter().enumerate() {
+            path = if idx == 0 { path.move_to(*point) } else { path.line_to(*point) };
+        }
+        path
+    }
+
+    // A closed polyline
+    pub fn polygon(points: &[Pos]) -> Self {
+        Self::polyline(points).close()
+    }
+
+    pub fn circle(center: Pos, radius: f32) -> Self {
+        Self::ellipse(center, Vec2::splat(radius))
+    }
+
+    pub fn ellipse(center: Pos, radii: Vec2) -> Self {
+        let mut path = Self::new();
+        path.segments = ellipse_segments(center, radii);
+        path.curr = Some(center + vec2(radii.x, 0.0));
+        path
+    }
+
+    pub fn move_to(mut self, point: Pos) -> Self {
+        self.segments.push(Segment::MoveTo(point));
+        self.curr = Some(point);
+        self
+    }
+
+    pub fn line_to(mut self, point: Pos) -> Self {
+        self.segments.push(if self.curr.is_some() { Segment::LineTo(point) } else { Segment::MoveTo(point) });
+        self.curr = Some(point);
+        self
+    }
+
+    pub fn quad_to(mut self, ctrl: Pos, point: Pos) -> Self {
+        if self.curr.is_none() {
+            self = self.move_to(ctrl);
+        }
+        self.segments.push(Segment::QuadTo(ctrl, point));
+        self.curr = Some(point);
+        self
+    }
+
+    pub fn cubic_to(mut self, ctrl1: Pos, ctrl2: Pos, point: Pos) -> Self {
+        if self.curr.is_none() {
+            self = self.move_to(ctrl1);
+        }
+        self.segments.push(Segment::CubicTo(ctrl1, ctrl2, point));
+        self.curr = Some(point);
+        self
+    }
+
+    // Part of a circle, from angle `start` to `end` in radians. Angles grow clockwise, since y points down.
+    // Joins the arc to the end of the path with a straight line.
+    pub fn arc(mut self, center: Pos, radius: f32, start: f32, end: f32) -> Self {
+        let arc_start = center + vec2(start.cos(), start.sin()) * radius;
+        self = self.line_to(arc_start);
+        ellipse_arc(center, Vec2::splat(radius), 0.0, start, end - start, &mut self.segments);
+        self.curr = Some(center + vec2(end.cos(), end.sin()) * radius);
+        self
+    }
+
+    // Join the end of the path back to its start
+    pub fn close(mut self) -> Self {
+        self.segments.push(Segment::Close);
+        self
+    }
+
+    pub fn fill<F: Into<Fill>>(mut self, fill: F) -> Self {
+        self.fill = Some(fill.into());
+        self
+    }
+
+    pub fn stroke(mut self, stroke: Color) -> Self {
+        self.stroke = Some(stroke);
+        self
+    }
+
+    pub fn stroke_width(mut self, width: f32) -> Self {
+        self.stroke_width = width;
+        self
+    }
+
+    // Draw the stroke as dashes of length `dash` separated by `gap`
+    pub fn dashed(mut self, dash: f32, gap: f32) -> Self {
+        self.dashes = Some((dash, gap));
+        self
+    }
+
+}
@@ -1,14 +1,18 @@
 
 use std::{any::Any, collections::HashMap};
 
-use crate::widget::Message;
+use crate::widget::{menu::ContextMenuState, tooltip::TooltipState, Message};
 
 pub struct WidgetState<S> {
     pub(crate) state: Box<dyn Any>,
     pub(crate) child_state: HashMap<usize, WidgetState<S>>,
     pub(crate) focused: bool,
     pub(crate) requested_focus: bool,
-    pub(crate) messages: Vec<Message<S>>
+    pub(crate) keyboard_focused: bool,
+    pub(crate) requested_keyboard_focus: bool,
+    pub(crate) messages: Vec<Message<S>>,
+    pub(crate) tooltip: TooltipState,
+    pub(crate) context_menu: Option<ContextMenuState>
 }
 
 impl<S> WidgetState<S> {
@@ -19,7 +23,11 @@ impl<S> WidgetState<S> {
             child_state: HashMap::new(),
             focused: false,
             requested_focus: false,
-            messages: Vec::new() 
+            keyboard_focused: false,
+            requested_keyboard_focus: false,
+            messages: Vec::new(),
+            tooltip: TooltipState::default(),
+            context_menu: None
         }
     }
 
@@ -30,6 +38,13 @@ impl<S> WidgetState<S> {
         self.child_state.get_mut(&local_id).unwrap()
     }
 
+    pub(crate) fn close_context_menu(&mut self) {
+        if let Some(ContextMenuState { menu_id: Some(menu_id), .. }) = self.context_menu.take() {
+            // Forget the state of any open submenus
+            self.child_state.remove(&menu_id);
+        }
+    }
+
     pub fn focused(&self) -> bool {
         self.focused
     }
@@ -42,6 +57,20 @@ impl<S> WidgetState<S> {
         self.focused = false;
     }
 
+    // Keyboard focus is held by at most one widget, which is then the only one to see key presses and typed text.
+    // A click anywhere takes it away, so widgets ask for it again when they are clicked.
+    pub fn keyboard_focused(&self) -> bool {
+        self.keyboard_focused
+    }
+
+    pub fn request_keyboard_focus(&mut self) {
+        self.requested_keyboard_focus = true;
+    }
+
+    pub fn release_keyboard_focus(&mut self) {
+        self.keyboard_focused = false;
+    }
+
     pub fn message<F>(&mut self, handler: F) where F: Fn(&mut S) + 'static {
         self.messages.push(Message::new(handler));
     }
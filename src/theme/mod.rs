@@ -7,18 +7,26 @@ pub struct Theme {
     pub bg_light: Color,
     pub bg_window: Color,
     pub stroke: Color,
+    // Dims the content behind modal dialogs
+    pub backdrop: Color,
 
     pub text: Color,
 
     pub button: Color,
     pub text_field: Color,
+    // Marks the current position or selection, like a timeline's playhead
+    pub accent: Color,
 
     pub hovered_darkness: f32,
     pub pressed_darkness: f32,
 
     pub font_size: f32,
+    pub icon_size: f32,
     pub item_spacing: f32,
-    pub rounding: f32
+    pub rounding: f32,
+
+    // Seconds the pointer must rest on a widget before its tooltip appears
+    pub tooltip_delay: f32
 }
 
 impl Theme {
@@ -29,18 +37,23 @@ impl Theme {
             bg_light: Color::from_hex(0x363738FF),
             bg_window: Color::from_hex(0x404143FF),
             stroke: Color::from_hex(0x1D1D1DFF),
+            backdrop: Color::from_hex(0x00000080),
 
             text: Color::from_hex(0xDCDEE0FF),
 
             button: Color::from_hex(0x56585AFF),
             text_field: Color::from_hex(0x242328FF),
+            accent: Color::from_hex(0xE0803CFF),
 
             hovered_darkness: 0.2,
             pressed_darkness: 0.4,
             
             font_size: 13.0,
+            icon_size: 16.0,
             item_spacing: 10.0,
-            rounding: 7.0
+            rounding: 7.0,
+
+            tooltip_delay: 0.5
         }
     }
 
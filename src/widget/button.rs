@@ -1,7 +1,22 @@
 
-use crate::{painter::{Painter, RectBuilder}, state::WidgetState, Color, Rect, Vec2, WidgetNode};
-use super::{margin::Margin, LayoutContext, LayoutResult, Response, Widget};
+use crate::{painter::{icon::IconShape, Painter, RectBuilder}, state::WidgetState, theme::Theme, Color, Rect, Vec2, WidgetNode};
+use super::{icon::Icon, linear::Align, margin::Margin, row::Row, text::Text, LayoutContext, LayoutResult, Response, Widget};
 
+// Margin around the icon of an icon-only button, smaller than around text so toolbars stay compact
+const ICON_MARGIN: f32 = 5.0;
+const ICON_SPACING: f32 = 5.0;
+
+
+// Darken a clickable widget's color while it is hovered or pressed
+pub(crate) fn interaction_color(color: Color, resp: &Response, theme: &Theme) -> Color {
+    color.darken(if resp.mouse_down() {
+        theme.pressed_darkness
+    } else if resp.hovered() {
+        theme.hovered_darkness
+    } else {
+        0.0
+    })
+}
 
 pub struct Button<S> {
     inner_margin: WidgetNode<S>,
@@ -15,6 +30,21 @@ impl<S: 'static> Button<S> {
         }).sense_click(true)
     }
 
+    // A button showing just an icon, as in a toolbar
+    pub fn icon(icon: IconShape) -> WidgetNode<S> {
+        WidgetNode::new(Self {
+            inner_margin: Margin::new_with_margin(Icon::new(icon).build(), ICON_MARGIN),
+        }).sense_click(true)
+    }
+
+    // A button with an icon before its label
+    pub fn with_icon<T: Into<String>>(icon: IconShape, label: T) -> WidgetNode<S> {
+        Self::new(Row::builder(vec![Icon::new(icon).build(), Text::new(label)])
+            .align(Align::Baseline)
+            .spacing(ICON_SPACING)
+            .build())
+    }
+
 }
 
 impl<S> Widget<S> for Button<S> {
@@ -31,13 +61,7 @@ impl<S> Widget<S> for Button<S> {
 
     fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, _state: &mut WidgetState<S>) {
 
-        let color = painter.theme.button.lerp(Color::BLACK, if resp.mouse_down() {
-            painter.theme.pressed_darkness
-        } else if resp.hovered() {
-            painter.theme.hovered_darkness
-        } else {
-            0.0
-        });
+        let color = interaction_color(painter.theme.button, resp, painter.theme);
 
         painter.rect(RectBuilder::new(rect).fill(color).stroke(painter.theme.stroke).rounding(painter.theme.rounding));
     }
@@ -0,0 +1,297 @@
+
+use std::{ops::RangeInclusive, rc::Rc};
+
+use crate::{painter::{cursor::Cursor, Painter, RectBuilder}, vec2, Pos, Rect, Response, Transform, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{key::Key, LayoutContext, LayoutResult};
+
+// Zoom change per point scrolled
+const ZOOM_SPEED: f32 = 0.002;
+// Space left around the content when zooming to fit
+const FIT_MARGIN: f32 = 20.0;
+
+// What a view may zoom to and what it fits to
+pub(crate) struct ViewLimits {
+    pub(crate) zoom_range: RangeInclusive<f32>,
+    // The area the content covers, in content space
+    pub(crate) content_bounds: Option<Rect>,
+    // Whether fitting zooms both axes equally
+    pub(crate) keep_aspect: bool
+}
+
+// A pan offset and zoom, shared by widgets showing content in its own coordinate space
+pub(crate) struct CanvasView {
+    // Where the content's origin sits, from the top left of the widget
+    pub(crate) offset: Vec2,
+    // Points per content unit on each axis
+    pub(crate) zoom: Vec2,
+    // Set once the view has been fit to the content for the first time
+    initialized: bool,
+    // The last pointer position while panning
+    pan_pos: Option<Pos>
+}
+
+impl Default for CanvasView {
+
+    fn default() -> Self {
+        Self {
+            offset: Vec2::ZERO,
+            zoom: Vec2::splat(1.0),
+            initialized: false,
+            pan_pos: None
+        }
+    }
+
+}
+
+impl CanvasView {
+
+    // Maps content positions to positions relative to the top left of the widget
+    pub(crate) fn transform(&self) -> Transform {
+        Transform::translate(self.offset) * Transform::scale_xy(self.zoom)
+    }
+
+    pub(crate) fn panning(&self) -> bool {
+        self.pan_pos.is_some()
+    }
+
+    // Change the zoom while keeping the content under `anchor` in place
+    fn zoom_around(&mut self, anchor: Vec2, factor: f32, zoom_range: &RangeInclusive<f32>) {
+        let content = vec2((anchor.x - self.offset.x) / self.zoom.x, (anchor.y - self.offset.y) / self.zoom.y);
+        let zoom_x = (self.zoom.x * factor).clamp(*zoom_range.start(), *zoom_range.end());
+        let zoom_y = (self.zoom.y * factor).clamp(*zoom_range.start(), *zoom_range.end());
+        self.zoom = vec2(zoom_x, zoom_y);
+        self.offset = anchor - vec2(content.x * zoom_x, content.y * zoom_y);
+    }
+
+    fn fit(&mut self, bounds: Rect, size: Vec2, limits: &ViewLimits) {
+        if bounds.width() <= 0.0 || bounds.height() <= 0.0 {
+            return;
+        }
+        let available = (size - Vec2::splat(2.0 * FIT_MARGIN)).max(Vec2::splat(1.0));
+        let zoom = vec2(available.x / bounds.width(), available.y / bounds.height());
+        let zoom = if limits.keep_aspect { Vec2::splat(zoom.x.min(zoom.y)) } else { zoom };
+        let clamp = |zoom: f32| zoom.clamp(*limits.zoom_range.start(), *limits.zoom_range.end());
+        self.zoom = vec2(clamp(zoom.x), clamp(zoom.y));
+        self.offset = size / 2.0 - vec2(bounds.center().x * self.zoom.x, bounds.center().y * self.zoom.y);
+    }
+
+    // Fits the content when first shown, pans with the middle button or space and the left button, pans by scrolling,
+    // zooms around the pointer with ctrl+scroll and fits the content again with F.
+    // Shared with other widgets, which keep their `CanvasView` wherever `view_of` finds it.
+    // Returns whether the view took the pointer input this frame.
+    pub(crate) fn handle_input<S>(widget_state: &mut WidgetState<S>, view_of: fn(&mut WidgetState<S>) -> &mut CanvasView, limits: &ViewLimits, rect: Rect, painter: &mut Painter, resp: &Response) -> bool {
+        let view = view_of(widget_state);
+        if !view.initialized {
+            view.initialized = true;
+            if let Some(bounds) = limits.content_bounds {
+                view.fit(bounds, rect.size(), limits);
+            }
+        }
+
+        // Start panning
+        if resp.middle_mouse_clicked() || (resp.mouse_clicked() && resp.key_down(Key::Space)) {
+            view.pan_pos = resp.hover_pos();
+            widget_state.request_focus();
+            return true;
+        }
+
+        if let Some(pan_pos) = view.pan_pos {
+            if let Some(hover_pos) = resp.global_hover_pos() {
+                view.offset += hover_pos - pan_pos;
+                view.pan_pos = Some(hover_pos);
+            }
+            painter.cursor = Cursor::Move;
+            if resp.global_middle_mouse_released() || resp.global_mouse_released() {
+                view.pan_pos = None;
+                widget_state.unfocus();
+            }
+            return true;
+        }
+
+        if let Some(hover_pos) = resp.hover_pos() {
+            let scroll = resp.scroll();
+            if resp.modifiers().ctrl && scroll.y != 0.0 {
+                view.zoom_around(hover_pos - rect.min(), (1.0 + scroll.y * ZOOM_SPEED).max(0.1), &limits.zoom_range);
+            } else {
+                view.offset += scroll;
+            }
+
+            if resp.key_pressed(Key::F) {
+                if let Some(bounds) = limits.content_bounds {
+                    view.fit(bounds, rect.size(), limits);
+                    painter.request_redraw();
+                }
+            }
+        }
+        false
+    }
+
+}
+
+// The view of a canvas, kept between frames
+#[derive(Default)]
+pub struct Canvas2DState {
+    view: CanvasView,
+    // Whether the left button is drawing rather than panning
+    pressed: bool
+}
+
+impl Canvas2DState {
+
+    // Maps content positions to positions relative to the top left of the canvas
+    pub fn view(&self) -> Transform {
+        self.view.transform()
+    }
+
+}
+
+// What the draw callback knows about the pointer and the view
+pub struct CanvasInput {
+    // The pointer in content space, while it is over the canvas or dragging on it
+    pub pointer: Option<Pos>,
+    pub zoom: f32,
+    // Maps content space to the screen
+    pub view: Transform,
+    // The part of the content that is visible
+    pub visible: Rect
+}
+
+type DrawContent = Box<dyn Fn(&mut Painter, &CanvasInput)>;
+type OnPointer<S> = Rc<dyn Fn(&mut S, Pos)>;
+
+// A pannable, zoomable view onto content drawn by a callback in its own coordinate space.
+// Middle-drag or space-drag pans, scrolling pans, ctrl+scroll zooms around the pointer and F zooms to fit.
+pub struct Canvas2D<S> {
+    draw: DrawContent,
+    limits: ViewLimits,
+    on_press: Option<OnPointer<S>>,
+    on_drag: Option<OnPointer<S>>,
+    on_release: Option<OnPointer<S>>
+}
+
+impl<S: 'static> Canvas2D<S> {
+
+    pub fn new<F>(draw: F) -> Self where F: Fn(&mut Painter, &CanvasInput) + 'static {
+        Self {
+            draw: Box::new(draw),
+            limits: ViewLimits {
+                zoom_range: 0.1..=10.0,
+                content_bounds: None,
+                keep_aspect: true
+            },
+            on_press: None,
+            on_drag: None,
+            on_release: None
+        }
+    }
+
+    pub fn zoom_range(mut self, zoom_range: RangeInclusive<f32>) -> Self {
+        // Zooming clamps to the range, which panics when it is reversed
+        let (start, end) = zoom_range.into_inner();
+        self.limits.zoom_range = start.min(end)..=start.max(end);
+        self
+    }
+
+    // The area the content covers, which the view fits on the first frame and when F is pressed
+    pub fn content_bounds(mut self, bounds: Rect) -> Self {
+        self.limits.content_bounds = Some(bounds);
+        self
+    }
+
+    // Called with the pointer in content space when the left button is pressed on the canvas
+    pub fn on_press<F>(mut self, handler: F) -> Self where F: Fn(&mut S, Pos) + 'static {
+        self.on_press = Some(Rc::new(handler));
+        self
+    }
+
+    // Called every frame the pointer moves while the left button is held
+    pub fn on_drag<F>(mut self, handler: F) -> Self where F: Fn(&mut S, Pos) + 'static {
+        self.on_drag = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn on_release<F>(mut self, handler: F) -> Self where F: Fn(&mut S, Pos) + 'static {
+        self.on_release = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        WidgetNode::new(self).sense_click(true)
+    }
+
+    fn view_state(widget_state: &mut WidgetState<S>) -> &mut CanvasView {
+        &mut Self::get(widget_state).view
+    }
+
+    fn send(handler: &Option<OnPointer<S>>, pos: Pos, state: &mut WidgetState<S>) {
+        if let Some(handler) = handler.clone() {
+            state.message(move |state| handler(state, pos));
+        }
+    }
+
+    fn handle_pointer_input(&self, painter: &Painter, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let state = Self::get(widget_state);
+        if resp.mouse_clicked() {
+            if let Some(hover_pos) = resp.hover_pos() {
+                state.pressed = true;
+                widget_state.request_focus();
+                Self::send(&self.on_press, painter.to_local(hover_pos), widget_state);
+            }
+        } else if state.pressed {
+            // The pointer is gone when the button is released outside the window
+            let pos = resp.global_hover_pos().map(|pos| painter.to_local(pos));
+            if resp.global_mouse_released() {
+                state.pressed = false;
+                widget_state.unfocus();
+                if let Some(pos) = pos {
+                    Self::send(&self.on_release, pos, widget_state);
+                }
+            } else if let Some(pos) = pos {
+                Self::send(&self.on_drag, pos, widget_state);
+            }
+        }
+    }
+
+}
+
+impl<S: 'static> Widget<S> for Canvas2D<S> {
+
+    type State = Canvas2DState;
+
+    fn layout(&self, max_size: Vec2, _ctx: &mut LayoutContext, _state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let size = vec2(
+            if max_size.x.is_finite() { max_size.x } else { 400.0 },
+            if max_size.y.is_finite() { max_size.y } else { 300.0 }
+        );
+        LayoutResult::new(size)
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let panning = CanvasView::handle_input(widget_state, Self::view_state, &self.limits, rect, painter, resp);
+
+        let state = Self::get(widget_state);
+        let view = Transform::translate(rect.min().to_vec()) * state.view.transform();
+        painter.push_clip_rect(rect);
+        painter.rect(RectBuilder::new(rect).fill(painter.theme.bg_dark));
+        painter.push_transform(view);
+
+        if !panning {
+            self.handle_pointer_input(painter, resp, widget_state);
+        }
+
+        let state = Self::get(widget_state);
+        let pointer = if state.pressed || state.view.panning() { resp.global_hover_pos() } else { resp.hover_pos() };
+        let input = CanvasInput {
+            pointer: pointer.map(|pointer| painter.to_local(pointer)),
+            zoom: state.view.zoom.x,
+            view,
+            visible: view.inverse().map_or(rect, |inverse| inverse.apply_rect(rect))
+        };
+        (self.draw)(painter, &input);
+
+        painter.pop_transform();
+        painter.pop_clip_rect();
+    }
+
+}
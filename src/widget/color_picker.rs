@@ -0,0 +1,457 @@
+
+use std::{cell::{Cell, OnceCell, RefCell}, rc::Rc};
+
+use crate::{color, painter::{Painter, RectBuilder}, pos, vec2, Color, Pos, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{column::Column, drag_value::DragValue, dropdown::Dropdown, key::Key, linear::Align, row::Row, text::Text, LayoutContext, LayoutResult};
+
+const SQUARE_SIZE: f32 = 150.0;
+const STRIP_SIZE: f32 = 18.0;
+// Number of flat steps gradients are drawn with
+const GRADIENT_STEPS: usize = 32;
+const CHECKER_SIZE: f32 = 5.0;
+const MARKER_SIZE: f32 = 8.0;
+const SWATCH_SIZE: Vec2 = vec2(36.0, 20.0);
+const HEX_FIELD_WIDTH: f32 = 90.0;
+const HEX_FIELD_HEIGHT: f32 = 20.0;
+const PADDING: f32 = 6.0;
+
+// Shows through transparent colors
+fn draw_checkerboard(painter: &mut Painter, rect: Rect) {
+    painter.push_clip_rect(rect);
+    painter.rect(RectBuilder::new(rect).fill(color(0.8, 0.8, 0.8, 1.0)));
+    let cols = (rect.width() / CHECKER_SIZE).ceil() as usize;
+    let rows = (rect.height() / CHECKER_SIZE).ceil() as usize;
+    for row in 0..rows {
+        for col in (row % 2..cols).step_by(2) {
+            let cell = Rect::min_size(rect.min() + vec2(col as f32, row as f32) * CHECKER_SIZE, Vec2::splat(CHECKER_SIZE));
+            painter.rect(RectBuilder::new(cell).fill(color(0.6, 0.6, 0.6, 1.0)));
+        }
+    }
+    painter.pop_clip_rect();
+}
+
+#[derive(Clone, Copy)]
+enum ColorAreaKind {
+    // Saturation across, value up
+    SaturationValue,
+    Hue,
+    Alpha
+}
+
+type OnSet<S> = Rc<dyn Fn(&mut S, Color)>;
+type OnFinish<S> = Rc<dyn Fn(&mut S)>;
+
+// A gradient that picks part of the color where it is clicked or dragged
+struct ColorArea<S> {
+    kind: ColorAreaKind,
+    hsv: (f32, f32, f32),
+    // Shared with the picker, which can't get the hue back out of a gray
+    picked: Rc<Cell<(f32, f32, f32)>>,
+    alpha: f32,
+    on_set: OnSet<S>,
+    on_finish: Option<OnFinish<S>>
+}
+
+impl<S> ColorArea<S> {
+
+    fn pick(&self, rect: Rect, hover_pos: Pos) -> Color {
+        let x = ((hover_pos.x - rect.left()) / rect.width()).clamp(0.0, 1.0);
+        let y = ((hover_pos.y - rect.top()) / rect.height()).clamp(0.0, 1.0);
+        let (h, s, v) = self.hsv;
+        let ((h, s, v), alpha) = match self.kind {
+            ColorAreaKind::SaturationValue => ((h, x, 1.0 - y), self.alpha),
+            // The top of the strip wraps around to red again, so stop just short of it
+            ColorAreaKind::Hue => ((y.min(0.9999), s, v), self.alpha),
+            ColorAreaKind::Alpha => ((h, s, v), x)
+        };
+        self.picked.set((h, s, v));
+        Color::from_hsv(h, s, v, alpha)
+    }
+
+}
+
+impl<S: 'static> Widget<S> for ColorArea<S> {
+
+    type State = ();
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, _state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let size = match self.kind {
+            ColorAreaKind::SaturationValue => Vec2::splat(SQUARE_SIZE),
+            ColorAreaKind::Hue => vec2(STRIP_SIZE, SQUARE_SIZE),
+            // As wide as the square and hue strip beside each other
+            ColorAreaKind::Alpha => vec2(SQUARE_SIZE + ctx.theme.item_spacing + STRIP_SIZE, STRIP_SIZE)
+        };
+        LayoutResult::new(size.min(max_size))
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, state: &mut WidgetState<S>) {
+        if resp.mouse_clicked() {
+            state.request_focus();
+        }
+        if state.focused() || state.requested_focus {
+            if let Some(hover_pos) = resp.global_hover_pos() {
+                let color = self.pick(rect, hover_pos);
+                let on_set = self.on_set.clone();
+                state.message(move |state| on_set(state, color));
+            }
+            if resp.global_mouse_released() {
+                state.unfocus();
+                if let Some(on_finish) = self.on_finish.clone() {
+                    state.message(move |state| on_finish(state));
+                }
+            }
+        }
+
+        let (h, s, v) = self.hsv;
+        let step = 1.0 / GRADIENT_STEPS as f32;
+        let marker = match self.kind {
+            ColorAreaKind::SaturationValue => {
+                let cell_size = rect.size() * step;
+                for row in 0..GRADIENT_STEPS {
+                    for col in 0..GRADIENT_STEPS {
+                        let cell = Rect::min_size(rect.min() + vec2(col as f32 * cell_size.x, row as f32 * cell_size.y), cell_size + Vec2::splat(0.5));
+                        let color = Color::from_hsv(h, (col as f32 + 0.5) * step, 1.0 - (row as f32 + 0.5) * step, 1.0);
+                        painter.rect(RectBuilder::new(cell).fill(color));
+                    }
+                }
+                Rect::center_size(pos(rect.left() + s * rect.width(), rect.top() + (1.0 - v) * rect.height()), Vec2::splat(MARKER_SIZE))
+            },
+            ColorAreaKind::Hue => {
+                let cell_height = rect.height() * step;
+                for row in 0..GRADIENT_STEPS {
+                    let cell = Rect::min_size(rect.min() + vec2(0.0, row as f32 * cell_height), vec2(rect.width(), cell_height + 0.5));
+                    painter.rect(RectBuilder::new(cell).fill(Color::from_hsv((row as f32 + 0.5) * step, 1.0, 1.0, 1.0)));
+                }
+                Rect::center_size(pos(rect.center().x, rect.top() + h * rect.height()), vec2(rect.width() + 2.0, MARKER_SIZE / 2.0))
+            },
+            ColorAreaKind::Alpha => {
+                draw_checkerboard(painter, rect);
+                let cell_width = rect.width() * step;
+                for col in 0..GRADIENT_STEPS {
+                    let cell = Rect::min_size(rect.min() + vec2(col as f32 * cell_width, 0.0), vec2(cell_width + 0.5, rect.height()));
+                    painter.rect(RectBuilder::new(cell).fill(Color::from_hsv(h, s, v, (col as f32 + 0.5) * step)));
+                }
+                Rect::center_size(pos(rect.left() + self.alpha * rect.width(), rect.center().y), vec2(MARKER_SIZE / 2.0, rect.height() + 2.0))
+            }
+        };
+        painter.rect(RectBuilder::new(rect).stroke(painter.theme.stroke));
+        painter.rect(RectBuilder::new(marker).stroke(painter.theme.text).rounding(MARKER_SIZE / 2.0));
+    }
+
+}
+
+#[derive(Default)]
+struct HexFieldState {
+    // Digits being typed, while in text entry
+    editing: Option<String>
+}
+
+// The color as a hex code, which can be typed over after clicking it
+struct HexField<S> {
+    color: Color,
+    on_set: OnSet<S>,
+    on_finish: Option<OnFinish<S>>,
+    text: RefCell<(String, Vec2)>
+}
+
+impl<S: 'static> HexField<S> {
+
+    fn commit(&self, widget_state: &mut WidgetState<S>) {
+        let typed = Self::get(widget_state).editing.take();
+        // Codes without an alpha part keep the current alpha
+        let parsed = typed.and_then(|text| {
+            let color = Color::from_hex_str(&text)?;
+            let has_alpha = !matches!(text.trim().trim_start_matches('#').len(), 3 | 6);
+            Some(if has_alpha { color } else { color.with_alpha(self.color.a) })
+        });
+        if let Some(color) = parsed {
+            let on_set = self.on_set.clone();
+            widget_state.message(move |state| on_set(state, color));
+            if let Some(on_finish) = self.on_finish.clone() {
+                widget_state.message(move |state| on_finish(state));
+            }
+        }
+    }
+
+}
+
+impl<S: 'static> Widget<S> for HexField<S> {
+
+    type State = HexFieldState;
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let text = match &Self::get(state).editing {
+            Some(text) => format!("#{}", text),
+            None => self.color.to_hex_string()
+        };
+        let text_size = ctx.text_shaper.measure_text(ctx.theme.font_size, &text);
+        *self.text.borrow_mut() = (text, text_size);
+
+        let size = vec2(HEX_FIELD_WIDTH.max(text_size.x + 2.0 * PADDING), HEX_FIELD_HEIGHT.max(text_size.y)).min(max_size);
+        let mut layout = LayoutResult::new(size);
+        layout.baseline = Some((size.y + text_size.y) / 2.0);
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        // A click anywhere takes the keyboard away, so clicks on the text while editing take it back
+        if Self::get(widget_state).editing.is_some() && resp.mouse_clicked() {
+            widget_state.request_keyboard_focus();
+        }
+        let state = Self::get(widget_state);
+        if let Some(text) = &mut state.editing {
+            text.extend(resp.text_input().chars().filter(char::is_ascii_hexdigit));
+            text.truncate(8);
+            if resp.key_pressed(Key::Backspace) {
+                text.pop();
+            }
+            if !resp.text_input().is_empty() || !resp.keys_pressed().is_empty() {
+                painter.request_redraw();
+            }
+            if resp.key_pressed(Key::Escape) {
+                state.editing = None;
+                widget_state.release_keyboard_focus();
+            } else if resp.key_pressed(Key::Enter) || resp.clicked_elsewhere() {
+                self.commit(widget_state);
+                widget_state.release_keyboard_focus();
+            }
+        } else if resp.mouse_clicked() {
+            state.editing = Some(self.color.to_hex_string().trim_start_matches('#').to_owned());
+            widget_state.request_keyboard_focus();
+            painter.request_redraw();
+        }
+
+        let editing = Self::get(widget_state).editing.is_some();
+        let fill = if editing || !resp.hovered() {
+            painter.theme.text_field
+        } else {
+            painter.theme.text_field.darken(painter.theme.hovered_darkness)
+        };
+        painter.rect(RectBuilder::new(rect).fill(fill).stroke(painter.theme.stroke).rounding(painter.theme.rounding / 2.0));
+
+        let text = self.text.borrow();
+        let (text, text_size) = &*text;
+        let text_pos = pos(rect.left() + PADDING, rect.center().y + text_size.y / 2.0);
+        painter.text(text, text_pos, painter.theme.text, painter.theme.font_size);
+        if editing {
+            let caret_x = text_pos.x + text_size.x + 1.0;
+            painter.line(pos(caret_x, rect.top() + 3.0), pos(caret_x, rect.bottom() - 3.0), painter.theme.text);
+        }
+    }
+
+}
+
+#[derive(Default)]
+pub struct ColorPickerState {
+    // Kept between frames so grays and black don't forget the hue and saturation they were picked with.
+    // The gradients and HSV fields write what they pick here, since the color they emit may not keep it.
+    hsv: Rc<Cell<(f32, f32, f32)>>
+}
+
+// Edits a color with a saturation/value square, hue and alpha strips, a hex code and numeric fields
+pub struct ColorPicker<S> {
+    color: Color,
+    on_set: Option<OnSet<S>>,
+    on_finish: Option<OnFinish<S>>,
+    // Built in the first layout pass, once the hue and saturation are known
+    contents: OnceCell<WidgetNode<S>>
+}
+
+impl<S: 'static> ColorPicker<S> {
+
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            on_set: None,
+            on_finish: None,
+            contents: OnceCell::new()
+        }
+    }
+
+    pub fn on_set<F>(mut self, handler: F) -> Self where F: Fn(&mut S, Color) + 'static {
+        self.on_set = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn on_finish<F>(mut self, handler: F) -> Self where F: Fn(&mut S) + 'static {
+        self.on_finish = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        WidgetNode::new(self)
+    }
+
+    fn build_contents(&self, picked: Rc<Cell<(f32, f32, f32)>>) -> WidgetNode<S> {
+        let on_set = self.on_set.clone().unwrap_or_else(|| Rc::new(|_, _| {}));
+        let color = self.color;
+        let hsv = picked.get();
+        let (h, s, v) = hsv;
+
+        let area = |kind| WidgetNode::new(ColorArea {
+            kind,
+            hsv,
+            picked: picked.clone(),
+            alpha: color.a,
+            on_set: on_set.clone(),
+            on_finish: self.on_finish.clone()
+        }).sense_click(true);
+
+        let field = |label: &str, value: f32, max: i32, to_color: Box<dyn Fn(f32) -> Color>| {
+            let on_set = on_set.clone();
+            let on_finish = self.on_finish.clone();
+            let mut drag_value = DragValue::new((value * max as f32).round() as i32)
+                .range(0..=max)
+                .speed(0.5)
+                .on_set(move |state, value| on_set(state, to_color(value as f32 / max as f32)));
+            if let Some(on_finish) = on_finish {
+                drag_value = drag_value.on_finish(move |state| on_finish(state));
+            }
+            Row::builder(vec![Text::new(label), drag_value.build()]).align(Align::Baseline).build()
+        };
+
+        let hsv_field = |label: &str, value: f32, max: i32, to_hsv: Box<dyn Fn(f32) -> (f32, f32, f32)>| {
+            let picked = picked.clone();
+            field(label, value, max, Box::new(move |value| {
+                let (h, s, v) = to_hsv(value);
+                picked.set((h, s, v));
+                Color::from_hsv(h, s, v, color.a)
+            }))
+        };
+
+        Column::new(vec![
+            Row::new(vec![
+                area(ColorAreaKind::SaturationValue),
+                area(ColorAreaKind::Hue)
+            ]),
+            area(ColorAreaKind::Alpha),
+            Row::builder(vec![
+                Text::new("Hex"),
+                WidgetNode::new(HexField {
+                    color,
+                    on_set: on_set.clone(),
+                    on_finish: self.on_finish.clone(),
+                    text: RefCell::new((String::new(), Vec2::ZERO))
+                }).sense_click(true)
+            ]).align(Align::Baseline).build(),
+            Row::new(vec![
+                field("R", color.r, 255, Box::new(move |r| Color { r, ..color })),
+                field("G", color.g, 255, Box::new(move |g| Color { g, ..color })),
+                field("B", color.b, 255, Box::new(move |b| Color { b, ..color })),
+                field("A", color.a, 255, Box::new(move |a| color.with_alpha(a)))
+            ]),
+            Row::new(vec![
+                hsv_field("H", h, 360, Box::new(move |h| (h.min(0.9999), s, v))),
+                hsv_field("S", s, 100, Box::new(move |s| (h, s, v))),
+                hsv_field("V", v, 100, Box::new(move |v| (h, s, v)))
+            ])
+        ])
+    }
+
+}
+
+impl<S: 'static> Widget<S> for ColorPicker<S> {
+
+    type State = ColorPickerState;
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, widget_state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let state = Self::get(widget_state);
+        let (prev_h, prev_s, _) = state.hsv.get();
+        let (h, s, v) = self.color.to_hsv();
+        state.hsv.set(if v <= 0.0 {
+            (prev_h, prev_s, v)
+        } else if s <= 0.0 {
+            (prev_h, s, v)
+        } else {
+            (h, s, v)
+        });
+        let picked = state.hsv.clone();
+
+        let contents = self.contents.get_or_init(|| self.build_contents(picked)).layout(max_size, ctx, widget_state);
+        let mut layout = LayoutResult::new(contents.size());
+        layout.add_child(Vec2::ZERO, contents);
+        layout
+    }
+
+}
+
+// A swatch of the color that opens a color picker when clicked
+pub struct ColorButton<S> {
+    color: Color,
+    on_set: Option<OnSet<S>>,
+    on_finish: Option<OnFinish<S>>,
+    // Built the first time the picker is open
+    dropdown: OnceCell<WidgetNode<S>>
+}
+
+impl<S: 'static> ColorButton<S> {
+
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            on_set: None,
+            on_finish: None,
+            dropdown: OnceCell::new()
+        }
+    }
+
+    pub fn on_set<F>(mut self, handler: F) -> Self where F: Fn(&mut S, Color) + 'static {
+        self.on_set = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn on_finish<F>(mut self, handler: F) -> Self where F: Fn(&mut S) + 'static {
+        self.on_finish = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        WidgetNode::new(self).sense_click(true)
+    }
+
+    fn build_dropdown(&self) -> WidgetNode<S> {
+        let mut picker = ColorPicker::new(self.color);
+        picker.on_set = self.on_set.clone();
+        picker.on_finish = self.on_finish.clone();
+        Dropdown::new(picker.build())
+    }
+
+}
+
+impl<S: 'static> Widget<S> for ColorButton<S> {
+
+    type State = bool;
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let size = SWATCH_SIZE.min(max_size);
+        let mut layout = LayoutResult::new(size);
+        if *Self::get(state) {
+            let dropdown = self.dropdown.get_or_init(|| self.build_dropdown());
+            layout.add_popover(vec2(0.0, size.y), dropdown.layout_popover(ctx, state));
+        }
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, state: &mut WidgetState<S>) {
+        let open = Self::get(state);
+        if resp.mouse_clicked() {
+            *open = !*open;
+            painter.request_redraw();
+        } else if resp.clicked_elsewhere() && !resp.popover_hovered() {
+            *open = false;
+        }
+
+        // The left half shows the color without its transparency
+        let half = vec2(rect.width() / 2.0, rect.height());
+        let left = Rect::min_size(rect.min(), half);
+        let right = Rect::min_size(rect.min() + vec2(half.x, 0.0), half);
+        painter.rect(RectBuilder::new(left).fill(self.color.with_alpha(1.0)));
+        draw_checkerboard(painter, right);
+        painter.rect(RectBuilder::new(right).fill(self.color));
+
+        let stroke = if resp.hovered() || *Self::get(state) { painter.theme.text } else { painter.theme.stroke };
+        painter.rect(RectBuilder::new(rect).stroke(stroke));
+    }
+
+}
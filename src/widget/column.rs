@@ -1,18 +1,45 @@
+use crate::{state::WidgetState, Axis, Vec2, Widget, WidgetNode};
 
-use crate::{state::WidgetState, vec2, Vec2, Widget, WidgetNode};
-
-use super::{LayoutContext, LayoutResult};
+use super::{linear::{Align, Justify, Linear}, LayoutContext, LayoutResult};
 
 pub struct Column<S> {
-    contents: Vec<WidgetNode<S>>
+    contents: Vec<WidgetNode<S>>,
+    linear: Linear
 }
 
 impl<S: 'static> Column<S> {
 
     pub fn new(contents: Vec<WidgetNode<S>>) -> WidgetNode<S> {
-        WidgetNode::new(Self {
-            contents
-        })
+        Self::builder(contents).build()
+    }
+
+    pub fn builder(contents: Vec<WidgetNode<S>>) -> Self {
+        Self {
+            contents,
+            linear: Linear::new(Axis::Y)
+        }
+    }
+
+    // Space between items. Defaults to the theme's item spacing.
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.linear.spacing = Some(spacing);
+        self
+    }
+
+    // Horizontal placement of the items
+    pub fn align(mut self, align: Align) -> Self {
+        self.linear.align = align;
+        self
+    }
+
+    // Vertical placement of the items when the column is taller than its contents
+    pub fn justify(mut self, justify: Justify) -> Self {
+        self.linear.justify = justify;
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        WidgetNode::new(self)
     }
 
 }
@@ -22,22 +49,7 @@ impl<S> Widget<S> for Column<S> {
     type State = ();
 
     fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<S> {
-        let mut layout = LayoutResult::new(Vec2::ZERO);
-        for item in &self.contents {
-            let item_layout = item.layout(vec2(max_size.x, max_size.y - layout.size.y - ctx.theme.item_spacing), ctx, state);
-            let size = item_layout.size();
-            layout.add_child(vec2(0.0, layout.size.y), item_layout);
-
-            layout.size.y += size.y + ctx.theme.item_spacing;
-            layout.size.x = layout.size.x.max(size.x);
-            if layout.size.y >= max_size.y - 0.05 {
-                break;
-            }
-        }
-
-        layout.size.y -= ctx.theme.item_spacing;
-        
-        layout
+        self.linear.layout(&self.contents, max_size, ctx, state)
     }
 
 }
@@ -0,0 +1,338 @@
+
+use std::{cell::{Cell, OnceCell, RefCell}, rc::Rc};
+
+use crate::{painter::{Painter, RectBuilder}, pos, vec2, Axis, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{button::interaction_color, dropdown::Dropdown, key::Key, menu::{MENU_ITEM_HEIGHT, MENU_ITEM_PADDING}, scroll_area::{ScrollArea, ScrollAreaState, SCROLLBAR_SIZE}, LayoutContext, LayoutResult};
+
+const ARROW_SPACE: f32 = 20.0;
+const ARROW_SIZE: f32 = 3.5;
+const MAX_LIST_HEIGHT: f32 = 250.0;
+const NO_MATCHES: &str = "No matches";
+
+// Local ids of the dropdown popover and of the list inside it
+const POPUP_ID: usize = 0;
+const LIST_ID: usize = 0;
+
+#[derive(Default)]
+struct OptionListState {
+    scroll: ScrollAreaState,
+    // Typed text the options are filtered by
+    filter: String,
+    // Index of the option under the pointer or picked with the arrow keys
+    highlighted: Option<usize>,
+    // Set once an option is chosen or the list is dismissed, so the combo box closes it
+    done: bool
+}
+
+type OnSelect<S> = Rc<dyn Fn(&mut S, usize)>;
+
+// The options shown in a combo box's dropdown
+struct OptionList<S> {
+    options: Vec<String>,
+    selected: usize,
+    on_select: Option<OnSelect<S>>,
+    label_sizes: RefCell<Vec<Vec2>>,
+    text_height: Cell<f32>,
+    // Indices of the options passing the filter this frame
+    shown: RefCell<Vec<usize>>,
+    // The row of the first sensor, as only rows in view get one
+    first_sensed_row: Cell<usize>
+}
+
+impl<S: 'static> OptionList<S> {
+
+    fn scroll_state(widget_state: &mut WidgetState<S>) -> &mut ScrollAreaState {
+        &mut Self::get(widget_state).scroll
+    }
+
+    fn filter_height(state: &OptionListState) -> f32 {
+        if state.filter.is_empty() { 0.0 } else { MENU_ITEM_HEIGHT }
+    }
+
+    fn choose(&self, option: usize, widget_state: &mut WidgetState<S>) {
+        if let Some(on_select) = self.on_select.clone() {
+            widget_state.message(move |state| on_select(state, option));
+        }
+        Self::get(widget_state).done = true;
+    }
+
+    // Move the highlight by `step` rows with the arrow keys, scrolling it into view
+    fn move_highlight(&self, step: isize, view_height: f32, state: &mut OptionListState) {
+        let shown = self.shown.borrow();
+        if shown.is_empty() {
+            return;
+        }
+        let row = state.highlighted.and_then(|option| shown.iter().position(|shown| *shown == option));
+        let row = match row {
+            Some(row) => (row as isize + step).clamp(0, shown.len() as isize - 1) as usize,
+            None => 0
+        };
+        state.highlighted = Some(shown[row]);
+
+        let row_top = row as f32 * MENU_ITEM_HEIGHT;
+        let view_top = -state.scroll.scroll.y;
+        if row_top < view_top {
+            state.scroll.scroll.y = -row_top;
+        } else if row_top + MENU_ITEM_HEIGHT > view_top + view_height {
+            state.scroll.scroll.y = -(row_top + MENU_ITEM_HEIGHT - view_height);
+        }
+    }
+
+}
+
+impl<S: 'static> Widget<S> for OptionList<S> {
+
+    type State = OptionListState;
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, widget_state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let state = Self::get(widget_state);
+        let label_sizes: Vec<Vec2> = self.options.iter().map(|option| ctx.text_shaper.measure_text(ctx.theme.font_size, option)).collect();
+        // Sized by every option, so the list doesn't change width while filtering
+        let label_width = label_sizes.iter().map(|size| size.x).fold(0.0, f32::max);
+        *self.label_sizes.borrow_mut() = label_sizes;
+        self.text_height.set(ctx.text_shaper.measure_text(ctx.theme.font_size, NO_MATCHES).y);
+
+        let filter = state.filter.to_lowercase();
+        let shown: Vec<usize> = (0..self.options.len()).filter(|idx| self.options[*idx].to_lowercase().contains(&filter)).collect();
+        if !state.highlighted.is_some_and(|option| shown.contains(&option)) {
+            state.highlighted = if shown.contains(&self.selected) { Some(self.selected) } else { shown.first().copied() };
+        }
+
+        let width = label_width + 2.0 * MENU_ITEM_PADDING + SCROLLBAR_SIZE;
+        let filter_height = Self::filter_height(state);
+        let rows_height = shown.len() as f32 * MENU_ITEM_HEIGHT;
+        let view_height = rows_height.clamp(MENU_ITEM_HEIGHT, MAX_LIST_HEIGHT).min(max_size.y - filter_height);
+        state.scroll.inner_size = vec2(width - SCROLLBAR_SIZE, rows_height);
+        state.scroll.clamp_scroll(false, true, vec2(width - SCROLLBAR_SIZE, view_height));
+
+        let mut layout = LayoutResult::new(vec2(width, filter_height + view_height).min(max_size));
+
+        // Sense the rows in view, cut down to the part that is visible
+        let view_top = -state.scroll.scroll.y;
+        let first_row = ((view_top / MENU_ITEM_HEIGHT).floor().max(0.0) as usize).min(shown.len());
+        self.first_sensed_row.set(first_row);
+        for row in first_row..shown.len() {
+            let top = row as f32 * MENU_ITEM_HEIGHT;
+            if top >= view_top + view_height {
+                break;
+            }
+            let visible_top = top.max(view_top);
+            let visible_bottom = (top + MENU_ITEM_HEIGHT).min(view_top + view_height);
+            layout.add_sensor(vec2(0.0, filter_height + visible_top - view_top), vec2(width - SCROLLBAR_SIZE, visible_bottom - visible_top));
+        }
+
+        *self.shown.borrow_mut() = shown;
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let filter_height = Self::filter_height(Self::get(widget_state));
+        let list_rect = Rect::min_size(rect.min() + vec2(0.0, filter_height), vec2(rect.width() - SCROLLBAR_SIZE, rect.height() - filter_height));
+
+        // Keyboard: arrows move the highlight, typing filters the options
+        let state = Self::get(widget_state);
+        let prev_filter = state.filter.clone();
+        let mut chosen = None;
+        for key in resp.keys_pressed() {
+            match key {
+                Key::Up => self.move_highlight(-1, list_rect.height(), state),
+                Key::Down => self.move_highlight(1, list_rect.height(), state),
+                Key::Enter => chosen = state.highlighted,
+                Key::Escape => state.done = true,
+                Key::Backspace => {
+                    state.filter.pop();
+                },
+                _ => {}
+            }
+        }
+        state.filter.push_str(resp.text_input());
+        if state.filter != prev_filter {
+            state.scroll.scroll = Vec2::ZERO;
+        }
+        if !resp.keys_pressed().is_empty() || !resp.text_input().is_empty() {
+            painter.request_redraw();
+        }
+
+        // Mouse: hovering highlights an option, clicking chooses it
+        if let Some(sensor_idx) = resp.sensor_idx() {
+            let option = self.shown.borrow().get(self.first_sensed_row.get() + sensor_idx).copied();
+            if option.is_some() && option != state.highlighted {
+                state.highlighted = option;
+                painter.request_redraw();
+            }
+            if resp.mouse_clicked() {
+                chosen = option;
+            }
+        }
+
+        if let Some(option) = chosen {
+            self.choose(option, widget_state);
+            painter.request_redraw();
+        }
+
+        ScrollArea::<S>::draw_scrollbar(Axis::Y, true, widget_state, Self::scroll_state, list_rect, painter, resp);
+        let state = Self::get(widget_state);
+        state.scroll.scroll += resp.scroll();
+        state.scroll.clamp_scroll(false, true, list_rect.size());
+
+        let label_sizes = self.label_sizes.borrow();
+        let text_height = self.text_height.get();
+        if !state.filter.is_empty() {
+            let filter_pos = pos(rect.left() + MENU_ITEM_PADDING, rect.top() + (MENU_ITEM_HEIGHT + text_height) / 2.0);
+            painter.text(&state.filter, filter_pos, painter.theme.text, painter.theme.font_size);
+            painter.line(pos(rect.left(), list_rect.top()), pos(rect.right(), list_rect.top()), painter.theme.stroke);
+        }
+
+        painter.push_clip_rect(list_rect);
+        let shown = self.shown.borrow();
+        if shown.is_empty() {
+            let label_pos = pos(list_rect.left() + MENU_ITEM_PADDING, list_rect.top() + (MENU_ITEM_HEIGHT + text_height) / 2.0);
+            painter.text(NO_MATCHES, label_pos, painter.theme.text.darken(painter.theme.hovered_darkness), painter.theme.font_size);
+        }
+        for (row, option) in shown.iter().enumerate() {
+            let row_rect = Rect::min_size(list_rect.min() + vec2(0.0, row as f32 * MENU_ITEM_HEIGHT + state.scroll.scroll.y), vec2(list_rect.width(), MENU_ITEM_HEIGHT));
+            if row_rect.bottom() < list_rect.top() || row_rect.top() > list_rect.bottom() {
+                continue;
+            }
+            if state.highlighted == Some(*option) {
+                let color = painter.theme.bg_light.darken(painter.theme.hovered_darkness);
+                painter.rect(RectBuilder::new(row_rect).fill(color).rounding(painter.theme.rounding / 2.0));
+            }
+            let label_pos = pos(row_rect.left() + MENU_ITEM_PADDING, row_rect.center().y + label_sizes[*option].y / 2.0);
+            painter.text(&self.options[*option], label_pos, painter.theme.text, painter.theme.font_size);
+        }
+        painter.pop_clip_rect();
+    }
+
+}
+
+// A button showing the selected option, which opens a dropdown to pick another one
+pub struct ComboBox<S> {
+    options: Vec<String>,
+    selected: usize,
+    on_select: Option<OnSelect<S>>,
+    label_size: Cell<Vec2>,
+    // Built the first time the dropdown is open
+    dropdown: OnceCell<WidgetNode<S>>
+}
+
+impl<S: 'static> Default for ComboBox<S> {
+
+    fn default() -> Self {
+        Self::new()
+    }
+
+}
+
+impl<S: 'static> ComboBox<S> {
+
+    pub fn new() -> Self {
+        Self {
+            options: Vec::new(),
+            selected: 0,
+            on_select: None,
+            label_size: Cell::new(Vec2::ZERO),
+            dropdown: OnceCell::new()
+        }
+    }
+
+    pub fn option<T: Into<String>>(mut self, label: T) -> Self {
+        self.options.push(label.into());
+        self
+    }
+
+    pub fn selected(mut self, selected: usize) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    // Called with the index of the chosen option
+    pub fn on_select<F>(mut self, handler: F) -> Self where F: Fn(&mut S, usize) + 'static {
+        self.on_select = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        WidgetNode::new(self).sense_click(true)
+    }
+
+    fn build_dropdown(&self) -> WidgetNode<S> {
+        let list = WidgetNode::new(OptionList {
+            options: self.options.clone(),
+            selected: self.selected,
+            on_select: self.on_select.clone(),
+            label_sizes: RefCell::new(Vec::new()),
+            text_height: Cell::new(0.0),
+            shown: RefCell::new(Vec::new()),
+            first_sensed_row: Cell::new(0)
+        }).sense_click(true).id(LIST_ID);
+        Dropdown::new(list).id(POPUP_ID)
+    }
+
+    fn close(widget_state: &mut WidgetState<S>) {
+        *Self::get(widget_state) = false;
+        // Forget the filter and scroll of the list
+        widget_state.child_state.remove(&POPUP_ID);
+    }
+
+}
+
+impl<S: 'static> Widget<S> for ComboBox<S> {
+
+    type State = bool;
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let label_width = self.options.iter().map(|option| ctx.text_shaper.measure_text(ctx.theme.font_size, option).x).fold(0.0, f32::max);
+        let label_size = ctx.text_shaper.measure_text(ctx.theme.font_size, self.options.get(self.selected).map(String::as_str).unwrap_or(""));
+        self.label_size.set(label_size);
+
+        let size = vec2(label_width + 2.0 * MENU_ITEM_PADDING + ARROW_SPACE, MENU_ITEM_HEIGHT.max(label_size.y)).min(max_size);
+        let mut layout = LayoutResult::new(size);
+        layout.baseline = Some((size.y + label_size.y) / 2.0);
+
+        if *Self::get(state) {
+            let dropdown = self.dropdown.get_or_init(|| self.build_dropdown());
+            layout.add_popover(vec2(0.0, size.y), dropdown.layout_popover(ctx, state));
+        }
+
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let open = *Self::get(widget_state);
+        if open {
+            let list_state = Dropdown::contents_state(widget_state.get_child(POPUP_ID), LIST_ID);
+            if OptionList::<S>::get(list_state).done || (resp.clicked_elsewhere() && !resp.popover_hovered()) {
+                Self::close(widget_state);
+                painter.request_redraw();
+            }
+        }
+        if resp.mouse_clicked() {
+            if open {
+                Self::close(widget_state);
+            } else {
+                *Self::get(widget_state) = true;
+            }
+            painter.request_redraw();
+        }
+
+        let color = if *Self::get(widget_state) {
+            painter.theme.button.darken(painter.theme.pressed_darkness)
+        } else {
+            interaction_color(painter.theme.button, resp, painter.theme)
+        };
+        painter.rect(RectBuilder::new(rect).fill(color).stroke(painter.theme.stroke).rounding(painter.theme.rounding));
+
+        if let Some(label) = self.options.get(self.selected) {
+            let label_pos = pos(rect.left() + MENU_ITEM_PADDING, rect.center().y + self.label_size.get().y / 2.0);
+            painter.text(label, label_pos, painter.theme.text, painter.theme.font_size);
+        }
+
+        let arrow_x = rect.right() - ARROW_SPACE / 2.0 - MENU_ITEM_PADDING / 2.0;
+        let arrow_y = rect.center().y + ARROW_SIZE / 2.0;
+        painter.line(pos(arrow_x - ARROW_SIZE, arrow_y - ARROW_SIZE), pos(arrow_x, arrow_y), painter.theme.text);
+        painter.line(pos(arrow_x + ARROW_SIZE, arrow_y - ARROW_SIZE), pos(arrow_x, arrow_y), painter.theme.text);
+    }
+
+}
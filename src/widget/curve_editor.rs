@@ -0,0 +1,573 @@
+
+use std::{collections::HashSet, rc::Rc};
+
+use crate::{painter::{path::PathBuilder, Painter, RectBuilder}, pos, vec2, Color, Pos, Rect, Response, Transform, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{canvas_2d::{CanvasView, ViewLimits}, timeline::{draw_key_diamond, draw_select_rect, select_rect}, LayoutContext, LayoutResult};
+
+// Limits on the points per unit of time or value
+const MIN_SCALE: f32 = 0.001;
+const MAX_SCALE: f32 = 100000.0;
+// Points per unit of time and value before there are keys to fit to
+const DEFAULT_SCALE: f32 = 100.0;
+// Grid lines are spaced at least this far apart
+const MIN_GRID_SPACING: f32 = 50.0;
+const LABEL_PADDING: f32 = 4.0;
+const KEY_RADIUS: f32 = 4.0;
+const HANDLE_RADIUS: f32 = 3.0;
+// How close the pointer must be to grab a key or handle
+const GRAB_DISTANCE: f32 = 7.0;
+// The closest two keys on a curve can get in time
+const MIN_KEY_SPACING: f32 = 0.001;
+// Steps of the bisection finding where a curve reaches a time
+const EVALUATE_STEPS: usize = 24;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TangentMode {
+    // The handles stay on opposite sides of the key, keeping the curve smooth
+    Aligned,
+    // The handles move independently, allowing sharp corners
+    Free,
+    // The handles are worked out from the neighbouring keys
+    Auto
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CurveKey {
+    pub time: f32,
+    pub value: f32,
+    // The bézier handles in (time, value), relative to the key. Ignored in auto mode.
+    pub in_handle: Vec2,
+    pub out_handle: Vec2,
+    pub mode: TangentMode
+}
+
+impl CurveKey {
+
+    // A key with auto tangents
+    pub fn new(time: f32, value: f32) -> Self {
+        Self {
+            time,
+            value,
+            in_handle: Vec2::ZERO,
+            out_handle: Vec2::ZERO,
+            mode: TangentMode::Auto
+        }
+    }
+
+    pub fn with_handles(mut self, in_handle: Vec2, out_handle: Vec2, mode: TangentMode) -> Self {
+        self.in_handle = in_handle;
+        self.out_handle = out_handle;
+        self.mode = mode;
+        self
+    }
+
+    fn pos(&self) -> Pos {
+        pos(self.time, self.value)
+    }
+
+}
+
+// Keys sorted by time, joined by bézier segments
+#[derive(Clone)]
+pub struct Curve {
+    pub color: Color,
+    pub keys: Vec<CurveKey>
+}
+
+impl Curve {
+
+    pub fn new(color: Color, keys: Vec<CurveKey>) -> Self {
+        Self {
+            color,
+            keys
+        }
+    }
+
+    // The in and out handles of a key, working out auto tangents from its neighbours
+    pub fn handles(&self, idx: usize) -> (Vec2, Vec2) {
+        let key = &self.keys[idx];
+        if key.mode != TangentMode::Auto {
+            return (key.in_handle, key.out_handle);
+        }
+        let prev = idx.checked_sub(1).map(|prev| self.keys[prev]);
+        let next = self.keys.get(idx + 1);
+        // Auto tangents are flat at the ends of the curve
+        let slope = match (prev, next) {
+            (Some(prev), Some(next)) => (next.value - prev.value) / (next.time - prev.time).max(MIN_KEY_SPACING),
+            _ => 0.0
+        };
+        let in_length = prev.map(|prev| (key.time - prev.time) / 3.0).unwrap_or(0.0);
+        let out_length = next.map(|next| (next.time - key.time) / 3.0).unwrap_or(0.0);
+        (vec2(-in_length, -in_length * slope), vec2(out_length, out_length * slope))
+    }
+
+    // The control points of the segment from key `idx` to the next, in (time, value).
+    // Handles reaching past the other key are shortened, so time only moves forwards.
+    fn segment(&self, idx: usize) -> [Pos; 4] {
+        let start = self.keys[idx];
+        let end = self.keys[idx + 1];
+        let span = end.time - start.time;
+        let out_handle = limit_handle(self.handles(idx).1, span);
+        let in_handle = limit_handle(self.handles(idx + 1).0, span);
+        [start.pos(), start.pos() + out_handle, end.pos() + in_handle, end.pos()]
+    }
+
+    // The value of the curve at a time, holding the first and last values beyond the keys
+    pub fn evaluate(&self, time: f32) -> f32 {
+        let (Some(first), Some(last)) = (self.keys.first(), self.keys.last()) else { return 0.0; };
+        if time <= first.time {
+            return first.value;
+        }
+        if time >= last.time {
+            return last.value;
+        }
+        let idx = self.keys.partition_point(|key| key.time <= time) - 1;
+        let [p0, p1, p2, p3] = self.segment(idx);
+
+        // Time doesn't move linearly along the segment, so search for the point at this time
+        let mut low = 0.0;
+        let mut high = 1.0;
+        for _ in 0..EVALUATE_STEPS {
+            let mid = (low + high) / 2.0;
+            if cubic(p0.x, p1.x, p2.x, p3.x, mid) < time {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        cubic(p0.y, p1.y, p2.y, p3.y, (low + high) / 2.0)
+    }
+
+}
+
+fn limit_handle(handle: Vec2, span: f32) -> Vec2 {
+    if handle.x.abs() > span {
+        handle * (span / handle.x.abs())
+    } else {
+        handle
+    }
+}
+
+fn cubic(a: f32, b: f32, c: f32, d: f32, t: f32) -> f32 {
+    let s = 1.0 - t;
+    s * s * s * a + 3.0 * s * s * t * b + 3.0 * s * t * t * c + t * t * t * d
+}
+
+// The spacing between grid lines for a number of points per unit, stepping ..., 0.1, 0.2, 0.5, 1, 2, 5, 10, ...
+fn grid_step(scale: f32) -> f32 {
+    let min_step = MIN_GRID_SPACING / scale;
+    let magnitude = 10.0f32.powf(min_step.log10().floor());
+    [1.0, 2.0, 5.0, 10.0].into_iter().map(|step| step * magnitude).find(|step| *step >= min_step).unwrap_or(10.0 * magnitude)
+}
+
+fn format_grid_label(value: f32, step: f32) -> String {
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    format!("{:.*}", decimals, value)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CurveKeyId {
+    pub curve: usize,
+    pub key: usize
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum HandleSide {
+    In,
+    Out
+}
+
+enum CurveDrag {
+    // Moving the selected keys, from where they were grabbed in (time, value) and where they started
+    Keys {
+        grab: Pos,
+        start: Vec<(CurveKeyId, CurveKey)>
+    },
+    Handle(CurveKeyId, HandleSide),
+    // Selecting the keys in the box from `start` to the pointer, on top of `base`
+    Select {
+        start: Pos,
+        base: HashSet<CurveKeyId>
+    }
+}
+
+pub struct CurveEditorState {
+    // Shows values growing downwards, which the editor flips
+    view: CanvasView,
+    selected: HashSet<CurveKeyId>,
+    drag: Option<CurveDrag>
+}
+
+impl Default for CurveEditorState {
+
+    fn default() -> Self {
+        let mut view = CanvasView::default();
+        view.zoom = Vec2::splat(DEFAULT_SCALE);
+        Self {
+            view,
+            selected: HashSet::new(),
+            drag: None
+        }
+    }
+
+}
+
+type OnSet<S> = Rc<dyn Fn(&mut S, CurveKeyId, CurveKey)>;
+type OnFinish<S> = Rc<dyn Fn(&mut S)>;
+type OnSelect<S> = Rc<dyn Fn(&mut S, Vec<CurveKeyId>)>;
+
+// A pannable, zoomable plot of value-over-time curves with draggable keys and bézier handles.
+// Click or box-select keys and drag them, or drag the handles of selected keys. Shift adds to the selection.
+// Middle-drag or space-drag pans, ctrl+scroll zooms around the pointer and F fits the view to the keys.
+pub struct CurveEditor<S> {
+    curves: Vec<Curve>,
+    on_set: Option<OnSet<S>>,
+    on_finish: Option<OnFinish<S>>,
+    on_select: Option<OnSelect<S>>
+}
+
+impl<S: 'static> CurveEditor<S> {
+
+    pub fn new(curves: Vec<Curve>) -> Self {
+        Self {
+            curves,
+            on_set: None,
+            on_finish: None,
+            on_select: None
+        }
+    }
+
+    // Called with the new state of each key as it is dragged. Keys keep their order in time.
+    pub fn on_set<F>(mut self, handler: F) -> Self where F: Fn(&mut S, CurveKeyId, CurveKey) + 'static {
+        self.on_set = Some(Rc::new(handler));
+        self
+    }
+
+    // Called when a drag that changed keys ends
+    pub fn on_finish<F>(mut self, handler: F) -> Self where F: Fn(&mut S) + 'static {
+        self.on_finish = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn on_select<F>(mut self, handler: F) -> Self where F: Fn(&mut S, Vec<CurveKeyId>) + 'static {
+        self.on_select = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        WidgetNode::new(self).sense_click(true)
+    }
+
+    fn view_state(widget_state: &mut WidgetState<S>) -> &mut CanvasView {
+        &mut Self::get(widget_state).view
+    }
+
+    fn key(&self, id: CurveKeyId) -> Option<&CurveKey> {
+        self.curves.get(id.curve)?.keys.get(id.key)
+    }
+
+    fn key_ids(&self) -> impl Iterator<Item = CurveKeyId> + '_ {
+        self.curves.iter().enumerate().flat_map(|(curve, data)| (0..data.keys.len()).map(move |key| CurveKeyId { curve, key }))
+    }
+
+    // The area the view fits to, in (time, -value) since the view has values growing downwards
+    fn view_bounds(&self) -> Option<Rect> {
+        let bounds = self.key_ids().filter_map(|id| self.key(id)).fold(None, |bounds: Option<Rect>, key| Some(match bounds {
+            Some(bounds) => Rect::new(pos(bounds.left().min(key.time), bounds.top().min(-key.value)), pos(bounds.right().max(key.time), bounds.bottom().max(-key.value))),
+            None => Rect::new(pos(key.time, -key.value), pos(key.time, -key.value))
+        }))?;
+        // Give flat or single keys some room
+        Some(Rect::center_size(bounds.center(), bounds.size().max(Vec2::splat(1.0))))
+    }
+
+    // The handles of a key that can be dragged. The first key has no in handle and the last no out handle.
+    fn handle_sides(&self, id: CurveKeyId) -> impl Iterator<Item = HandleSide> {
+        let len = self.curves.get(id.curve).map(|curve| curve.keys.len()).unwrap_or(0);
+        let has_in = id.key > 0 && id.key < len;
+        let has_out = id.key + 1 < len;
+        [(HandleSide::In, has_in), (HandleSide::Out, has_out)].into_iter().filter(|(_, shown)| *shown).map(|(side, _)| side)
+    }
+
+    fn handle_pos(&self, id: CurveKeyId, side: HandleSide) -> Pos {
+        let curve = &self.curves[id.curve];
+        let (in_handle, out_handle) = curve.handles(id.key);
+        curve.keys[id.key].pos() + if side == HandleSide::In { in_handle } else { out_handle }
+    }
+
+    // The selected key handle under the pointer, if any. Keys and handles are grabbed within a distance on the screen.
+    fn handle_at(&self, painter: &Painter, selected: &HashSet<CurveKeyId>, hover_pos: Pos) -> Option<(CurveKeyId, HandleSide)> {
+        selected.iter()
+            .filter(|id| self.key(**id).is_some())
+            .flat_map(|id| self.handle_sides(*id).map(move |side| (*id, side)))
+            .map(|(id, side)| ((id, side), (painter.to_screen(self.handle_pos(id, side)) - hover_pos).length()))
+            .filter(|(_, dist)| *dist <= GRAB_DISTANCE)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(handle, _)| handle)
+    }
+
+    // The key under the pointer, if any
+    fn key_at(&self, painter: &Painter, hover_pos: Pos) -> Option<CurveKeyId> {
+        self.key_ids()
+            .map(|id| (id, (painter.to_screen(self.curves[id.curve].keys[id.key].pos()) - hover_pos).length()))
+            .filter(|(_, dist)| *dist <= GRAB_DISTANCE)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(id, _)| id)
+    }
+
+    // How far in time the selection can move without passing the unselected keys beside it
+    fn time_limits(&self, selected: &HashSet<CurveKeyId>, start: &[(CurveKeyId, CurveKey)]) -> (f32, f32) {
+        let mut min = f32::NEG_INFINITY;
+        let mut max = f32::INFINITY;
+        for (id, key) in start {
+            // Keys removed by the app during the drag are skipped
+            let Some(curve) = self.curves.get(id.curve).filter(|curve| id.key < curve.keys.len()) else { continue; };
+            let unselected = |idx: &usize| !selected.contains(&CurveKeyId { curve: id.curve, key: *idx });
+            if let Some(prev) = (0..id.key).rev().find(unselected) {
+                min = min.max(curve.keys[prev].time - key.time + MIN_KEY_SPACING);
+            }
+            if let Some(next) = (id.key + 1..curve.keys.len()).find(unselected) {
+                max = max.min(curve.keys[next].time - key.time - MIN_KEY_SPACING);
+            }
+        }
+        (min, max)
+    }
+
+    fn set(&self, id: CurveKeyId, key: CurveKey, widget_state: &mut WidgetState<S>) {
+        // Skip keys that haven't changed or were removed by the app
+        let changed = self.key(id).is_some_and(|current| *current != key);
+        if let (true, Some(on_set)) = (changed, self.on_set.clone()) {
+            widget_state.message(move |state| on_set(state, id, key));
+        }
+    }
+
+    fn finish(&self, widget_state: &mut WidgetState<S>) {
+        if let Some(on_finish) = self.on_finish.clone() {
+            widget_state.message(move |state| on_finish(state));
+        }
+    }
+
+    fn send_selection(&self, widget_state: &mut WidgetState<S>) {
+        if let Some(on_select) = self.on_select.clone() {
+            let mut selected: Vec<CurveKeyId> = Self::get(widget_state).selected.iter().copied().collect();
+            selected.sort_by_key(|id| (id.curve, id.key));
+            widget_state.message(move |state| on_select(state, selected.clone()));
+        }
+    }
+
+    fn start_drag(&self, painter: &Painter, hover_pos: Pos, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let additive = resp.modifiers().shift;
+        let state = Self::get(widget_state);
+
+        if let Some((id, side)) = self.handle_at(painter, &state.selected, hover_pos) {
+            state.drag = Some(CurveDrag::Handle(id, side));
+            widget_state.request_focus();
+            return;
+        }
+
+        if let Some(id) = self.key_at(painter, hover_pos) {
+            if additive && state.selected.contains(&id) {
+                state.selected.remove(&id);
+            } else {
+                if !additive && !state.selected.contains(&id) {
+                    state.selected.clear();
+                }
+                state.selected.insert(id);
+                let start = state.selected.iter().filter_map(|id| self.key(*id).map(|key| (*id, *key))).collect();
+                state.drag = Some(CurveDrag::Keys { grab: painter.to_local(hover_pos), start });
+                widget_state.request_focus();
+            }
+            self.send_selection(widget_state);
+        } else {
+            let base = if additive { state.selected.clone() } else { HashSet::new() };
+            state.selected.clone_from(&base);
+            state.drag = Some(CurveDrag::Select {
+                start: hover_pos,
+                base
+            });
+            widget_state.request_focus();
+        }
+    }
+
+    fn update_drag(&self, painter: &mut Painter, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let state = Self::get(widget_state);
+        let released = resp.global_mouse_released();
+        let Some(drag) = &state.drag else { return; };
+
+        if let Some(mouse_pos) = resp.global_hover_pos() {
+            let mouse_curve_pos = painter.to_local(mouse_pos);
+            match drag {
+                CurveDrag::Keys { grab, start } => {
+                    let (min_time, max_time) = self.time_limits(&state.selected, start);
+                    let mut delta = mouse_curve_pos - *grab;
+                    delta.x = if min_time <= max_time { delta.x.clamp(min_time, max_time) } else { 0.0 };
+                    let moved: Vec<_> = start.iter().map(|(id, key)| (*id, CurveKey {
+                        time: key.time + delta.x,
+                        value: key.value + delta.y,
+                        ..*key
+                    })).collect();
+                    for (id, key) in moved {
+                        self.set(id, key, widget_state);
+                    }
+                },
+                CurveDrag::Handle(id, side) => {
+                    let (id, side) = (*id, *side);
+                    if let Some(key) = self.key(id) {
+                        let (in_handle, out_handle) = self.curves[id.curve].handles(id.key);
+                        let mut handle = mouse_curve_pos - key.pos();
+                        // Keep the handles pointing away from the key in time
+                        handle.x = if side == HandleSide::In { handle.x.min(0.0) } else { handle.x.max(0.0) };
+                        // Dragging an auto handle takes over its tangent
+                        let mode = if key.mode == TangentMode::Auto { TangentMode::Aligned } else { key.mode };
+                        let (mut in_handle, mut out_handle) = (in_handle, out_handle);
+                        let (moved, other) = if side == HandleSide::In { (&mut in_handle, &mut out_handle) } else { (&mut out_handle, &mut in_handle) };
+                        *moved = handle;
+                        if mode == TangentMode::Aligned && handle.length() > 0.0 {
+                            *other = handle * (-other.length() / handle.length());
+                        }
+                        self.set(id, key.with_handles(in_handle, out_handle, mode), widget_state);
+                    }
+                },
+                CurveDrag::Select { start, base } => {
+                    let select_rect = select_rect(*start, mouse_pos);
+                    let mut selected = base.clone();
+                    selected.extend(self.key_ids().filter(|id| select_rect.contains(painter.to_screen(self.curves[id.curve].keys[id.key].pos()))));
+                    state.selected = selected;
+                    if released {
+                        self.send_selection(widget_state);
+                    }
+                }
+            }
+            painter.request_redraw();
+        }
+
+        if released {
+            let drag = Self::get(widget_state).drag.take();
+            widget_state.unfocus();
+            if matches!(drag, Some(CurveDrag::Keys { .. } | CurveDrag::Handle(..))) {
+                self.finish(widget_state);
+            }
+        }
+    }
+
+    // Grid lines and their labels stay crisp and evenly sized, so they're drawn on the screen at the view's positions
+    fn draw_grid(&self, painter: &mut Painter, rect: Rect, view: &Transform, scale: Vec2) {
+        let Some(inverse) = view.inverse() else { return; };
+        let visible = inverse.apply_rect(rect);
+        let font_size = painter.theme.font_size;
+
+        let time_step = grid_step(scale.x);
+        for idx in (visible.left() / time_step).ceil() as i64..=(visible.right() / time_step).floor() as i64 {
+            let time = idx as f32 * time_step;
+            let x = view.apply(pos(time, 0.0)).x;
+            let color = if time.abs() < time_step / 2.0 { painter.theme.bg_light.lighten(0.2) } else { painter.theme.bg_light };
+            painter.line(pos(x, rect.top()), pos(x, rect.bottom()), color);
+            painter.text(&format_grid_label(time, time_step), pos(x + LABEL_PADDING, rect.bottom() - LABEL_PADDING), painter.theme.text, font_size);
+        }
+
+        let value_step = grid_step(scale.y);
+        for idx in (visible.top() / value_step).ceil() as i64..=(visible.bottom() / value_step).floor() as i64 {
+            let value = idx as f32 * value_step;
+            let y = view.apply(pos(0.0, value)).y;
+            let color = if value.abs() < value_step / 2.0 { painter.theme.bg_light.lighten(0.2) } else { painter.theme.bg_light };
+            painter.line(pos(rect.left(), y), pos(rect.right(), y), color);
+            painter.text(&format_grid_label(value, value_step), pos(rect.left() + LABEL_PADDING, y - LABEL_PADDING), painter.theme.text, font_size);
+        }
+    }
+
+    // Drawn in (time, value) under the view's transform. `point` is the size of a screen point on each axis.
+    fn draw_curve(&self, painter: &mut Painter, visible: Rect, point: Vec2, curve: &Curve) {
+        let (Some(first), Some(last)) = (curve.keys.first(), curve.keys.last()) else { return; };
+        let stroke_width = 1.0 / painter.transform().average_scale();
+
+        // The values held before the first key and after the last
+        let held = curve.color.with_alpha(0.5);
+        let dash = 4.0 * point.x;
+        painter.path(PathBuilder::line(pos(visible.left(), first.value), first.pos()).stroke(held).stroke_width(stroke_width).dashed(dash, dash));
+        painter.path(PathBuilder::line(last.pos(), pos(visible.right(), last.value)).stroke(held).stroke_width(stroke_width).dashed(dash, dash));
+
+        let mut path = PathBuilder::new().move_to(first.pos());
+        for idx in 0..curve.keys.len() - 1 {
+            let [_, ctrl1, ctrl2, end] = curve.segment(idx);
+            path = path.cubic_to(ctrl1, ctrl2, end);
+        }
+        painter.path(path.stroke(curve.color).stroke_width(2.0 * stroke_width));
+    }
+
+}
+
+impl<S: 'static> Widget<S> for CurveEditor<S> {
+
+    type State = CurveEditorState;
+
+    fn layout(&self, max_size: Vec2, _ctx: &mut LayoutContext, _state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let size = vec2(
+            if max_size.x.is_finite() { max_size.x } else { 400.0 },
+            if max_size.y.is_finite() { max_size.y } else { 300.0 }
+        );
+        LayoutResult::new(size)
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let limits = ViewLimits {
+            zoom_range: MIN_SCALE..=MAX_SCALE,
+            content_bounds: self.view_bounds(),
+            keep_aspect: false
+        };
+        // Dragging keys or a box takes the left button, so the view can't start panning
+        let dragging = Self::get(widget_state).drag.is_some();
+        let panning = !dragging && CanvasView::handle_input(widget_state, Self::view_state, &limits, rect, painter, resp);
+
+        let focused = widget_state.focused();
+        let state = Self::get(widget_state);
+        let scale = state.view.zoom;
+        // Maps (time, value) to the screen, with values growing upwards
+        let view = Transform::translate(rect.min().to_vec()) * state.view.transform() * Transform::scale_xy(vec2(1.0, -1.0));
+        painter.push_clip_rect(rect);
+        painter.rect(RectBuilder::new(rect).fill(painter.theme.bg_dark));
+        self.draw_grid(painter, rect, &view, scale);
+        painter.push_transform(view);
+
+        if !panning {
+            if focused && dragging {
+                self.update_drag(painter, resp, widget_state);
+            } else if let (true, Some(hover_pos)) = (resp.mouse_clicked(), resp.hover_pos()) {
+                self.start_drag(painter, hover_pos, resp, widget_state);
+                painter.request_redraw();
+            }
+        }
+
+        let visible = view.inverse().map_or(rect, |inverse| inverse.apply_rect(rect));
+        let point = vec2(1.0 / scale.x, 1.0 / scale.y);
+        for curve in &self.curves {
+            self.draw_curve(painter, visible, point, curve);
+        }
+
+        // Keys and handles keep their size at any zoom, so they're drawn on the screen at their transformed positions
+        let state = Self::get(widget_state);
+        let transformed: &Painter = painter;
+        let key_screen_pos = |id: CurveKeyId| transformed.to_screen(self.curves[id.curve].keys[id.key].pos());
+        let handles: Vec<(Pos, Pos)> = state.selected.iter()
+            .filter(|id| self.key(**id).is_some())
+            .flat_map(|id| self.handle_sides(*id).map(move |side| (key_screen_pos(*id), transformed.to_screen(self.handle_pos(*id, side)))))
+            .collect();
+        let keys: Vec<(Pos, Color)> = self.key_ids()
+            .map(|id| (key_screen_pos(id), if state.selected.contains(&id) { transformed.theme.accent } else { self.curves[id.curve].color }))
+            .collect();
+        painter.pop_transform();
+
+        for (key_pos, handle_pos) in handles {
+            painter.line(key_pos, handle_pos, painter.theme.text);
+            painter.path(PathBuilder::circle(handle_pos, HANDLE_RADIUS).fill(painter.theme.bg_dark).stroke(painter.theme.text));
+        }
+        for (center, color) in keys {
+            draw_key_diamond(painter, center, KEY_RADIUS, color);
+        }
+
+        if let (Some(CurveDrag::Select { start, .. }), Some(mouse_pos)) = (&state.drag, resp.global_hover_pos()) {
+            draw_select_rect(painter, *start, mouse_pos);
+        }
+        painter.pop_clip_rect();
+    }
+
+}
@@ -0,0 +1,118 @@
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{painter::{Painter, RectBuilder}, pos, vec2, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{button::Button, key::Key, text::Text, LayoutContext, LayoutResult};
+
+const TITLE_BAR_HEIGHT: f32 = 30.0;
+const DIALOG_MIN_WIDTH: f32 = 300.0;
+
+type OnClose<S> = Rc<dyn Fn(&mut S)>;
+
+// A window with a title, a body and a row of buttons. Show it with `Modal`.
+pub struct Dialog<S> {
+    title: String,
+    title_size: RefCell<Vec2>,
+    body: WidgetNode<S>,
+    buttons: Vec<WidgetNode<S>>,
+    on_close: Option<OnClose<S>>
+}
+
+impl<S: 'static> Dialog<S> {
+
+    pub fn new<T: Into<String>>(title: T, body: WidgetNode<S>) -> Self {
+        Self {
+            title: title.into(),
+            title_size: RefCell::new(Vec2::ZERO),
+            body,
+            buttons: Vec::new(),
+            on_close: None
+        }
+    }
+
+    pub fn button<T, F>(mut self, label: T, handler: F) -> Self where T: Into<String>, F: Fn(&mut S) + 'static {
+        self.buttons.push(Button::new(Text::new(label)).on_click(handler));
+        self
+    }
+
+    // Called when the user dismisses the dialog with Escape
+    pub fn on_close<F>(mut self, handler: F) -> Self where F: Fn(&mut S) + 'static {
+        self.on_close = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        WidgetNode::new(self).sense_click(true)
+    }
+
+}
+
+impl<S: 'static> Widget<S> for Dialog<S> {
+
+    type State = ();
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let padding = ctx.theme.item_spacing;
+        let title_size = ctx.text_shaper.measure_text(ctx.theme.font_size, &self.title);
+        *self.title_size.borrow_mut() = title_size;
+
+        let inner_max_size = (max_size - Vec2::splat(2.0 * padding) - vec2(0.0, TITLE_BAR_HEIGHT)).max(Vec2::ZERO);
+        let body_layout = self.body.layout(inner_max_size, ctx, state);
+        let body_size = body_layout.size();
+
+        let button_max_size = vec2(inner_max_size.x, (inner_max_size.y - body_size.y - padding).max(0.0));
+        let mut button_layouts = Vec::new();
+        let mut buttons_width: f32 = 0.0;
+        let mut buttons_height: f32 = 0.0;
+        for button in &self.buttons {
+            let button_layout = button.layout(button_max_size, ctx, state);
+            buttons_width += button_layout.size().x + padding;
+            buttons_height = buttons_height.max(button_layout.size().y);
+            button_layouts.push(button_layout);
+        }
+        buttons_width = (buttons_width - padding).max(0.0);
+
+        let inner_width = body_size.x.max(buttons_width).max(title_size.x).max(DIALOG_MIN_WIDTH - 2.0 * padding);
+        let buttons_y = TITLE_BAR_HEIGHT + padding + body_size.y + if button_layouts.is_empty() { 0.0 } else { padding };
+        let size = vec2(inner_width + 2.0 * padding, buttons_y + buttons_height + padding).min(max_size);
+
+        let mut layout = LayoutResult::new(size);
+        layout.add_child(vec2(padding, TITLE_BAR_HEIGHT + padding), body_layout);
+
+        // Buttons are aligned to the right edge of the dialog
+        let mut button_x = size.x - padding - buttons_width;
+        for button_layout in button_layouts {
+            let width = button_layout.size().x;
+            layout.add_child(vec2(button_x, buttons_y), button_layout);
+            button_x += width + padding;
+        }
+
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, state: &mut WidgetState<S>) {
+        if resp.key_pressed(Key::Escape) {
+            if let Some(on_close) = &self.on_close {
+                let on_close = on_close.clone();
+                state.message(move |state| {
+                    on_close(state);
+                });
+            }
+        }
+
+        painter.rect(RectBuilder::new(rect)
+            .fill(painter.theme.bg_window)
+            .stroke(painter.theme.stroke)
+            .rounding(painter.theme.rounding));
+
+        let title_size = *self.title_size.borrow();
+        let title_pos = pos(rect.left() + painter.theme.item_spacing, rect.top() + (TITLE_BAR_HEIGHT + title_size.y) / 2.0);
+        painter.text(&self.title, title_pos, painter.theme.text, painter.theme.font_size);
+        painter.line(
+            pos(rect.left(), rect.top() + TITLE_BAR_HEIGHT),
+            pos(rect.right(), rect.top() + TITLE_BAR_HEIGHT),
+            painter.theme.stroke);
+    }
+
+}
@@ -0,0 +1,499 @@
+
+use std::{cell::RefCell, collections::hash_map::DefaultHasher, fmt::Display, hash::{Hash, Hasher}, rc::Rc};
+
+use crate::{painter::{cursor::Cursor, Painter, RectBuilder}, pos, vec2, Axis, Pos, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+
+use self::window::DockWindow;
+use super::{floating_window::FloatingWindow, tabs::{draw_tab, draw_tab_strip, DRAG_THRESHOLD, TAB_HEIGHT, TAB_PADDING}, LayoutContext, LayoutResult};
+
+mod tree;
+mod save;
+mod window;
+
+pub use tree::{DockLayout, DockNode, DockZone, FloatingPanel};
+
+const SEPARATOR_SENSOR_SIZE: f32 = 8.0;
+const DROP_MARKER_SIZE: f32 = 28.0;
+const DEFAULT_FLOATING_SIZE: Vec2 = vec2(300.0, 200.0);
+
+type OnChange<S, P> = Rc<dyn Fn(&mut S, DockLayout<P>)>;
+
+// Panels keep their widget state wherever they are docked
+fn panel_id<P: Hash>(panel: &P) -> usize {
+    let mut hasher = DefaultHasher::new();
+    panel.hash(&mut hasher);
+    hasher.finish() as usize
+}
+
+fn emit_change<S: 'static, P: Clone + 'static, F>(state: &mut WidgetState<S>, on_change: &Option<OnChange<S, P>>, layout: &DockLayout<P>, change: F) where F: FnOnce(&mut DockLayout<P>) {
+    if let Some(on_change) = on_change {
+        let on_change = on_change.clone();
+        let mut new_layout = layout.clone();
+        change(&mut new_layout);
+        state.message(move |state| {
+            on_change(state, new_layout.clone());
+        });
+    }
+}
+
+// A docked panel group that dragged panels can be dropped onto
+struct DropTarget<P> {
+    panels: Vec<P>,
+    rect: Rect
+}
+
+fn drop_markers(rect: Rect) -> [(DockZone, Rect); 5] {
+    let center = rect.center();
+    let spacing = DROP_MARKER_SIZE + 4.0;
+    let marker = |offset: Vec2| Rect::center_size(center + offset, Vec2::splat(DROP_MARKER_SIZE));
+    [
+        (DockZone::Center, marker(Vec2::ZERO)),
+        (DockZone::Left, marker(vec2(-spacing, 0.0))),
+        (DockZone::Right, marker(vec2(spacing, 0.0))),
+        (DockZone::Top, marker(vec2(0.0, -spacing))),
+        (DockZone::Bottom, marker(vec2(0.0, spacing)))
+    ]
+}
+
+// The part of a group a panel dropped into a zone will take up
+fn zone_preview(rect: Rect, zone: DockZone) -> Rect {
+    let half = rect.size() / 2.0;
+    match zone {
+        DockZone::Left => Rect::min_size(rect.min(), vec2(half.x, rect.height())),
+        DockZone::Right => Rect::min_size(rect.min() + vec2(half.x, 0.0), vec2(half.x, rect.height())),
+        DockZone::Top => Rect::min_size(rect.min(), vec2(rect.width(), half.y)),
+        DockZone::Bottom => Rect::min_size(rect.min() + vec2(0.0, half.y), vec2(rect.width(), half.y)),
+        DockZone::Center => rect
+    }
+}
+
+fn hovered_target<P>(targets: &[DropTarget<P>], mouse_pos: Pos) -> Option<&DropTarget<P>> {
+    targets.iter().find(|target| target.rect.contains(mouse_pos))
+}
+
+// Find the panel and zone to dock into if a dragged panel was dropped at the mouse position
+fn find_drop<P: Clone + PartialEq>(targets: &[DropTarget<P>], mouse_pos: Pos, dragged: &P) -> Option<(P, DockZone, Rect)> {
+    let target = hovered_target(targets, mouse_pos)?;
+    let panel = target.panels.iter().find(|panel| *panel != dragged)?;
+    let (zone, _) = drop_markers(target.rect).into_iter().find(|(_, marker)| marker.contains(mouse_pos))?;
+    Some((panel.clone(), zone, zone_preview(target.rect, zone)))
+}
+
+fn draw_drop_zones<P: Clone + PartialEq>(painter: &mut Painter, targets: &[DropTarget<P>], mouse_pos: Pos, dragged: &P) {
+    let target = if let Some(target) = hovered_target(targets, mouse_pos) {
+        target
+    } else {
+        return;
+    };
+    if !target.panels.iter().any(|panel| panel != dragged) {
+        return;
+    }
+
+    let mut preview_color = painter.theme.button;
+    preview_color.a = 0.35;
+    if let Some((_, _, preview)) = find_drop(targets, mouse_pos, dragged) {
+        painter.rect(RectBuilder::new(preview).fill(preview_color).rounding(painter.theme.rounding));
+    }
+    for (zone, marker) in drop_markers(target.rect) {
+        let color = if marker.contains(mouse_pos) { painter.theme.button } else { painter.theme.bg_window };
+        painter.rect(RectBuilder::new(marker).fill(color).stroke(painter.theme.stroke).rounding(painter.theme.rounding / 2.0));
+        let inner = zone_preview(Rect::center_size(marker.center(), Vec2::splat(DROP_MARKER_SIZE - 10.0)), zone);
+        painter.rect(RectBuilder::new(inner).fill(painter.theme.text));
+    }
+}
+
+struct TabGeometry<P> {
+    panel: P,
+    label: String,
+    label_size: Vec2,
+    rect: Rect
+}
+
+struct GroupGeometry<P> {
+    rect: Rect,
+    tabs: Vec<TabGeometry<P>>,
+    active: usize
+}
+
+struct SeparatorGeometry {
+    // Child indices leading to the split
+    path: Vec<usize>,
+    // The separator lies between child `idx` and `idx + 1`
+    idx: usize,
+    axis: Axis,
+    split_rect: Rect,
+    line_start: Pos,
+    line_end: Pos
+}
+
+#[derive(Clone, Copy)]
+enum DockSensor {
+    Tab {
+        group: usize,
+        tab: usize
+    },
+    Separator(usize)
+}
+
+enum DockDrag<P> {
+    None,
+    Tab {
+        panel: P,
+        start: Pos,
+        dragging: bool
+    },
+    // The separator between child `idx` and `idx + 1` of the split at `path`, which stays put when the layout changes
+    Separator {
+        path: Vec<usize>,
+        idx: usize
+    }
+}
+
+// Geometry is relative to the top left of the dock area
+pub struct DockAreaState<P> {
+    groups: Vec<GroupGeometry<P>>,
+    separators: Vec<SeparatorGeometry>,
+    sensors: Vec<DockSensor>,
+    drag: DockDrag<P>
+}
+
+impl<P> Default for DockAreaState<P> {
+
+    fn default() -> Self {
+        Self {
+            groups: Vec::new(),
+            separators: Vec::new(),
+            sensors: Vec::new(),
+            drag: DockDrag::None
+        }
+    }
+
+}
+
+// Panels arranged in tab groups and splits that the user can rearrange by dragging tabs.
+// The arrangement lives in the app state as a `DockLayout`.
+pub struct DockArea<S, P> {
+    layout: Rc<DockLayout<P>>,
+    // The active panel of each docked group
+    contents: Vec<(P, WidgetNode<S>)>,
+    floating_contents: Vec<WidgetNode<S>>,
+    windows: Vec<WidgetNode<S>>,
+    on_change: Option<OnChange<S, P>>,
+    targets: Rc<RefCell<Vec<DropTarget<P>>>>
+}
+
+impl<S, P> DockArea<S, P> where S: 'static, P: Clone + PartialEq + Hash + Display + 'static {
+
+    pub fn new<F>(layout: &DockLayout<P>, panel: F) -> Self where F: Fn(&P) -> WidgetNode<S> {
+        let mut contents = Vec::new();
+        let mut groups = Vec::new();
+        if let Some(root) = &layout.root {
+            Self::collect_groups(root, &mut groups);
+        }
+        for (panels, active) in groups {
+            if let Some(active_panel) = panels.get(active) {
+                contents.push((active_panel.clone(), panel(active_panel).id(panel_id(active_panel))));
+            }
+        }
+
+        Self {
+            layout: Rc::new(layout.clone()),
+            contents,
+            floating_contents: layout.floating.iter().map(|floating| panel(&floating.panel).id(panel_id(&floating.panel))).collect(),
+            windows: Vec::new(),
+            on_change: None,
+            targets: Rc::new(RefCell::new(Vec::new()))
+        }
+    }
+
+    fn collect_groups<'a>(node: &'a DockNode<P>, groups: &mut Vec<(&'a Vec<P>, usize)>) {
+        match node {
+            DockNode::Tabs { panels, active } => groups.push((panels, *active)),
+            DockNode::Split { children, .. } => {
+                for (_, child) in children {
+                    Self::collect_groups(child, groups);
+                }
+            }
+        }
+    }
+
+    // Called with the new layout whenever the user rearranges the panels
+    pub fn on_change<F>(mut self, handler: F) -> Self where F: Fn(&mut S, DockLayout<P>) + 'static {
+        self.on_change = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn build(mut self) -> WidgetNode<S> {
+        let floating_contents = std::mem::take(&mut self.floating_contents);
+        for (idx, contents) in floating_contents.into_iter().enumerate() {
+            let panel = self.layout.floating[idx].panel.clone();
+            let id = !panel_id(&panel);
+            let floating = &self.layout.floating[idx];
+            let window = FloatingWindow::new(panel.to_string(), contents).default_pos(floating.pos).default_size(floating.size);
+            self.windows.push(WidgetNode::new(DockWindow {
+                panel,
+                floating_idx: idx,
+                window,
+                layout: self.layout.clone(),
+                on_change: self.on_change.clone(),
+                targets: self.targets.clone()
+            }).id(id).sense_click(true));
+        }
+        WidgetNode::new(self)
+    }
+
+    fn layout_node<'ui>(&'ui self, node: &DockNode<P>, rect: Rect, path: &mut Vec<usize>, ctx: &mut LayoutContext, widget_state: &mut WidgetState<S>, layout: &mut LayoutResult<'ui, S>) {
+        match node {
+            DockNode::Tabs { panels, active } => {
+                let state = Self::get(widget_state);
+                let group_idx = state.groups.len();
+                let mut tabs = Vec::new();
+                let mut tab_x = rect.left();
+                for (tab_idx, panel) in panels.iter().enumerate() {
+                    let label = panel.to_string();
+                    let label_size = ctx.text_shaper.measure_text(ctx.theme.font_size, &label);
+                    let width = (label_size.x + 2.0 * TAB_PADDING).min(rect.right() - tab_x).max(0.0);
+                    let tab_rect = Rect::min_size(pos(tab_x, rect.top()), vec2(width, TAB_HEIGHT.min(rect.height())));
+                    layout.add_sensor(tab_rect.min().to_vec(), tab_rect.size());
+                    state.sensors.push(DockSensor::Tab { group: group_idx, tab: tab_idx });
+                    tabs.push(TabGeometry {
+                        panel: panel.clone(),
+                        label,
+                        label_size,
+                        rect: tab_rect
+                    });
+                    tab_x += width;
+                }
+                state.groups.push(GroupGeometry {
+                    rect,
+                    tabs,
+                    active: *active
+                });
+
+                let content_size = vec2(rect.width(), (rect.height() - TAB_HEIGHT).max(0.0));
+                let active_panel = panels.get(*active);
+                if let Some((_, contents)) = self.contents.iter().find(|(panel, _)| Some(panel) == active_panel) {
+                    let contents_layout = contents.layout(content_size, ctx, widget_state);
+                    layout.add_child(rect.min().to_vec() + vec2(0.0, TAB_HEIGHT), contents_layout);
+                }
+            },
+            DockNode::Split { axis, children } => {
+                let axis = *axis;
+                let mut child_min = rect.min();
+                for (idx, (fraction, child)) in children.iter().enumerate() {
+                    let child_size = axis.unit() * (rect.dimension(axis) * fraction) + axis.other().unit() * rect.dimension(axis.other());
+                    let child_rect = Rect::min_size(child_min, child_size);
+                    path.push(idx);
+                    self.layout_node(child, child_rect, path, ctx, widget_state, layout);
+                    path.pop();
+                    child_min += axis.unit() * child_size.axis(axis);
+
+                    if idx + 1 < children.len() {
+                        let line_start = child_min;
+                        let line_end = child_min + axis.other().unit() * rect.dimension(axis.other());
+                        layout.add_sensor(line_start.to_vec() - axis.unit() * SEPARATOR_SENSOR_SIZE / 2.0, axis.unit() * SEPARATOR_SENSOR_SIZE + axis.other().unit() * rect.dimension(axis.other()));
+                        let state = Self::get(widget_state);
+                        state.sensors.push(DockSensor::Separator(state.separators.len()));
+                        state.separators.push(SeparatorGeometry {
+                            path: path.clone(),
+                            idx,
+                            axis,
+                            split_rect: rect,
+                            line_start,
+                            line_end
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    // The strip of tabs along the top of a group
+    fn tab_strip(group_rect: Rect) -> Rect {
+        Rect::min_size(group_rect.min(), vec2(group_rect.width(), TAB_HEIGHT.min(group_rect.height())))
+    }
+
+    fn handle_tab_drag(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let state = Self::get(widget_state);
+        let (panel, start, dragging) = if let DockDrag::Tab { panel, start, dragging } = &mut state.drag {
+            (panel.clone(), *start, dragging)
+        } else {
+            return;
+        };
+        let mouse_pos = resp.global_hover_pos();
+        if let Some(mouse_pos) = mouse_pos {
+            if (mouse_pos - start).length() > DRAG_THRESHOLD {
+                *dragging = true;
+            }
+        }
+        let dragging = *dragging;
+        if dragging {
+            painter.request_redraw();
+        }
+
+        if !resp.global_mouse_released() {
+            return;
+        }
+        state.drag = DockDrag::None;
+        widget_state.unfocus();
+        if !dragging {
+            emit_change(widget_state, &self.on_change, &self.layout, |layout| layout.set_active(&panel));
+            return;
+        }
+        let Some(mouse_pos) = mouse_pos else { return; };
+        if let Some((target, zone, _)) = find_drop(&self.targets.borrow(), mouse_pos, &panel) {
+            emit_change(widget_state, &self.on_change, &self.layout, |layout| layout.dock(panel, &target, zone));
+            return;
+        }
+        // Tabs dropped back onto their own strip stay where they are, while dropping anywhere else floats the panel
+        let state = Self::get(widget_state);
+        let on_strip = state.groups.iter()
+            .find(|group| group.tabs.iter().any(|tab| tab.panel == panel))
+            .is_some_and(|group| Self::tab_strip(group.rect.shift(rect.min().to_vec())).contains(mouse_pos));
+        if !on_strip {
+            emit_change(widget_state, &self.on_change, &self.layout, |layout| {
+                layout.undock(panel, mouse_pos - rect.min() - vec2(DEFAULT_FLOATING_SIZE.x / 2.0, TAB_HEIGHT / 2.0), DEFAULT_FLOATING_SIZE);
+            });
+        }
+    }
+
+    fn handle_separator_drag(&self, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let state = Self::get(widget_state);
+        let DockDrag::Separator { path, idx } = &state.drag else { return; };
+        // The split may have been removed by a change to the layout during the drag
+        let Some(separator) = state.separators.iter().find(|separator| separator.path == *path && separator.idx == *idx) else {
+            state.drag = DockDrag::None;
+            widget_state.unfocus();
+            return;
+        };
+        let axis = separator.axis;
+        let path = separator.path.clone();
+        let idx = separator.idx;
+        let split_rect = separator.split_rect.shift(rect.min().to_vec());
+
+        if resp.global_mouse_released() {
+            state.drag = DockDrag::None;
+            widget_state.unfocus();
+            return;
+        }
+        let mouse_pos = if let Some(mouse_pos) = resp.global_hover_pos() {
+            mouse_pos
+        } else {
+            return;
+        };
+
+        let t = (mouse_pos.axis(axis) - split_rect.min().axis(axis)) / split_rect.dimension(axis);
+        emit_change(widget_state, &self.on_change, &self.layout, |layout| {
+            let split = layout.root.as_mut().and_then(|root| root.node_mut(&path));
+            let Some(DockNode::Split { children, .. }) = split else { return; };
+            if idx + 1 >= children.len() {
+                return;
+            }
+            let before = children[..idx].iter().map(|(fraction, _)| *fraction).sum::<f32>();
+            let total = children[idx].0 + children[idx + 1].0;
+            let fraction = (t - before).clamp(0.05 * total, 0.95 * total);
+            children[idx].0 = fraction;
+            children[idx + 1].0 = total - fraction;
+        });
+    }
+
+}
+
+impl<S, P> Widget<S> for DockArea<S, P> where S: 'static, P: Clone + PartialEq + Hash + Display + 'static {
+
+    type State = DockAreaState<P>;
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, widget_state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let mut layout = LayoutResult::new(max_size);
+
+        let state = Self::get(widget_state);
+        state.groups.clear();
+        state.separators.clear();
+        state.sensors.clear();
+        if let Some(root) = &self.layout.root {
+            self.layout_node(root, Rect::min_size(Pos::ZERO, max_size), &mut Vec::new(), ctx, widget_state, &mut layout);
+        }
+
+        for window in &self.windows {
+            let window_layout = window.layout(max_size, ctx, widget_state);
+            let window_state = DockWindow::<S, P>::get(widget_state.get_child(window_layout.local_id));
+            let pos = window_state.pos.min(max_size - window_layout.size()).max(Vec2::ZERO);
+            layout.add_popover(pos, window_layout);
+        }
+
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let focused = widget_state.focused();
+        let state = Self::get(widget_state);
+        let hovered_sensor = resp.sensor_idx().and_then(|idx| state.sensors.get(idx)).copied();
+
+        for (group_idx, group) in state.groups.iter().enumerate() {
+            draw_tab_strip(painter, Self::tab_strip(group.rect.shift(rect.min().to_vec())), |painter| {
+                for (tab_idx, tab) in group.tabs.iter().enumerate() {
+                    let hovered = matches!(hovered_sensor, Some(DockSensor::Tab { group, tab }) if group == group_idx && tab == tab_idx);
+                    draw_tab(painter, tab.rect.shift(rect.min().to_vec()), &tab.label, tab.label_size, tab_idx == group.active, hovered);
+                }
+            });
+        }
+        for separator in &state.separators {
+            painter.line(separator.line_start + rect.min().to_vec(), separator.line_end + rect.min().to_vec(), painter.theme.stroke);
+        }
+
+        // Share the groups with the floating windows so they can be docked too
+        *self.targets.borrow_mut() = state.groups.iter().map(|group| DropTarget {
+            panels: group.tabs.iter().map(|tab| tab.panel.clone()).collect(),
+            rect: group.rect.shift(rect.min().to_vec())
+        }).collect();
+
+        if let Some(separator) = hovered_sensor.and_then(|sensor| match sensor {
+            DockSensor::Separator(separator) => state.separators.get(separator),
+            DockSensor::Tab { .. } => None
+        }) {
+            painter.cursor = match separator.axis {
+                Axis::X => Cursor::ColResize,
+                Axis::Y => Cursor::RowResize
+            };
+        }
+
+        if focused {
+            self.handle_tab_drag(painter, rect, resp, widget_state);
+            self.handle_separator_drag(rect, resp, widget_state);
+        } else if resp.mouse_clicked() {
+            let state = Self::get(widget_state);
+            match (hovered_sensor, resp.hover_pos()) {
+                (Some(DockSensor::Tab { group, tab }), Some(hover_pos)) => {
+                    state.drag = DockDrag::Tab {
+                        panel: state.groups[group].tabs[tab].panel.clone(),
+                        start: hover_pos,
+                        dragging: false
+                    };
+                    widget_state.request_focus();
+                },
+                (Some(DockSensor::Separator(separator)), _) => {
+                    if let Some(separator) = state.separators.get(separator) {
+                        state.drag = DockDrag::Separator {
+                            path: separator.path.clone(),
+                            idx: separator.idx
+                        };
+                        widget_state.request_focus();
+                    }
+                },
+                _ => {}
+            }
+        }
+    }
+
+    fn post_draw(&self, painter: &mut Painter, _rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let state = Self::get(widget_state);
+        if let (DockDrag::Tab { panel, dragging: true, .. }, Some(mouse_pos)) = (&state.drag, resp.global_hover_pos()) {
+            draw_drop_zones(painter, &self.targets.borrow(), mouse_pos, panel);
+
+            let label = panel.to_string();
+            let label_pos = mouse_pos + vec2(12.0, 0.0);
+            painter.text(&label, label_pos, painter.theme.text, painter.theme.font_size);
+        }
+    }
+
+}
@@ -0,0 +1,289 @@
+
+use std::{fmt::{Display, Write}, str::FromStr};
+
+use crate::{vec2, Axis};
+
+use super::tree::{DockLayout, DockNode, FloatingPanel};
+
+/*
+    Layouts are saved as s-expressions, with panels written as quoted strings:
+
+    (dock (split x (0.3 (tabs 0 "Layers")) (0.7 (tabs 1 "Scene" "Timeline"))) (float 100 80 300 200 "Library"))
+
+    A layout with nothing docked has `none` instead of a root node.
+*/
+
+fn write_panel<P: Display>(out: &mut String, panel: &P) {
+    out.push('"');
+    for c in panel.to_string().chars() {
+        if c == '"' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+}
+
+fn write_node<P: Display>(out: &mut String, node: &DockNode<P>) {
+    match node {
+        DockNode::Tabs { panels, active } => {
+            let _ = write!(out, "(tabs {}", active);
+            for panel in panels {
+                out.push(' ');
+                write_panel(out, panel);
+            }
+            out.push(')');
+        },
+        DockNode::Split { axis, children } => {
+            out.push_str(match axis {
+                Axis::X => "(split x",
+                Axis::Y => "(split y"
+            });
+            for (fraction, child) in children {
+                let _ = write!(out, " ({} ", fraction);
+                write_node(out, child);
+                out.push(')');
+            }
+            out.push(')');
+        }
+    }
+}
+
+impl<P: Display> DockLayout<P> {
+
+    pub fn save(&self) -> String {
+        let mut out = String::from("(dock ");
+        match &self.root {
+            Some(root) => write_node(&mut out, root),
+            None => out.push_str("none")
+        }
+        for floating in &self.floating {
+            let _ = write!(out, " (float {} {} {} {} ", floating.pos.x, floating.pos.y, floating.size.x, floating.size.y);
+            write_panel(&mut out, &floating.panel);
+            out.push(')');
+        }
+        out.push(')');
+        out
+    }
+
+}
+
+#[derive(PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Atom(String),
+    Str(String)
+}
+
+fn tokenize(data: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = data.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '"' => {
+                let mut str = String::new();
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => str.push(chars.next()?),
+                        c => str.push(c)
+                    }
+                }
+                tokens.push(Token::Str(str));
+            },
+            c if c.is_whitespace() => {},
+            c => {
+                let mut atom = String::from(c);
+                while let Some(next) = chars.peek() {
+                    if next.is_whitespace() || *next == '(' || *next == ')' || *next == '"' {
+                        break;
+                    }
+                    atom.push(*next);
+                    chars.next();
+                }
+                tokens.push(Token::Atom(atom));
+            }
+        }
+    }
+    Some(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    idx: usize
+}
+
+impl Parser {
+
+    fn next(&mut self) -> Option<&Token> {
+        self.idx += 1;
+        self.tokens.get(self.idx - 1)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.idx)
+    }
+
+    fn expect(&mut self, token: Token) -> Option<()> {
+        (self.next()? == &token).then_some(())
+    }
+
+    fn atom(&mut self) -> Option<&str> {
+        match self.next()? {
+            Token::Atom(atom) => Some(atom),
+            _ => None
+        }
+    }
+
+    fn number<N: FromStr>(&mut self) -> Option<N> {
+        self.atom()?.parse().ok()
+    }
+
+    // A finite coordinate or size
+    fn coord(&mut self) -> Option<f32> {
+        self.number().filter(|coord: &f32| coord.is_finite())
+    }
+
+    fn panel<P: FromStr>(&mut self) -> Option<P> {
+        match self.next()? {
+            Token::Str(str) => str.parse().ok(),
+            _ => None
+        }
+    }
+
+    fn node<P: FromStr>(&mut self) -> Option<DockNode<P>> {
+        self.expect(Token::Open)?;
+        let node = match self.atom()? {
+            "tabs" => {
+                let active = self.number()?;
+                let mut panels = Vec::new();
+                while self.peek()? != &Token::Close {
+                    panels.push(self.panel()?);
+                }
+                DockNode::Tabs { panels, active }
+            },
+            "split" => {
+                let axis = match self.atom()? {
+                    "x" => Axis::X,
+                    "y" => Axis::Y,
+                    _ => return None
+                };
+                let mut children = Vec::new();
+                while self.peek()? != &Token::Close {
+                    self.expect(Token::Open)?;
+                    let fraction = self.number().filter(|fraction: &f32| fraction.is_finite() && *fraction >= 0.0)?;
+                    children.push((fraction, self.node()?));
+                    self.expect(Token::Close)?;
+                }
+                DockNode::Split { axis, children }
+            },
+            _ => return None
+        };
+        self.expect(Token::Close)?;
+        Some(node)
+    }
+
+    fn layout<P: FromStr + Clone + PartialEq>(&mut self) -> Option<DockLayout<P>> {
+        self.expect(Token::Open)?;
+        if self.atom()? != "dock" {
+            return None;
+        }
+        let root = if self.peek()? == &Token::Atom("none".to_owned()) {
+            self.next();
+            None
+        } else {
+            Some(self.node()?)
+        };
+
+        let mut floating = Vec::new();
+        while self.peek()? != &Token::Close {
+            self.expect(Token::Open)?;
+            if self.atom()? != "float" {
+                return None;
+            }
+            let pos = vec2(self.coord()?, self.coord()?);
+            let size = vec2(self.coord()?, self.coord()?);
+            floating.push(FloatingPanel { panel: self.panel()?, pos, size });
+            self.expect(Token::Close)?;
+        }
+        self.expect(Token::Close)?;
+
+        // A panel can only be in one place
+        let mut panels = root.as_ref().map(DockNode::panels).unwrap_or_default();
+        panels.extend(floating.iter().map(|floating| &floating.panel));
+        if panels.iter().enumerate().any(|(idx, panel)| panels[..idx].contains(panel)) {
+            return None;
+        }
+
+        Some(DockLayout {
+            root: root.and_then(DockNode::normalize),
+            floating
+        })
+    }
+
+}
+
+impl<P: FromStr + Clone + PartialEq> DockLayout<P> {
+
+    // Restore a layout written by `save`. Returns `None` if the data is malformed.
+    pub fn load(data: &str) -> Option<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(data)?,
+            idx: 0
+        };
+        let layout = parser.layout()?;
+        if parser.peek().is_some() {
+            return None;
+        }
+        Some(layout)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_load_round_trip() {
+        let layout = DockLayout {
+            root: Some(DockNode::Split {
+                axis: Axis::X,
+                children: vec![
+                    (0.25, DockNode::Tabs { panels: vec!["Layers".to_owned()], active: 0 }),
+                    (0.75, DockNode::Split {
+                        axis: Axis::Y,
+                        children: vec![
+                            (0.6, DockNode::Tabs { panels: vec!["Scene \"main\"".to_owned(), "C:\\Assets".to_owned()], active: 1 }),
+                            (0.4, DockNode::Tabs { panels: vec!["Timeline".to_owned()], active: 0 })
+                        ]
+                    })
+                ]
+            }),
+            floating: vec![FloatingPanel { panel: "Library".to_owned(), pos: vec2(100.0, 80.5), size: vec2(300.0, 200.0) }]
+        };
+        assert_eq!(DockLayout::load(&layout.save()), Some(layout));
+
+        let empty = DockLayout::<String> { root: None, floating: Vec::new() };
+        assert_eq!(DockLayout::load(&empty.save()), Some(empty));
+    }
+
+    #[test]
+    fn load_rejects_invalid_layouts() {
+        for data in [
+            "(dock (split x (NaN (tabs 0 \"A\")) (0.5 (tabs 0 \"B\"))))",
+            "(dock (split x (-0.5 (tabs 0 \"A\")) (0.5 (tabs 0 \"B\"))))",
+            "(dock (split x (inf (tabs 0 \"A\")) (0.5 (tabs 0 \"B\"))))",
+            "(dock (tabs 0 \"A\" \"A\"))",
+            "(dock (tabs 0 \"A\") (float 0 0 100 100 \"A\"))",
+            "(dock none (float 0 0 100 100 \"A\") (float 10 10 100 100 \"A\"))",
+            "(dock none (float NaN 0 100 100 \"A\"))"
+        ] {
+            assert_eq!(DockLayout::<String>::load(data), None, "{}", data);
+        }
+    }
+
+}
@@ -0,0 +1,267 @@
+
+use crate::{Axis, Vec2};
+
+// Where a panel is dropped relative to the panel group it is docked into
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DockZone {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Center
+}
+
+impl DockZone {
+
+    // The axis of the split created by docking into this zone
+    pub fn axis(&self) -> Option<Axis> {
+        match self {
+            DockZone::Left | DockZone::Right => Some(Axis::X),
+            DockZone::Top | DockZone::Bottom => Some(Axis::Y),
+            DockZone::Center => None
+        }
+    }
+
+    fn before(&self) -> bool {
+        matches!(self, DockZone::Left | DockZone::Top)
+    }
+
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DockNode<P> {
+    // A group of panels shown one at a time
+    Tabs {
+        panels: Vec<P>,
+        active: usize
+    },
+    // Children laid out along an axis, each with the fraction of the space it takes up
+    Split {
+        axis: Axis,
+        children: Vec<(f32, DockNode<P>)>
+    }
+}
+
+impl<P: Clone + PartialEq> DockNode<P> {
+
+    pub fn tabs(panels: Vec<P>) -> Self {
+        Self::Tabs {
+            panels,
+            active: 0
+        }
+    }
+
+    pub fn split(axis: Axis, children: Vec<DockNode<P>>) -> Self {
+        let fraction = 1.0 / children.len() as f32;
+        Self::Split {
+            axis,
+            children: children.into_iter().map(|child| (fraction, child)).collect()
+        }
+    }
+
+    pub fn contains(&self, panel: &P) -> bool {
+        match self {
+            DockNode::Tabs { panels, .. } => panels.contains(panel),
+            DockNode::Split { children, .. } => children.iter().any(|(_, child)| child.contains(panel))
+        }
+    }
+
+    // All the panels in the tree, in order
+    pub fn panels(&self) -> Vec<&P> {
+        match self {
+            DockNode::Tabs { panels, .. } => panels.iter().collect(),
+            DockNode::Split { children, .. } => children.iter().flat_map(|(_, child)| child.panels()).collect()
+        }
+    }
+
+    // Drop empty tab groups, collapse splits with one child and merge nested splits along the same axis
+    pub(super) fn normalize(self) -> Option<Self> {
+        match self {
+            DockNode::Tabs { panels, active } => {
+                if panels.is_empty() {
+                    return None;
+                }
+                Some(DockNode::Tabs {
+                    active: active.min(panels.len() - 1),
+                    panels
+                })
+            },
+            DockNode::Split { axis, children } => {
+                let mut new_children = Vec::new();
+                for (fraction, child) in children {
+                    match child.normalize() {
+                        Some(DockNode::Split { axis: child_axis, children: grandchildren }) if child_axis == axis => {
+                            for (child_fraction, grandchild) in grandchildren {
+                                new_children.push((fraction * child_fraction, grandchild));
+                            }
+                        },
+                        Some(child) => new_children.push((fraction, child)),
+                        None => {}
+                    }
+                }
+
+                match new_children.len() {
+                    0 => None,
+                    1 => new_children.pop().map(|(_, child)| child),
+                    _ => {
+                        let total = new_children.iter().map(|(fraction, _)| *fraction).sum::<f32>().max(0.001);
+                        for (fraction, _) in new_children.iter_mut() {
+                            *fraction /= total;
+                        }
+                        Some(DockNode::Split { axis, children: new_children })
+                    }
+                }
+            }
+        }
+    }
+
+    fn remove(self, panel: &P) -> Option<Self> {
+        match self {
+            DockNode::Tabs { mut panels, active } => {
+                let active_panel = panels.get(active).cloned();
+                panels.retain(|other| other != panel);
+                let active = panels.iter().position(|other| Some(other) == active_panel.as_ref()).unwrap_or(active);
+                DockNode::Tabs { panels, active }.normalize()
+            },
+            DockNode::Split { axis, children } => DockNode::Split {
+                axis,
+                children: children.into_iter().filter_map(|(fraction, child)| Some((fraction, child.remove(panel)?))).collect()
+            }.normalize()
+        }
+    }
+
+    fn dock(self, panel: P, target: &P, zone: DockZone) -> Self {
+        match self {
+            DockNode::Tabs { mut panels, active } if panels.contains(target) => match zone.axis() {
+                None => {
+                    panels.push(panel);
+                    DockNode::Tabs {
+                        active: panels.len() - 1,
+                        panels
+                    }
+                },
+                Some(axis) => {
+                    let new_group = DockNode::tabs(vec![panel]);
+                    let old_group = DockNode::Tabs { panels, active };
+                    let children = if zone.before() {
+                        vec![(0.5, new_group), (0.5, old_group)]
+                    } else {
+                        vec![(0.5, old_group), (0.5, new_group)]
+                    };
+                    DockNode::Split { axis, children }
+                }
+            },
+            DockNode::Split { axis, children } => {
+                let mut panel = Some(panel);
+                DockNode::Split {
+                    axis,
+                    children: children.into_iter().map(|(fraction, child)| {
+                        match panel.take() {
+                            Some(to_dock) if child.contains(target) => (fraction, child.dock(to_dock, target, zone)),
+                            to_dock => {
+                                panel = to_dock;
+                                (fraction, child)
+                            }
+                        }
+                    }).collect()
+                }
+            },
+            tabs => tabs
+        }
+    }
+
+    fn set_active(&mut self, panel: &P) {
+        match self {
+            DockNode::Tabs { panels, active } => {
+                if let Some(idx) = panels.iter().position(|other| other == panel) {
+                    *active = idx;
+                }
+            },
+            DockNode::Split { children, .. } => {
+                for (_, child) in children {
+                    child.set_active(panel);
+                }
+            }
+        }
+    }
+
+    // Find a node by the child indices leading to it
+    pub(crate) fn node_mut(&mut self, path: &[usize]) -> Option<&mut Self> {
+        match path.split_first() {
+            None => Some(self),
+            Some((idx, rest)) => match self {
+                DockNode::Split { children, .. } => children.get_mut(*idx)?.1.node_mut(rest),
+                DockNode::Tabs { .. } => None
+            }
+        }
+    }
+
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FloatingPanel<P> {
+    pub panel: P,
+    pub pos: Vec2,
+    pub size: Vec2
+}
+
+// The arrangement of all panels in a `DockArea`. Keep it in the app state so it can be saved.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DockLayout<P> {
+    pub root: Option<DockNode<P>>,
+    pub floating: Vec<FloatingPanel<P>>
+}
+
+impl<P: Clone + PartialEq> DockLayout<P> {
+
+    pub fn new(root: DockNode<P>) -> Self {
+        Self {
+            root: Some(root),
+            floating: Vec::new()
+        }
+    }
+
+    pub fn contains(&self, panel: &P) -> bool {
+        self.root.as_ref().is_some_and(|root| root.contains(panel)) || self.floating.iter().any(|floating| floating.panel == *panel)
+    }
+
+    pub fn remove(&mut self, panel: &P) {
+        self.floating.retain(|floating| floating.panel != *panel);
+        self.root = self.root.take().and_then(|root| root.remove(panel));
+    }
+
+    // Move a panel next to or into the group containing `target`
+    pub fn dock(&mut self, panel: P, target: &P, zone: DockZone) {
+        if panel == *target {
+            return;
+        }
+        self.remove(&panel);
+        self.root = Some(match self.root.take() {
+            Some(root) if root.contains(target) => root.dock(panel, target, zone),
+            Some(root) => DockNode::split(Axis::X, vec![root, DockNode::tabs(vec![panel])]),
+            None => DockNode::tabs(vec![panel])
+        }.normalize().unwrap());
+    }
+
+    // Add a panel as a new tab of the first group, or as the only group if nothing is docked
+    pub fn add(&mut self, panel: P) {
+        self.remove(&panel);
+        let target = self.root.as_ref().and_then(|root| root.panels().first().map(|first| (*first).clone()));
+        match target {
+            Some(target) => self.dock(panel, &target, DockZone::Center),
+            None => self.root = Some(DockNode::tabs(vec![panel]))
+        }
+    }
+
+    pub fn undock(&mut self, panel: P, pos: Vec2, size: Vec2) {
+        self.remove(&panel);
+        self.floating.push(FloatingPanel { panel, pos, size });
+    }
+
+    pub fn set_active(&mut self, panel: &P) {
+        if let Some(root) = &mut self.root {
+            root.set_active(panel);
+        }
+    }
+
+}
@@ -0,0 +1,69 @@
+
+use std::{cell::RefCell, fmt::Display, hash::Hash, rc::Rc};
+
+use crate::{painter::Painter, Rect, Response, Vec2, Widget, WidgetState};
+
+use super::{draw_drop_zones, emit_change, find_drop, DockLayout, DropTarget, OnChange};
+use crate::widget::{floating_window::{FloatingWindow, FloatingWindowState}, LayoutContext, LayoutResult};
+
+// A panel floating above the dock area. Dragging its title bar onto a group docks it again.
+pub(super) struct DockWindow<S, P> {
+    pub(super) panel: P,
+    pub(super) floating_idx: usize,
+    pub(super) window: FloatingWindow<S>,
+    pub(super) layout: Rc<DockLayout<P>>,
+    pub(super) on_change: Option<OnChange<S, P>>,
+    pub(super) targets: Rc<RefCell<Vec<DropTarget<P>>>>
+}
+
+impl<S, P> Widget<S> for DockWindow<S, P> where S: 'static, P: Clone + PartialEq + Hash + Display + 'static {
+
+    type State = FloatingWindowState;
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, widget_state: &mut WidgetState<S>) -> LayoutResult<S> {
+        // The layout is the source of truth unless the window is being dragged
+        let floating = &self.layout.floating[self.floating_idx];
+        let state = Self::get(widget_state);
+        if !state.dragged() {
+            state.pos = floating.pos;
+            state.size = floating.size;
+        }
+        self.window.layout(max_size, ctx, widget_state)
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let state = Self::get(widget_state);
+        let (dragged, moved) = (state.dragged(), state.moved());
+        self.window.draw(painter, rect, resp, widget_state);
+
+        // Save the window's new place when a drag ends, or dock it if it was dropped onto a group
+        let state = Self::get(widget_state);
+        if !dragged || state.dragged() {
+            return;
+        }
+        let (new_pos, new_size) = (state.pos, state.size);
+        let drop = match (moved, resp.global_hover_pos()) {
+            (true, Some(mouse_pos)) => find_drop(&self.targets.borrow(), mouse_pos, &self.panel),
+            _ => None
+        };
+        let floating_idx = self.floating_idx;
+        let panel = self.panel.clone();
+        emit_change(widget_state, &self.on_change, &self.layout, move |layout| match drop {
+            Some((target, zone, _)) => layout.dock(panel, &target, zone),
+            None => {
+                layout.floating[floating_idx].pos = new_pos;
+                layout.floating[floating_idx].size = new_size;
+            }
+        });
+    }
+
+    fn post_draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        self.window.post_draw(painter, rect, resp, widget_state);
+
+        let state = Self::get(widget_state);
+        if let (true, Some(mouse_pos)) = (state.moved(), resp.global_hover_pos()) {
+            draw_drop_zones(painter, &self.targets.borrow(), mouse_pos, &self.panel);
+        }
+    }
+
+}
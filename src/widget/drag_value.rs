@@ -0,0 +1,238 @@
+
+use std::{cell::RefCell, ops::RangeInclusive, rc::Rc};
+
+use crate::{painter::{Painter, RectBuilder}, pos, vec2, Numeric, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{key::Key, LayoutContext, LayoutResult};
+
+const HEIGHT: f32 = 20.0;
+const MIN_WIDTH: f32 = 50.0;
+const PADDING: f32 = 6.0;
+// Speed multiplier while shift is held, for fine adjustments
+const SLOW_FACTOR: f64 = 0.1;
+
+#[derive(Default)]
+pub struct DragValueState {
+    // Text being typed, while in text entry
+    editing: Option<String>,
+    drag_start_x: f32,
+    drag_start_value: f64,
+    dragged: bool
+}
+
+type OnSet<S, N> = Rc<dyn Fn(&mut S, N)>;
+type OnFinish<S> = Rc<dyn Fn(&mut S)>;
+
+// A number that changes when dragged horizontally, or can be typed in after a double click
+pub struct DragValue<S, N> {
+    value: N,
+    range: Option<RangeInclusive<N>>,
+    speed: f64,
+    decimals: usize,
+    prefix: String,
+    suffix: String,
+    on_set: Option<OnSet<S, N>>,
+    on_finish: Option<OnFinish<S>>,
+    // The text shown this frame and its size
+    text: RefCell<(String, Vec2)>
+}
+
+impl<S: 'static, N: Numeric> DragValue<S, N> {
+
+    pub fn new(value: N) -> Self {
+        Self {
+            value,
+            range: None,
+            speed: 1.0,
+            decimals: if N::INTEGRAL { 0 } else { 2 },
+            prefix: String::new(),
+            suffix: String::new(),
+            on_set: None,
+            on_finish: None,
+            text: RefCell::new((String::new(), Vec2::ZERO))
+        }
+    }
+
+    pub fn range(mut self, range: RangeInclusive<N>) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    // Change in value per point dragged
+    pub fn speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn decimals(mut self, decimals: usize) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    pub fn prefix<T: Into<String>>(mut self, prefix: T) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    pub fn suffix<T: Into<String>>(mut self, suffix: T) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
+
+    pub fn on_set<F>(mut self, handler: F) -> Self where F: Fn(&mut S, N) + 'static {
+        self.on_set = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn on_finish<F>(mut self, handler: F) -> Self where F: Fn(&mut S) + 'static {
+        self.on_finish = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        WidgetNode::new(self).sense_click(true)
+    }
+
+    fn format(&self, value: f64) -> String {
+        format!("{:.*}", self.decimals, value)
+    }
+
+    // Clamp to the range and round to the shown decimal places
+    fn constrain(&self, value: f64) -> N {
+        let value = match &self.range {
+            Some(range) => {
+                // Reversed or NaN bounds are taken as they come rather than panicking like `clamp`
+                let (start, end) = (range.start().to_f64(), range.end().to_f64());
+                value.max(start.min(end)).min(start.max(end))
+            },
+            None => value
+        };
+        let scale = 10.0_f64.powi(self.decimals as i32);
+        N::from_f64((value * scale).round() / scale)
+    }
+
+    fn set(&self, value: N, state: &mut WidgetState<S>) {
+        if let Some(on_set) = self.on_set.clone() {
+            state.message(move |state| on_set(state, value));
+        }
+    }
+
+    fn finish(&self, state: &mut WidgetState<S>) {
+        if let Some(on_finish) = self.on_finish.clone() {
+            state.message(move |state| on_finish(state));
+        }
+    }
+
+    // Leave text entry, setting the typed value if it is a number
+    fn commit(&self, widget_state: &mut WidgetState<S>) {
+        let typed = Self::get(widget_state).editing.take();
+        if let Some(value) = typed.and_then(|text| text.trim().parse::<f64>().ok()) {
+            self.set(self.constrain(value), widget_state);
+            self.finish(widget_state);
+        }
+    }
+
+}
+
+impl<S, N> Widget<S> for DragValue<S, N> where
+    S: 'static,
+    N: Numeric {
+
+    type State = DragValueState;
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let text = match &Self::get(state).editing {
+            Some(text) => text.clone(),
+            None => format!("{}{}{}", self.prefix, self.format(self.value.to_f64()), self.suffix)
+        };
+        let text_size = ctx.text_shaper.measure_text(ctx.theme.font_size, &text);
+        *self.text.borrow_mut() = (text, text_size);
+
+        let size = vec2((text_size.x + 2.0 * PADDING).max(MIN_WIDTH), HEIGHT.max(text_size.y)).min(max_size);
+        let mut layout = LayoutResult::new(size);
+        layout.baseline = Some((size.y + text_size.y) / 2.0);
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let focused = widget_state.focused();
+        // Typed entry holds the keyboard, which a click anywhere takes away, so clicks on the text take it back
+        if Self::get(widget_state).editing.is_some() && resp.mouse_clicked() {
+            widget_state.request_keyboard_focus();
+        }
+        let state = Self::get(widget_state);
+
+        if let Some(text) = &mut state.editing {
+            text.extend(resp.text_input().chars().filter(|char| char.is_ascii_digit() || matches!(char, '.' | '-' | '+' | 'e' | 'E')));
+            if resp.key_pressed(Key::Backspace) {
+                text.pop();
+            }
+            if !resp.text_input().is_empty() || !resp.keys_pressed().is_empty() {
+                painter.request_redraw();
+            }
+            if resp.key_pressed(Key::Escape) {
+                state.editing = None;
+                widget_state.release_keyboard_focus();
+            } else if resp.key_pressed(Key::Enter) || resp.clicked_elsewhere() {
+                self.commit(widget_state);
+                widget_state.release_keyboard_focus();
+            }
+        } else if resp.mouse_double_clicked() {
+            // The first click of the double click may have nudged the value, so start from what is shown
+            state.editing = Some(self.format(self.value.to_f64()));
+            widget_state.unfocus();
+            widget_state.request_keyboard_focus();
+            painter.request_redraw();
+        } else if focused {
+            if let Some(hover_pos) = resp.global_hover_pos() {
+                let speed = if resp.modifiers().shift { self.speed * SLOW_FACTOR } else { self.speed };
+                let delta = (hover_pos.x - state.drag_start_x) as f64;
+                if delta != 0.0 {
+                    state.dragged = true;
+                }
+                let value = self.constrain(state.drag_start_value + delta * speed);
+                if state.dragged && value != self.value {
+                    self.set(value, widget_state);
+                }
+            }
+            if resp.global_mouse_released() {
+                widget_state.unfocus();
+                if Self::get(widget_state).dragged {
+                    self.finish(widget_state);
+                }
+            }
+        } else if resp.mouse_clicked() {
+            if let Some(hover_pos) = resp.hover_pos() {
+                state.drag_start_x = hover_pos.x;
+                state.drag_start_value = self.value.to_f64();
+                state.dragged = false;
+                widget_state.request_focus();
+            }
+        }
+
+        let editing = Self::get(widget_state).editing.is_some();
+        let color = if editing {
+            painter.theme.text_field
+        } else {
+            painter.theme.text_field.darken(if widget_state.focused() {
+                painter.theme.pressed_darkness
+            } else if resp.hovered() {
+                painter.theme.hovered_darkness
+            } else {
+                0.0
+            })
+        };
+        painter.rect(RectBuilder::new(rect).fill(color).stroke(painter.theme.stroke).rounding(painter.theme.rounding / 2.0));
+
+        let text = self.text.borrow();
+        let (text, text_size) = &*text;
+        let text_x = if editing { rect.left() + PADDING } else { rect.center().x - text_size.x / 2.0 };
+        let text_pos = pos(text_x, rect.center().y + text_size.y / 2.0);
+        painter.text(text, text_pos, painter.theme.text, painter.theme.font_size);
+        if editing {
+            let caret_x = text_x + text_size.x + 1.0;
+            painter.line(pos(caret_x, rect.top() + 3.0), pos(caret_x, rect.bottom() - 3.0), painter.theme.text);
+        }
+    }
+
+}
@@ -3,6 +3,9 @@ use crate::{painter::{Painter, RectBuilder}, Rect, Response, Vec2, Widget, Widge
 
 use super::{margin::Margin, LayoutContext, LayoutResult};
 
+// Local id of the margin wrapping the contents
+const MARGIN_ID: usize = 0;
+
 pub struct Dropdown<S> {
     contents: WidgetNode<S>
 }
@@ -11,10 +14,15 @@ impl<S: 'static> Dropdown<S> {
 
     pub fn new(contents: WidgetNode<S>) -> WidgetNode<S> {
         WidgetNode::new(Self {
-            contents: Margin::new_with_margin(contents, 10.0)
+            contents: Margin::new_with_margin(contents, 10.0).id(MARGIN_ID)
         })
     }
 
+    // The state of the contents, given the dropdown's state and the local id of the contents
+    pub(crate) fn contents_state(state: &mut WidgetState<S>, contents_id: usize) -> &mut WidgetState<S> {
+        state.get_child(MARGIN_ID).get_child(contents_id)
+    }
+
 }
 
 impl<S> Widget<S> for Dropdown<S> {
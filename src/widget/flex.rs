@@ -0,0 +1,228 @@
+
+use crate::{state::WidgetState, Axis, LayoutNode, Vec2, Widget, WidgetNode};
+
+use super::{LayoutContext, LayoutResult};
+
+// How much space a flex item takes along the flex axis
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FlexSize {
+    Fixed(f32),
+    // As much as the item asks for when laid out
+    Natural,
+    // A share of the space left over by the other items, weighted by the factor
+    Grow(f32)
+}
+
+pub struct FlexItem<S> {
+    node: WidgetNode<S>,
+    size: FlexSize,
+    min: f32,
+    max: f32
+}
+
+impl<S> FlexItem<S> {
+
+    pub fn new(size: FlexSize, node: WidgetNode<S>) -> Self {
+        Self {
+            node,
+            size,
+            min: 0.0,
+            max: f32::INFINITY
+        }
+    }
+
+    pub fn fixed(size: f32, node: WidgetNode<S>) -> Self {
+        Self::new(FlexSize::Fixed(size), node)
+    }
+
+    pub fn natural(node: WidgetNode<S>) -> Self {
+        Self::new(FlexSize::Natural, node)
+    }
+
+    pub fn grow(factor: f32, node: WidgetNode<S>) -> Self {
+        Self::new(FlexSize::Grow(factor), node)
+    }
+
+    pub fn min(mut self, min: f32) -> Self {
+        self.min = min;
+        self
+    }
+
+    pub fn max(mut self, max: f32) -> Self {
+        self.max = max;
+        self
+    }
+
+    fn clamp(&self, size: f32) -> f32 {
+        size.min(self.max).max(self.min)
+    }
+
+    fn grow_factor(&self) -> f32 {
+        match self.size {
+            FlexSize::Grow(factor) => factor.max(0.0),
+            _ => 0.0
+        }
+    }
+
+}
+
+// Lays out items along an axis, sharing the space left over by fixed and natural items among the growing ones
+pub struct Flex<S> {
+    axis: Axis,
+    items: Vec<FlexItem<S>>,
+    spacing: f32
+}
+
+impl<S: 'static> Flex<S> {
+
+    pub fn row(items: Vec<FlexItem<S>>) -> WidgetNode<S> {
+        Self::builder(Axis::X, items).build()
+    }
+
+    pub fn column(items: Vec<FlexItem<S>>) -> WidgetNode<S> {
+        Self::builder(Axis::Y, items).build()
+    }
+
+    pub fn builder(axis: Axis, items: Vec<FlexItem<S>>) -> Self {
+        Self {
+            axis,
+            items,
+            spacing: 0.0
+        }
+    }
+
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        WidgetNode::new(self)
+    }
+
+}
+
+// Share `free` space among growing items by their factors, respecting their limits.
+// Items that would break a limit are pinned to it and the rest is shared again.
+fn distribute_growth<S>(items: &[&FlexItem<S>], free: f32) -> Vec<f32> {
+    let mut sizes: Vec<Option<f32>> = vec![None; items.len()];
+    loop {
+        let pinned: f32 = sizes.iter().flatten().sum();
+        let total_factor: f32 = items.iter().zip(&sizes)
+            .filter(|(_, size)| size.is_none())
+            .map(|(item, _)| item.grow_factor())
+            .sum();
+        let left = (free - pinned).max(0.0);
+
+        let mut pinned_any = false;
+        for (item, size) in items.iter().zip(sizes.iter_mut()) {
+            if size.is_some() {
+                continue;
+            }
+            let share = if total_factor > 0.0 { left * item.grow_factor() / total_factor } else { 0.0 };
+            if share < item.min || share > item.max {
+                *size = Some(item.clamp(share));
+                pinned_any = true;
+            }
+        }
+
+        if !pinned_any {
+            return items.iter().zip(sizes).map(|(item, size)| size.unwrap_or_else(|| {
+                if total_factor > 0.0 { left * item.grow_factor() / total_factor } else { 0.0 }
+            })).collect();
+        }
+    }
+}
+
+impl<S> Widget<S> for Flex<S> {
+
+    type State = ();
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let axis = self.axis;
+        let cross = axis.other();
+        let max_main = max_size.axis(axis);
+        let spacing_total = self.spacing * self.items.len().saturating_sub(1) as f32;
+        let available = (max_main - spacing_total).max(0.0);
+
+        let child_max_size = |main: f32| {
+            let mut size = max_size;
+            *size.axis_mut(axis) = main;
+            size
+        };
+
+        // Pass 1: fixed items take their size, natural items take what they ask for
+        let mut nodes: Vec<Option<LayoutNode<S>>> = (0..self.items.len()).map(|_| None).collect();
+        let mut sizes = vec![0.0; self.items.len()];
+        // The space along the axis each item was laid out in
+        let mut max_mains = vec![0.0; self.items.len()];
+        let fixed_total: f32 = self.items.iter().map(|item| match item.size {
+            FlexSize::Fixed(size) => item.clamp(size),
+            _ => 0.0
+        }).sum();
+        let mut used = fixed_total;
+        for (idx, item) in self.items.iter().enumerate() {
+            match item.size {
+                FlexSize::Fixed(size) => {
+                    sizes[idx] = item.clamp(size);
+                    max_mains[idx] = sizes[idx];
+                    nodes[idx] = Some(item.node.layout(child_max_size(sizes[idx]), ctx, state));
+                },
+                FlexSize::Natural => {
+                    max_mains[idx] = (available - used).min(item.max).max(item.min);
+                    let node = item.node.layout(child_max_size(max_mains[idx]), ctx, state);
+                    sizes[idx] = item.clamp(node.size().axis(axis));
+                    used += sizes[idx];
+                    nodes[idx] = Some(node);
+                },
+                FlexSize::Grow(_) => {}
+            }
+        }
+
+        // Pass 2: growing items share the rest. Without a limit on the axis they fall back to their natural size.
+        let growing: Vec<usize> = (0..self.items.len()).filter(|idx| matches!(self.items[*idx].size, FlexSize::Grow(_))).collect();
+        if max_main.is_finite() {
+            let growing_items: Vec<&FlexItem<S>> = growing.iter().map(|idx| &self.items[*idx]).collect();
+            let shares = distribute_growth(&growing_items, available - used);
+            for (idx, share) in growing.iter().zip(shares) {
+                sizes[*idx] = share;
+                max_mains[*idx] = share;
+                nodes[*idx] = Some(self.items[*idx].node.layout(child_max_size(share), ctx, state));
+            }
+        } else {
+            for idx in growing {
+                let item = &self.items[idx];
+                max_mains[idx] = item.max;
+                let node = item.node.layout(child_max_size(item.max), ctx, state);
+                sizes[idx] = item.clamp(node.size().axis(axis));
+                nodes[idx] = Some(node);
+            }
+        }
+
+        let content_cross = nodes.iter().flatten().map(|node| node.size().axis(cross)).fold(0.0, f32::max);
+        let cross_size = if max_size.axis(cross).is_finite() { max_size.axis(cross) } else { content_cross };
+        let main_size = sizes.iter().sum::<f32>() + spacing_total;
+
+        let mut layout = LayoutResult::new(Vec2::ZERO);
+        *layout.size.axis_mut(axis) = main_size;
+        *layout.size.axis_mut(cross) = cross_size;
+        let mut main_pos = 0.0;
+        for (idx, (node, size)) in nodes.into_iter().zip(sizes).enumerate() {
+            if let Some(mut node) = node {
+                // Items fill the space they were given along the axis. Items clamped to a size other than
+                // the one they were laid out in are laid out again, so their contents fill it.
+                if node.size().axis(axis) != size && max_mains[idx] != size {
+                    node = self.items[idx].node.layout_with_id(node.local_id, child_max_size(size), ctx, state);
+                }
+                let mut node_size = node.size();
+                *node_size.axis_mut(axis) = size;
+                node.set_size(node_size);
+                layout.add_child(axis.unit() * main_pos, node);
+            }
+            main_pos += size + self.spacing;
+        }
+
+        layout
+    }
+
+}
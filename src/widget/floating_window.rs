@@ -0,0 +1,415 @@
+
+use std::{cell::RefCell, collections::hash_map::DefaultHasher, hash::{Hash, Hasher}, rc::Rc};
+
+use crate::{painter::{cursor::Cursor, Painter, RectBuilder}, pos, vec2, Pos, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{LayoutContext, LayoutResult};
+
+const TITLE_BAR_HEIGHT: f32 = 25.0;
+const RESIZE_MARGIN: f32 = 5.0;
+const CLOSE_BUTTON_SIZE: f32 = 17.0;
+const MIN_WINDOW_SIZE: Vec2 = vec2(100.0, 60.0);
+const DEFAULT_WINDOW_SIZE: Vec2 = vec2(300.0, 200.0);
+
+#[derive(Clone, Copy, PartialEq)]
+struct ResizeEdges {
+    left: bool,
+    right: bool,
+    top: bool,
+    bottom: bool
+}
+
+const fn edges(left: bool, right: bool, top: bool, bottom: bool) -> ResizeEdges {
+    ResizeEdges {
+        left,
+        right,
+        top,
+        bottom
+    }
+}
+
+// The edges each resize sensor drags. Corners come last so they win where they overlap the edges.
+const RESIZE_SENSOR_EDGES: [ResizeEdges; 8] = [
+    edges(true, false, false, false),
+    edges(false, true, false, false),
+    edges(false, false, true, false),
+    edges(false, false, false, true),
+    edges(true, false, true, false),
+    edges(false, true, true, false),
+    edges(true, false, false, true),
+    edges(false, true, false, true)
+];
+const CLOSE_SENSOR_IDX: usize = RESIZE_SENSOR_EDGES.len();
+
+impl ResizeEdges {
+
+    fn cursor(&self) -> Cursor {
+        match (self.left || self.right, self.top || self.bottom) {
+            (true, true) if (self.left && self.top) || (self.right && self.bottom) => Cursor::NwseResize,
+            (true, true) => Cursor::NeswResize,
+            (true, false) => Cursor::ColResize,
+            _ => Cursor::RowResize
+        }
+    }
+
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum WindowDrag {
+    None,
+    Move,
+    Resize(ResizeEdges)
+}
+
+pub struct FloatingWindowState {
+    // Position relative to the `FloatingWindows` area
+    pub(crate) pos: Vec2,
+    pub(crate) size: Vec2,
+    initialized: bool,
+
+    drag: WindowDrag,
+    drag_start_mouse: Pos,
+    drag_start_pos: Vec2,
+    drag_start_size: Vec2
+}
+
+impl Default for FloatingWindowState {
+
+    fn default() -> Self {
+        Self {
+            pos: Vec2::ZERO,
+            size: DEFAULT_WINDOW_SIZE,
+            initialized: false,
+            drag: WindowDrag::None,
+            drag_start_mouse: Pos::ZERO,
+            drag_start_pos: Vec2::ZERO,
+            drag_start_size: Vec2::ZERO
+        }
+    }
+
+}
+
+impl FloatingWindowState {
+
+    pub(crate) fn dragged(&self) -> bool {
+        self.drag != WindowDrag::None
+    }
+
+    pub(crate) fn moved(&self) -> bool {
+        self.drag == WindowDrag::Move
+    }
+
+    fn drag_to(&mut self, mouse_pos: Pos) {
+        let delta = mouse_pos - self.drag_start_mouse;
+        match self.drag {
+            WindowDrag::None => {},
+            WindowDrag::Move => {
+                self.pos = self.drag_start_pos + delta;
+            },
+            WindowDrag::Resize(edges) => {
+                let start_max = self.drag_start_pos + self.drag_start_size;
+                if edges.right {
+                    self.size.x = (self.drag_start_size.x + delta.x).max(MIN_WINDOW_SIZE.x);
+                }
+                if edges.bottom {
+                    self.size.y = (self.drag_start_size.y + delta.y).max(MIN_WINDOW_SIZE.y);
+                }
+                // Dragging the left or top edge keeps the opposite edge in place
+                if edges.left {
+                    self.size.x = (self.drag_start_size.x - delta.x).max(MIN_WINDOW_SIZE.x);
+                    self.pos.x = start_max.x - self.size.x;
+                }
+                if edges.top {
+                    self.size.y = (self.drag_start_size.y - delta.y).max(MIN_WINDOW_SIZE.y);
+                    self.pos.y = start_max.y - self.size.y;
+                }
+            }
+        }
+    }
+
+}
+
+type OnClose<S> = Rc<dyn Fn(&mut S)>;
+
+// A movable, resizable window. Place windows in a `FloatingWindows` area.
+pub struct FloatingWindow<S> {
+    title: String,
+    title_size: RefCell<Vec2>,
+    contents: WidgetNode<S>,
+    key: Option<usize>,
+    default_pos: Option<Vec2>,
+    default_size: Option<Vec2>,
+    on_close: Option<OnClose<S>>
+}
+
+impl<S: 'static> FloatingWindow<S> {
+
+    pub fn new<T: Into<String>>(title: T, contents: WidgetNode<S>) -> Self {
+        Self {
+            title: title.into(),
+            title_size: RefCell::new(Vec2::ZERO),
+            contents,
+            key: None,
+            default_pos: None,
+            default_size: None,
+            on_close: None
+        }
+    }
+
+    // Identifies the window across frames so its position and size are kept.
+    // Defaults to the window's index in the `FloatingWindows` area. Keys must be unique within an area.
+    pub fn key(mut self, key: usize) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    pub fn default_pos(mut self, pos: Vec2) -> Self {
+        self.default_pos = Some(pos);
+        self
+    }
+
+    pub fn default_size(mut self, size: Vec2) -> Self {
+        self.default_size = Some(size);
+        self
+    }
+
+    // Show a close button in the title bar
+    pub fn on_close<F>(mut self, handler: F) -> Self where F: Fn(&mut S) + 'static {
+        self.on_close = Some(Rc::new(handler));
+        self
+    }
+
+}
+
+impl<S: 'static> Widget<S> for FloatingWindow<S> {
+
+    type State = FloatingWindowState;
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, widget_state: &mut WidgetState<S>) -> LayoutResult<S> {
+        *self.title_size.borrow_mut() = ctx.text_shaper.measure_text(ctx.theme.font_size, &self.title);
+
+        let state = Self::get(widget_state);
+        if !state.initialized {
+            state.size = self.default_size.unwrap_or(DEFAULT_WINDOW_SIZE);
+            state.pos = self.default_pos.unwrap_or((max_size - state.size) / 2.0);
+            state.initialized = true;
+        }
+
+        // Keep the window inside the area
+        state.size = state.size.max(MIN_WINDOW_SIZE).min(max_size);
+        state.pos = state.pos.min(max_size - state.size).max(Vec2::ZERO);
+        let size = state.size;
+
+        let mut layout = LayoutResult::new(size);
+        let contents_layout = self.contents.layout(vec2(size.x, size.y - TITLE_BAR_HEIGHT), ctx, widget_state);
+        layout.add_child(vec2(0.0, TITLE_BAR_HEIGHT), contents_layout);
+
+        let m = RESIZE_MARGIN;
+        layout.add_sensor(vec2(-m, 0.0), vec2(2.0 * m, size.y));
+        layout.add_sensor(vec2(size.x - m, 0.0), vec2(2.0 * m, size.y));
+        layout.add_sensor(vec2(0.0, -m), vec2(size.x, 2.0 * m));
+        layout.add_sensor(vec2(0.0, size.y - m), vec2(size.x, 2.0 * m));
+        layout.add_sensor(vec2(-m, -m), Vec2::splat(3.0 * m));
+        layout.add_sensor(vec2(size.x - 2.0 * m, -m), Vec2::splat(3.0 * m));
+        layout.add_sensor(vec2(-m, size.y - 2.0 * m), Vec2::splat(3.0 * m));
+        layout.add_sensor(vec2(size.x - 2.0 * m, size.y - 2.0 * m), Vec2::splat(3.0 * m));
+        if self.on_close.is_some() {
+            let close_margin = (TITLE_BAR_HEIGHT - CLOSE_BUTTON_SIZE) / 2.0;
+            layout.add_sensor(vec2(size.x - close_margin - CLOSE_BUTTON_SIZE, close_margin), Vec2::splat(CLOSE_BUTTON_SIZE));
+        }
+
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let focused = widget_state.focused();
+        let title_bar = Rect::min_size(rect.min(), vec2(rect.width(), TITLE_BAR_HEIGHT));
+        let state = Self::get(widget_state);
+
+        let mut focus = false;
+        let mut unfocus = false;
+        let close_hovered = resp.sensor_idx() == Some(CLOSE_SENSOR_IDX);
+
+        if focused {
+            if let Some(mouse_pos) = resp.global_hover_pos() {
+                state.drag_to(mouse_pos);
+                painter.request_redraw();
+            }
+            if let WindowDrag::Resize(edges) = state.drag {
+                painter.cursor = edges.cursor();
+            }
+            if resp.global_mouse_released() {
+                state.drag = WindowDrag::None;
+                unfocus = true;
+            }
+        } else if let Some(hover_pos) = resp.hover_pos() {
+            let drag = match resp.sensor_idx() {
+                Some(idx) if idx < CLOSE_SENSOR_IDX => WindowDrag::Resize(RESIZE_SENSOR_EDGES[idx]),
+                None if title_bar.contains(hover_pos) => WindowDrag::Move,
+                _ => WindowDrag::None
+            };
+            if let WindowDrag::Resize(edges) = drag {
+                painter.cursor = edges.cursor();
+            }
+            if resp.mouse_clicked() && drag != WindowDrag::None {
+                state.drag = drag;
+                state.drag_start_mouse = hover_pos;
+                state.drag_start_pos = state.pos;
+                state.drag_start_size = state.size;
+                focus = true;
+            }
+        }
+
+        if close_hovered && resp.mouse_clicked() {
+            if let Some(on_close) = &self.on_close {
+                let on_close = on_close.clone();
+                widget_state.message(move |state| {
+                    on_close(state);
+                });
+            }
+        }
+        if focus {
+            widget_state.request_focus();
+        }
+        if unfocus {
+            widget_state.unfocus();
+        }
+
+        painter.rect(RectBuilder::new(rect)
+            .fill(painter.theme.bg_light)
+            .stroke(painter.theme.stroke)
+            .rounding(painter.theme.rounding));
+        painter.rect(RectBuilder::new(title_bar)
+            .fill(painter.theme.bg_window)
+            .stroke(painter.theme.stroke)
+            .rounding(painter.theme.rounding));
+
+        let title_size = *self.title_size.borrow();
+        let title_pos = pos(rect.left() + painter.theme.item_spacing, title_bar.center().y + title_size.y / 2.0);
+        painter.push_clip_rect(title_bar);
+        painter.text(&self.title, title_pos, painter.theme.text, painter.theme.font_size);
+        painter.pop_clip_rect();
+
+        if self.on_close.is_some() {
+            let close_margin = (TITLE_BAR_HEIGHT - CLOSE_BUTTON_SIZE) / 2.0;
+            let close_rect = Rect::min_size(pos(rect.right() - close_margin - CLOSE_BUTTON_SIZE, rect.top() + close_margin), Vec2::splat(CLOSE_BUTTON_SIZE));
+            if close_hovered {
+                let darkness = if resp.mouse_down() { painter.theme.pressed_darkness } else { painter.theme.hovered_darkness };
+                painter.rect(RectBuilder::new(close_rect)
+                    .fill(painter.theme.bg_window.darken(darkness))
+                    .rounding(painter.theme.rounding / 2.0));
+            }
+            let cross = Rect::center_size(close_rect.center(), Vec2::splat(CLOSE_BUTTON_SIZE / 3.0));
+            painter.line(cross.top_left(), cross.bottom_right(), painter.theme.text);
+            painter.line(cross.top_right(), cross.bottom_left(), painter.theme.text);
+        }
+
+        painter.push_clip_rect(Rect::new(title_bar.bottom_left(), rect.max()));
+    }
+
+    fn post_draw(&self, painter: &mut Painter, _rect: Rect, _resp: &Response, _state: &mut WidgetState<S>) {
+        painter.pop_clip_rect();
+    }
+
+}
+
+// Explicit keys and positions hash to different ids, so a keyed window never takes over an unkeyed one's state
+#[derive(Hash)]
+enum WindowKey {
+    Key(usize),
+    Index(usize)
+}
+
+fn window_id(key: WindowKey) -> usize {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish() as usize
+}
+
+#[derive(Default)]
+pub struct FloatingWindowsState {
+    // Window ids from back to front
+    order: Vec<usize>
+}
+
+// An area whose floating windows can be moved, resized and brought to the front by clicking them
+pub struct FloatingWindows<S> {
+    contents: WidgetNode<S>,
+    windows: Vec<(usize, WidgetNode<S>)>
+}
+
+impl<S: 'static> FloatingWindows<S> {
+
+    pub fn new(contents: WidgetNode<S>, windows: Vec<FloatingWindow<S>>) -> WidgetNode<S> {
+        let windows: Vec<_> = windows.into_iter().enumerate().map(|(idx, window)| {
+            let id = window_id(window.key.map_or(WindowKey::Index(idx), WindowKey::Key));
+            (id, WidgetNode::new(window).id(id))
+        }).collect();
+        debug_assert!(
+            windows.iter().enumerate().all(|(idx, (id, _))| windows[..idx].iter().all(|(other, _)| other != id)),
+            "floating window keys must be unique"
+        );
+        WidgetNode::new(Self {
+            contents,
+            windows
+        })
+    }
+
+    fn window_state(widget_state: &mut WidgetState<S>, id: usize) -> &mut FloatingWindowState {
+        FloatingWindow::<S>::get(widget_state.get_child(id))
+    }
+
+}
+
+impl<S: 'static> Widget<S> for FloatingWindows<S> {
+
+    type State = FloatingWindowsState;
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, widget_state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let mut layout = LayoutResult::new(max_size);
+        layout.add_child(Vec2::ZERO, self.contents.layout(max_size, ctx, widget_state));
+
+        let state = Self::get(widget_state);
+        state.order.retain(|id| self.windows.iter().any(|(window_id, _)| window_id == id));
+        for (id, _) in &self.windows {
+            if !state.order.contains(id) {
+                state.order.push(*id);
+            }
+        }
+
+        for id in state.order.clone() {
+            let (_, window) = self.windows.iter().find(|(window_id, _)| *window_id == id).unwrap();
+            let window_layout = window.layout(max_size, ctx, widget_state);
+            let pos = Self::window_state(widget_state, id).pos;
+            layout.add_popover(pos, window_layout);
+        }
+
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        if !resp.global_left_mouse_button.pressed() {
+            return;
+        }
+        let mouse_pos = if let Some(mouse_pos) = resp.global_hover_pos() {
+            mouse_pos
+        } else {
+            return;
+        };
+
+        // Bring the topmost window under the mouse to the front
+        let order = Self::get(widget_state).order.clone();
+        for (idx, id) in order.iter().enumerate().rev() {
+            let window = Self::window_state(widget_state, *id);
+            let window_rect = Rect::min_size(rect.min() + window.pos, window.size);
+            if window_rect.contains(mouse_pos) {
+                let state = Self::get(widget_state);
+                let id = state.order.remove(idx);
+                state.order.push(id);
+                painter.request_redraw();
+                break;
+            }
+        }
+    }
+
+}
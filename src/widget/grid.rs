@@ -0,0 +1,270 @@
+
+use crate::{state::WidgetState, vec2, Axis, Vec2, Widget, WidgetNode};
+
+use super::{linear::Align, LayoutContext, LayoutResult};
+
+// The size of a grid column or row
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Track {
+    Fixed(f32),
+    // As large as the largest cell in the track
+    Auto,
+    // A share of the space left over by the other tracks, weighted by the factor
+    Fraction(f32)
+}
+
+pub struct GridCell<S> {
+    node: WidgetNode<S>,
+    col: usize,
+    row: usize,
+    col_span: usize,
+    row_span: usize,
+    align_x: Align,
+    align_y: Align
+}
+
+impl<S> GridCell<S> {
+
+    pub fn new(col: usize, row: usize, node: WidgetNode<S>) -> Self {
+        Self {
+            node,
+            col,
+            row,
+            col_span: 1,
+            row_span: 1,
+            align_x: Align::Start,
+            align_y: Align::Start
+        }
+    }
+
+    pub fn span(mut self, cols: usize, rows: usize) -> Self {
+        self.col_span = cols.max(1);
+        self.row_span = rows.max(1);
+        self
+    }
+
+    // Placement of the contents within the cell. Baseline alignment is treated as start.
+    pub fn align(mut self, x: Align, y: Align) -> Self {
+        self.align_x = x;
+        self.align_y = y;
+        self
+    }
+
+    fn start(&self, axis: Axis) -> usize {
+        match axis {
+            Axis::X => self.col,
+            Axis::Y => self.row
+        }
+    }
+
+    fn span_on(&self, axis: Axis) -> usize {
+        match axis {
+            Axis::X => self.col_span,
+            Axis::Y => self.row_span
+        }
+    }
+
+    fn align_on(&self, axis: Axis) -> Align {
+        match axis {
+            Axis::X => self.align_x,
+            Axis::Y => self.align_y
+        }
+    }
+
+}
+
+// Cells arranged in columns and rows whose sizes line up across the whole grid.
+// Tracks that are not given are sized automatically.
+pub struct Grid<S> {
+    columns: Vec<Track>,
+    rows: Vec<Track>,
+    cells: Vec<GridCell<S>>,
+    gap: Vec2
+}
+
+impl<S: 'static> Grid<S> {
+
+    pub fn new(columns: Vec<Track>, rows: Vec<Track>) -> Self {
+        Self {
+            columns,
+            rows,
+            cells: Vec::new(),
+            gap: Vec2::ZERO
+        }
+    }
+
+    pub fn cell(self, col: usize, row: usize, node: WidgetNode<S>) -> Self {
+        self.add_cell(GridCell::new(col, row, node))
+    }
+
+    pub fn add_cell(mut self, cell: GridCell<S>) -> Self {
+        self.cells.push(cell);
+        self
+    }
+
+    // Space between columns and between rows
+    pub fn gap(mut self, x: f32, y: f32) -> Self {
+        self.gap = vec2(x, y);
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        WidgetNode::new(self)
+    }
+
+    fn tracks(&self, axis: Axis) -> Vec<Track> {
+        let mut tracks = match axis {
+            Axis::X => self.columns.clone(),
+            Axis::Y => self.rows.clone()
+        };
+        let used = self.cells.iter().map(|cell| cell.start(axis) + cell.span_on(axis)).max().unwrap_or(0);
+        while tracks.len() < used {
+            tracks.push(Track::Auto);
+        }
+        tracks
+    }
+
+    // The space a cell may take along an axis before the tracks are known
+    fn measure_limit(&self, cell: &GridCell<S>, tracks: &[Track], axis: Axis, available: f32) -> f32 {
+        let spanned = &tracks[cell.start(axis)..cell.start(axis) + cell.span_on(axis)];
+        if spanned.iter().all(|track| matches!(track, Track::Fixed(_))) {
+            span_size(spanned.iter().map(|track| if let Track::Fixed(size) = track { *size } else { 0.0 }), self.gap.axis(axis))
+        } else {
+            available
+        }
+    }
+
+}
+
+fn span_size<I: Iterator<Item = f32>>(sizes: I, gap: f32) -> f32 {
+    let mut total = 0.0;
+    let mut count = 0;
+    for size in sizes {
+        total += size;
+        count += 1;
+    }
+    total + gap * (count.max(1) - 1) as f32
+}
+
+// Resolve track sizes along an axis from the measured sizes of the cells on it
+fn resolve_tracks(tracks: &[Track], measured: &[(usize, usize, f32)], available: f32, gap: f32) -> Vec<f32> {
+    let fractions_fill = available.is_finite();
+    let mut sizes: Vec<f32> = tracks.iter().map(|track| match track {
+        Track::Fixed(size) => *size,
+        _ => 0.0
+    }).collect();
+    let is_auto = |track: &Track| matches!(track, Track::Auto) || (!fractions_fill && matches!(track, Track::Fraction(_)));
+
+    for (start, _, size) in measured.iter().filter(|(_, span, _)| *span == 1) {
+        if is_auto(&tracks[*start]) {
+            sizes[*start] = sizes[*start].max(*size);
+        }
+    }
+    // Spanning cells that don't fit grow the last automatic track they cover, unless a fraction track can take them
+    for (start, span, size) in measured.iter().filter(|(_, span, _)| *span > 1) {
+        let range = *start..*start + *span;
+        let current = span_size(sizes[range.clone()].iter().copied(), gap);
+        let has_fraction = fractions_fill && range.clone().any(|idx| matches!(tracks[idx], Track::Fraction(_)));
+        if let (Some(last_auto), false) = (range.rev().find(|idx| is_auto(&tracks[*idx])), has_fraction) {
+            sizes[last_auto] += (*size - current).max(0.0);
+        }
+    }
+
+    if fractions_fill {
+        let used = span_size(sizes.iter().copied(), gap);
+        let total_fraction: f32 = tracks.iter().map(|track| if let Track::Fraction(fraction) = track { fraction.max(0.0) } else { 0.0 }).sum();
+        let left = (available - used).max(0.0);
+        for (size, track) in sizes.iter_mut().zip(tracks) {
+            if let (Track::Fraction(fraction), true) = (track, total_fraction > 0.0) {
+                *size = left * fraction.max(0.0) / total_fraction;
+            }
+        }
+    }
+
+    sizes
+}
+
+fn track_offsets(sizes: &[f32], gap: f32) -> Vec<f32> {
+    let mut offsets = Vec::with_capacity(sizes.len());
+    let mut offset = 0.0;
+    for size in sizes {
+        offsets.push(offset);
+        offset += size + gap;
+    }
+    offsets
+}
+
+fn align_in_cell(align: Align, cell_size: f32, size: f32) -> f32 {
+    match align {
+        Align::Center => (cell_size - size) / 2.0,
+        Align::End => cell_size - size,
+        Align::Start | Align::Stretch | Align::Baseline => 0.0
+    }
+}
+
+impl<S: 'static> Widget<S> for Grid<S> {
+
+    type State = ();
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let columns = self.tracks(Axis::X);
+        let rows = self.tracks(Axis::Y);
+        // Cells are laid out several times, always under the same id so they keep one state
+        let ids: Vec<usize> = self.cells.iter().map(|cell| cell.node.take_id(ctx)).collect();
+
+        // Pass 1: measure the widths of cells in automatic columns
+        let mut measured_widths = Vec::new();
+        for (cell, id) in self.cells.iter().zip(&ids) {
+            let spanned = &columns[cell.col..cell.col + cell.col_span];
+            if spanned.iter().any(|track| !matches!(track, Track::Fixed(_))) {
+                let limit = vec2(self.measure_limit(cell, &columns, Axis::X, max_size.x), self.measure_limit(cell, &rows, Axis::Y, max_size.y));
+                let size = cell.node.layout_with_id(*id, limit, ctx, state).size();
+                measured_widths.push((cell.col, cell.col_span, size.x));
+            }
+        }
+        let col_sizes = resolve_tracks(&columns, &measured_widths, max_size.x, self.gap.x);
+        let col_offsets = track_offsets(&col_sizes, self.gap.x);
+
+        // Pass 2: measure the heights of cells in automatic rows, now that their widths are known
+        let mut measured_heights = Vec::new();
+        for (cell, id) in self.cells.iter().zip(&ids) {
+            let spanned = &rows[cell.row..cell.row + cell.row_span];
+            if spanned.iter().any(|track| !matches!(track, Track::Fixed(_))) {
+                let width = span_size(col_sizes[cell.col..cell.col + cell.col_span].iter().copied(), self.gap.x);
+                let limit = vec2(width, self.measure_limit(cell, &rows, Axis::Y, max_size.y));
+                let size = cell.node.layout_with_id(*id, limit, ctx, state).size();
+                measured_heights.push((cell.row, cell.row_span, size.y));
+            }
+        }
+        let row_sizes = resolve_tracks(&rows, &measured_heights, max_size.y, self.gap.y);
+        let row_offsets = track_offsets(&row_sizes, self.gap.y);
+
+        let size = vec2(span_size(col_sizes.iter().copied(), self.gap.x), span_size(row_sizes.iter().copied(), self.gap.y));
+        let mut layout = LayoutResult::new(size);
+
+        // Pass 3: lay out every cell in its final space, so the contents of stretched cells fill the cell
+        for (cell, id) in self.cells.iter().zip(&ids) {
+            let cell_size = vec2(
+                span_size(col_sizes[cell.col..cell.col + cell.col_span].iter().copied(), self.gap.x),
+                span_size(row_sizes[cell.row..cell.row + cell.row_span].iter().copied(), self.gap.y)
+            );
+            let mut node = cell.node.layout_with_id(*id, cell_size, ctx, state);
+            let mut node_size = node.size();
+            for axis in [Axis::X, Axis::Y] {
+                if cell.align_on(axis) == Align::Stretch {
+                    *node_size.axis_mut(axis) = cell_size.axis(axis);
+                }
+            }
+            node.set_size(node_size);
+
+            let offset = vec2(
+                col_offsets[cell.col] + align_in_cell(cell.align_x, cell_size.x, node_size.x),
+                row_offsets[cell.row] + align_in_cell(cell.align_y, cell_size.y, node_size.y)
+            );
+            layout.add_child(offset, node);
+        }
+
+        layout
+    }
+
+}
@@ -0,0 +1,63 @@
+
+use std::marker::PhantomData;
+
+use crate::{painter::{icon::IconShape, Painter}, Color, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{LayoutContext, LayoutResult};
+
+// How far the middle of lowercase letters sits above the baseline, as a fraction of the font size
+const TEXT_MIDDLE: f32 = 0.35;
+
+pub struct Icon<S> {
+    icon: IconShape,
+    size: Option<f32>,
+    color: Option<Color>,
+    _marker: PhantomData<S>
+}
+
+impl<S: 'static> Icon<S> {
+
+    pub fn new(icon: IconShape) -> Self {
+        Self {
+            icon,
+            size: None,
+            color: None,
+            _marker: PhantomData
+        }
+    }
+
+    // Width and height of the icon. Defaults to the theme's icon size.
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    // Defaults to the theme's text color
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        WidgetNode::new(self)
+    }
+
+}
+
+impl<S: 'static> Widget<S> for Icon<S> {
+
+    type State = ();
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, _state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let size = Vec2::splat(self.size.unwrap_or(ctx.theme.icon_size)).min(max_size);
+        let mut layout = LayoutResult::new(size);
+        // Center the icon on the text when lined up by baseline
+        layout.baseline = Some(size.y / 2.0 + ctx.theme.font_size * TEXT_MIDDLE);
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, _resp: &Response, _state: &mut WidgetState<S>) {
+        painter.icon(rect, &self.icon, self.color.unwrap_or(painter.theme.text));
+    }
+
+}
@@ -0,0 +1,140 @@
+
+use std::marker::PhantomData;
+
+use crate::{painter::{image::ImageHandle, Painter}, pos, Color, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{LayoutContext, LayoutResult};
+
+// How an image fills the space it is given
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImageFit {
+    // Scale to fit inside, keeping the aspect ratio
+    Contain,
+    // Scale to fill, keeping the aspect ratio and cropping what spills over
+    Cover,
+    // Fill, ignoring the aspect ratio
+    Stretch,
+    // Draw at the image's own size, centered and cropped
+    None
+}
+
+// Borders in pixels that keep their size when the image is scaled. The middle stretches.
+#[derive(Clone, Copy)]
+struct NineSlice {
+    left: f32,
+    top: f32,
+    right: f32,
+    bottom: f32
+}
+
+pub struct Image<S> {
+    image: ImageHandle,
+    fit: ImageFit,
+    tint: Color,
+    size: Option<Vec2>,
+    nine_slice: Option<NineSlice>,
+    _marker: PhantomData<S>
+}
+
+impl<S: 'static> Image<S> {
+
+    pub fn new(image: ImageHandle) -> Self {
+        Self {
+            image,
+            fit: ImageFit::Contain,
+            tint: Color::WHITE,
+            size: None,
+            nine_slice: None,
+            _marker: PhantomData
+        }
+    }
+
+    pub fn fit(mut self, fit: ImageFit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    // Multiply the image's colors, for fading or recoloring it
+    pub fn tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    // The size of the widget. Defaults to the image's size, one point per pixel.
+    pub fn size(mut self, size: Vec2) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    // Keep borders of the given widths in pixels unscaled while stretching the middle, as for panels and buttons.
+    // The fit mode is ignored, since a nine-slice image always fills the widget.
+    pub fn nine_slice(mut self, left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        self.nine_slice = Some(NineSlice { left, top, right, bottom });
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        WidgetNode::new(self)
+    }
+
+    fn draw_nine_slice(&self, painter: &mut Painter, rect: Rect, slice: NineSlice) {
+        let image_size = self.image.size();
+        // Shrink the borders if the rect is too small to fit them
+        let scale_x = (rect.width() / (slice.left + slice.right)).min(1.0);
+        let scale_y = (rect.height() / (slice.top + slice.bottom)).min(1.0);
+
+        let src_xs = [0.0, slice.left, image_size.x - slice.right, image_size.x];
+        let src_ys = [0.0, slice.top, image_size.y - slice.bottom, image_size.y];
+        let dst_xs = [rect.left(), rect.left() + slice.left * scale_x, rect.right() - slice.right * scale_x, rect.right()];
+        let dst_ys = [rect.top(), rect.top() + slice.top * scale_y, rect.bottom() - slice.bottom * scale_y, rect.bottom()];
+
+        for row in 0..3 {
+            for col in 0..3 {
+                let src = Rect::new(pos(src_xs[col], src_ys[row]), pos(src_xs[col + 1], src_ys[row + 1]));
+                let dst = Rect::new(pos(dst_xs[col], dst_ys[row]), pos(dst_xs[col + 1], dst_ys[row + 1]));
+                painter.image_region(dst, &self.image, src, self.tint);
+            }
+        }
+    }
+
+}
+
+impl<S: 'static> Widget<S> for Image<S> {
+
+    type State = ();
+
+    fn layout(&self, max_size: Vec2, _ctx: &mut LayoutContext, _state: &mut WidgetState<S>) -> LayoutResult<S> {
+        LayoutResult::new(self.size.unwrap_or(self.image.size()).min(max_size))
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, _resp: &Response, _state: &mut WidgetState<S>) {
+        if let Some(slice) = self.nine_slice {
+            self.draw_nine_slice(painter, rect, slice);
+            return;
+        }
+
+        let image_size = self.image.size();
+        if image_size.x <= 0.0 || image_size.y <= 0.0 {
+            return;
+        }
+        let scale_x = rect.width() / image_size.x;
+        let scale_y = rect.height() / image_size.y;
+        let draw_size = match self.fit {
+            ImageFit::Contain => image_size * scale_x.min(scale_y),
+            ImageFit::Cover => image_size * scale_x.max(scale_y),
+            ImageFit::Stretch => rect.size(),
+            ImageFit::None => image_size
+        };
+        let draw_rect = Rect::center_size(rect.center(), draw_size);
+
+        let crop = matches!(self.fit, ImageFit::Cover | ImageFit::None);
+        if crop {
+            painter.push_clip_rect(rect);
+        }
+        painter.image(draw_rect, &self.image, self.tint);
+        if crop {
+            painter.pop_clip_rect();
+        }
+    }
+
+}
@@ -0,0 +1,115 @@
+use winit::event::{ModifiersState, VirtualKeyCode};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    A, B, C, D, E, F, G, H, I, J, K, L, M,
+    N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    Num0, Num1, Num2, Num3, Num4, Num5, Num6, Num7, Num8, Num9,
+    Escape,
+    Enter,
+    Tab,
+    Space,
+    Backspace,
+    Delete,
+    Insert,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown
+}
+
+impl Key {
+
+    pub(crate) fn from_winit_key(key: VirtualKeyCode) -> Option<Self> {
+        Some(match key {
+            VirtualKeyCode::A => Key::A,
+            VirtualKeyCode::B => Key::B,
+            VirtualKeyCode::C => Key::C,
+            VirtualKeyCode::D => Key::D,
+            VirtualKeyCode::E => Key::E,
+            VirtualKeyCode::F => Key::F,
+            VirtualKeyCode::G => Key::G,
+            VirtualKeyCode::H => Key::H,
+            VirtualKeyCode::I => Key::I,
+            VirtualKeyCode::J => Key::J,
+            VirtualKeyCode::K => Key::K,
+            VirtualKeyCode::L => Key::L,
+            VirtualKeyCode::M => Key::M,
+            VirtualKeyCode::N => Key::N,
+            VirtualKeyCode::O => Key::O,
+            VirtualKeyCode::P => Key::P,
+            VirtualKeyCode::Q => Key::Q,
+            VirtualKeyCode::R => Key::R,
+            VirtualKeyCode::S => Key::S,
+            VirtualKeyCode::T => Key::T,
+            VirtualKeyCode::U => Key::U,
+            VirtualKeyCode::V => Key::V,
+            VirtualKeyCode::W => Key::W,
+            VirtualKeyCode::X => Key::X,
+            VirtualKeyCode::Y => Key::Y,
+            VirtualKeyCode::Z => Key::Z,
+            VirtualKeyCode::Key0 | VirtualKeyCode::Numpad0 => Key::Num0,
+            VirtualKeyCode::Key1 | VirtualKeyCode::Numpad1 => Key::Num1,
+            VirtualKeyCode::Key2 | VirtualKeyCode::Numpad2 => Key::Num2,
+            VirtualKeyCode::Key3 | VirtualKeyCode::Numpad3 => Key::Num3,
+            VirtualKeyCode::Key4 | VirtualKeyCode::Numpad4 => Key::Num4,
+            VirtualKeyCode::Key5 | VirtualKeyCode::Numpad5 => Key::Num5,
+            VirtualKeyCode::Key6 | VirtualKeyCode::Numpad6 => Key::Num6,
+            VirtualKeyCode::Key7 | VirtualKeyCode::Numpad7 => Key::Num7,
+            VirtualKeyCode::Key8 | VirtualKeyCode::Numpad8 => Key::Num8,
+            VirtualKeyCode::Key9 | VirtualKeyCode::Numpad9 => Key::Num9,
+            VirtualKeyCode::Escape => Key::Escape,
+            VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => Key::Enter,
+            VirtualKeyCode::Tab => Key::Tab,
+            VirtualKeyCode::Space => Key::Space,
+            VirtualKeyCode::Back => Key::Backspace,
+            VirtualKeyCode::Delete => Key::Delete,
+            VirtualKeyCode::Insert => Key::Insert,
+            VirtualKeyCode::Left => Key::Left,
+            VirtualKeyCode::Right => Key::Right,
+            VirtualKeyCode::Up => Key::Up,
+            VirtualKeyCode::Down => Key::Down,
+            VirtualKeyCode::Home => Key::Home,
+            VirtualKeyCode::End => Key::End,
+            VirtualKeyCode::PageUp => Key::PageUp,
+            VirtualKeyCode::PageDown => Key::PageDown,
+            _ => return None
+        })
+    }
+
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    // The Windows/Command key
+    pub logo: bool
+}
+
+impl Modifiers {
+
+    pub(crate) fn from_winit_modifiers(modifiers: ModifiersState) -> Self {
+        Self {
+            shift: modifiers.shift(),
+            ctrl: modifiers.ctrl(),
+            alt: modifiers.alt(),
+            logo: modifiers.logo()
+        }
+    }
+
+    // Ctrl on Windows and Linux, Command on macOS
+    pub fn command(&self) -> bool {
+        if cfg!(target_os = "macos") {
+            self.logo
+        } else {
+            self.ctrl
+        }
+    }
+
+}
@@ -9,10 +9,14 @@ pub struct LayoutNode<'ui, S> {
     // Stage 1: Layout
     pub(crate) local_id: usize,
     pub(crate) rect: Rect,
+    pub(crate) baseline: Option<f32>,
     pub(crate) widget: &'ui WidgetNode<S>,
     pub(crate) children: Vec<(Vec2, LayoutNode<'ui, S>)>,
     pub(crate) popovers: Vec<(Vec2, LayoutNode<'ui, S>)>,
+    pub(crate) modals: Vec<LayoutNode<'ui, S>>,
     pub(crate) sensors: Vec<(Vec2, Vec2)>,
+    // Whether this node is the root of a modal layer
+    pub(crate) blocks_input: bool,
 
     // Stage 2: Input Handling
     pub(crate) response: RefCell<Response>
@@ -27,15 +31,22 @@ impl<'ui, S> LayoutNode<'ui, S> {
         widget: &'ui WidgetNode<S>,
         children: Vec<(Vec2, LayoutNode<'ui, S>)>,
         popovers: Vec<(Vec2, LayoutNode<'ui, S>)>,
+        mut modals: Vec<LayoutNode<'ui, S>>,
         sensors: Vec<(Vec2, Vec2)>
     ) -> Self {
+        for modal in &mut modals {
+            modal.blocks_input = true;
+        }
         Self {
             local_id,
             rect: Rect::min_size(Pos::ZERO, size),
+            baseline: None,
             widget,
             children,
             popovers,
+            modals,
             sensors,
+            blocks_input: false,
             response: RefCell::new(Response::new())
         }
     }
@@ -44,4 +55,23 @@ impl<'ui, S> LayoutNode<'ui, S> {
         self.rect.size()
     }
 
+    pub fn baseline(&self) -> Option<f32> {
+        self.baseline
+    }
+
+    // Grow or shrink the node after layout, like when a container stretches its children
+    pub(crate) fn set_size(&mut self, size: Vec2) {
+        self.rect = Rect::min_size(self.rect.min(), size);
+    }
+
+    fn contains_with_popovers(&self, pos: Pos) -> bool {
+        self.rect.contains(pos) || self.popovers_contain(pos)
+    }
+
+    // Does any popover opened by this node or its children contain the position?
+    pub(crate) fn popovers_contain(&self, pos: Pos) -> bool {
+        self.popovers.iter().any(|(_, popover)| popover.contains_with_popovers(pos)) ||
+            self.children.iter().any(|(_, child)| child.popovers_contain(pos))
+    }
+
 }
@@ -0,0 +1,136 @@
+
+use crate::{state::WidgetState, Axis, LayoutNode, Vec2, WidgetNode};
+
+use super::{LayoutContext, LayoutResult};
+
+// How items are placed across a row or column
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Align {
+    #[default]
+    Start,
+    Center,
+    End,
+    // Grow every item to fill the available cross space, or to the largest item's size when that is unbounded
+    Stretch,
+    // Line up the first line of text of every item. Only meaningful in a row.
+    Baseline
+}
+
+// How items are distributed along a row or column when there is room left over
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Justify {
+    #[default]
+    Start,
+    Center,
+    End,
+    SpaceBetween
+}
+
+// Shared layout of `Row` and `Column`, placing the items one after another along `axis`
+#[derive(Clone, Copy)]
+pub(crate) struct Linear {
+    pub(crate) axis: Axis,
+    pub(crate) spacing: Option<f32>,
+    pub(crate) align: Align,
+    pub(crate) justify: Justify
+}
+
+impl Linear {
+
+    pub(crate) fn new(axis: Axis) -> Self {
+        Self {
+            axis,
+            spacing: None,
+            align: Align::Start,
+            justify: Justify::Start
+        }
+    }
+
+    pub(crate) fn layout<'ui, S>(&self, contents: &'ui [WidgetNode<S>], max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<'ui, S> {
+        let Self { axis, spacing, align, justify } = *self;
+        let cross = axis.other();
+        let spacing = spacing.unwrap_or(ctx.theme.item_spacing);
+
+        let mut items = Vec::new();
+        let mut main_size: f32 = 0.0;
+        for item in contents {
+            if !items.is_empty() {
+                main_size += spacing;
+            }
+            let mut item_max_size = max_size;
+            *item_max_size.axis_mut(axis) = max_size.axis(axis) - main_size;
+            let item_layout = item.layout(item_max_size, ctx, state);
+            main_size += item_layout.size().axis(axis);
+            items.push(item_layout);
+            if main_size >= max_size.axis(axis) - 0.05 {
+                break;
+            }
+        }
+
+        // Items without text are lined up by their bottom edge
+        let baseline_of = |item: &LayoutNode<S>| item.baseline().unwrap_or(item.size().y);
+        let align_baselines = align == Align::Baseline && axis == Axis::X;
+        let max_baseline = items.iter().map(baseline_of).fold(0.0, f32::max);
+
+        // Items fill the available space unless they are packed at the start
+        let content_cross = if align_baselines {
+            items.iter().map(|item| max_baseline - baseline_of(item) + item.size().y).fold(0.0, f32::max)
+        } else {
+            items.iter().map(|item| item.size().axis(cross)).fold(0.0, f32::max)
+        };
+        let cross_size = if align != Align::Start && align != Align::Baseline && max_size.axis(cross).is_finite() {
+            max_size.axis(cross)
+        } else {
+            content_cross
+        };
+        let container_main = if justify != Justify::Start && max_size.axis(axis).is_finite() {
+            max_size.axis(axis).max(main_size)
+        } else {
+            main_size
+        };
+        let free = container_main - main_size;
+        let (mut main_pos, gap) = match justify {
+            Justify::Start => (0.0, spacing),
+            Justify::Center => (free / 2.0, spacing),
+            Justify::End => (free, spacing),
+            Justify::SpaceBetween if items.len() > 1 => (0.0, spacing + free / (items.len() - 1) as f32),
+            Justify::SpaceBetween => (0.0, spacing)
+        };
+
+        let mut layout = LayoutResult::new(Vec2::ZERO);
+        *layout.size.axis_mut(axis) = container_main;
+        *layout.size.axis_mut(cross) = cross_size;
+        for (content, mut item) in contents.iter().zip(items) {
+            // Stretched items fill the cross size. When it isn't the space they were laid out in,
+            // they are laid out again so their contents fill it too.
+            if align == Align::Stretch {
+                let mut size = item.size();
+                *size.axis_mut(cross) = cross_size;
+                if max_size.axis(cross) != cross_size {
+                    item = content.layout_with_id(item.local_id, size, ctx, state);
+                }
+                item.set_size(size);
+            }
+            let item_cross = item.size().axis(cross);
+            let cross_pos = match align {
+                Align::Start | Align::Stretch => 0.0,
+                Align::Center => (cross_size - item_cross) / 2.0,
+                Align::End => cross_size - item_cross,
+                Align::Baseline if align_baselines => max_baseline - baseline_of(&item),
+                Align::Baseline => 0.0
+            };
+
+            let mut offset = Vec2::ZERO;
+            *offset.axis_mut(axis) = main_pos;
+            *offset.axis_mut(cross) = cross_pos;
+            main_pos += item.size().axis(axis) + gap;
+            layout.add_child(offset, item);
+        }
+        if align_baselines {
+            layout.baseline = Some(max_baseline);
+        }
+
+        layout
+    }
+
+}
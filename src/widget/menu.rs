@@ -0,0 +1,186 @@
+
+use std::{cell::RefCell, marker::PhantomData};
+
+use crate::{painter::{icon::IconShape, Painter, RectBuilder}, pos, vec2, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{dropdown::Dropdown, LayoutContext, LayoutResult};
+
+pub(crate) const MENU_ITEM_HEIGHT: f32 = 22.0;
+const MENU_ITEM_MIN_WIDTH: f32 = 150.0;
+pub(crate) const MENU_ITEM_PADDING: f32 = 8.0;
+
+// Per-node state for widgets with a context menu
+pub(crate) struct ContextMenuState {
+    // Offset of the menu from the top left of the widget
+    pub(crate) pos: Vec2,
+    // Local id of the menu's popover, once it has been laid out
+    pub(crate) menu_id: Option<usize>
+}
+
+pub(crate) fn update_context_menu<S>(painter: &mut Painter, rect: Rect, resp: &Response, state: &mut WidgetState<S>) {
+    if (resp.mouse_clicked() || resp.clicked_elsewhere()) && !resp.popover_hovered() {
+        state.close_context_menu();
+    }
+    if resp.right_mouse_clicked() {
+        if let Some(hover_pos) = resp.hover_pos() {
+            state.close_context_menu();
+            state.context_menu = Some(ContextMenuState {
+                pos: hover_pos - rect.min(),
+                menu_id: None
+            });
+            painter.request_redraw();
+        }
+    }
+}
+
+fn draw_menu_item_bg(painter: &mut Painter, rect: Rect, resp: &Response, highlighted: bool) {
+    let darkness = if resp.mouse_down() {
+        painter.theme.pressed_darkness
+    } else if resp.hovered() || highlighted {
+        painter.theme.hovered_darkness
+    } else {
+        return;
+    };
+    let color = painter.theme.bg_light.darken(darkness);
+    painter.rect(RectBuilder::new(rect).fill(color).rounding(painter.theme.rounding / 2.0));
+}
+
+// Room taken by an item's icon before its label
+fn icon_width(icon: Option<&IconShape>, icon_size: f32) -> f32 {
+    if icon.is_some() { icon_size + MENU_ITEM_PADDING / 2.0 } else { 0.0 }
+}
+
+fn layout_menu_item(label: &str, icon: Option<&IconShape>, max_size: Vec2, ctx: &mut LayoutContext) -> (Vec2, Vec2) {
+    let label_size = ctx.text_shaper.measure_text(ctx.theme.font_size, label);
+    let content_width = label_size.x + icon_width(icon, ctx.theme.icon_size);
+    let size = vec2((content_width + 4.0 * MENU_ITEM_PADDING).max(MENU_ITEM_MIN_WIDTH), MENU_ITEM_HEIGHT).min(max_size);
+    (size, label_size)
+}
+
+fn draw_menu_item_label(painter: &mut Painter, rect: Rect, label: &str, icon: Option<&IconShape>, label_size: Vec2) {
+    if let Some(icon) = icon {
+        let icon_size = painter.theme.icon_size.min(rect.height());
+        let icon_rect = Rect::min_size(pos(rect.left() + MENU_ITEM_PADDING, rect.center().y - icon_size / 2.0), Vec2::splat(icon_size));
+        painter.icon(icon_rect, icon, painter.theme.text);
+    }
+    let label_x = rect.left() + MENU_ITEM_PADDING + icon_width(icon, painter.theme.icon_size);
+    let label_pos = pos(label_x, rect.center().y + label_size.y / 2.0);
+    painter.text(label, label_pos, painter.theme.text, painter.theme.font_size);
+}
+
+// A clickable row in a menu. Attach an action with `on_click`.
+pub struct MenuItem<S> {
+    label: String,
+    icon: Option<IconShape>,
+    label_size: RefCell<Vec2>,
+    _marker: PhantomData<S>
+}
+
+impl<S: 'static> MenuItem<S> {
+
+    pub fn new<T: Into<String>>(label: T) -> WidgetNode<S> {
+        Self::new_with_icon(label, None)
+    }
+
+    pub fn with_icon<T: Into<String>>(icon: IconShape, label: T) -> WidgetNode<S> {
+        Self::new_with_icon(label, Some(icon))
+    }
+
+    fn new_with_icon<T: Into<String>>(label: T, icon: Option<IconShape>) -> WidgetNode<S> {
+        WidgetNode::new(Self {
+            label: label.into(),
+            icon,
+            label_size: RefCell::new(Vec2::ZERO),
+            _marker: PhantomData
+        }).sense_click(true)
+    }
+
+}
+
+impl<S> Widget<S> for MenuItem<S> {
+
+    type State = ();
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, _state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let (size, label_size) = layout_menu_item(&self.label, self.icon.as_ref(), max_size, ctx);
+        *self.label_size.borrow_mut() = label_size;
+        LayoutResult::new(size)
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, _state: &mut WidgetState<S>) {
+        draw_menu_item_bg(painter, rect, resp, false);
+        draw_menu_item_label(painter, rect, &self.label, self.icon.as_ref(), *self.label_size.borrow());
+    }
+
+}
+
+// A menu row that opens a nested menu to its side when hovered
+pub struct SubMenu<S> {
+    label: String,
+    icon: Option<IconShape>,
+    label_size: RefCell<Vec2>,
+    dropdown: WidgetNode<S>
+}
+
+impl<S: 'static> SubMenu<S> {
+
+    pub fn new<T: Into<String>>(label: T, contents: WidgetNode<S>) -> WidgetNode<S> {
+        Self::new_with_icon(label, None, contents)
+    }
+
+    pub fn with_icon<T: Into<String>>(icon: IconShape, label: T, contents: WidgetNode<S>) -> WidgetNode<S> {
+        Self::new_with_icon(label, Some(icon), contents)
+    }
+
+    fn new_with_icon<T: Into<String>>(label: T, icon: Option<IconShape>, contents: WidgetNode<S>) -> WidgetNode<S> {
+        WidgetNode::new(Self {
+            label: label.into(),
+            icon,
+            label_size: RefCell::new(Vec2::ZERO),
+            dropdown: Dropdown::new(contents)
+        }).sense_click(true)
+    }
+
+}
+
+impl<S: 'static> Widget<S> for SubMenu<S> {
+
+    type State = bool;
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let (size, label_size) = layout_menu_item(&self.label, self.icon.as_ref(), max_size, ctx);
+        *self.label_size.borrow_mut() = label_size;
+        let mut layout = LayoutResult::new(size);
+        if *Self::get(state) {
+            // Line the first item of the submenu up with this row
+            layout.add_popover(vec2(size.x + MENU_ITEM_PADDING, -10.0), self.dropdown.layout_popover(ctx, state));
+        }
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, state: &mut WidgetState<S>) {
+        let open = Self::get(state);
+        if resp.hovered() {
+            if !*open {
+                painter.request_redraw();
+            }
+            *open = true;
+        } else if !resp.popover_hovered() {
+            // Moving onto a sibling row or clicking outside closes the submenu
+            let on_sibling = resp.global_hover_pos().is_some_and(|hover_pos| hover_pos.x >= rect.left() && hover_pos.x <= rect.right());
+            if on_sibling || resp.clicked_elsewhere() {
+                *open = false;
+            }
+        }
+
+        draw_menu_item_bg(painter, rect, resp, *open);
+        draw_menu_item_label(painter, rect, &self.label, self.icon.as_ref(), *self.label_size.borrow());
+
+        let arrow_x = rect.right() - MENU_ITEM_PADDING;
+        let arrow_y = rect.center().y;
+        let arrow_size = 3.5;
+        painter.line(pos(arrow_x - arrow_size, arrow_y - arrow_size), pos(arrow_x, arrow_y), painter.theme.text);
+        painter.line(pos(arrow_x - arrow_size, arrow_y + arrow_size), pos(arrow_x, arrow_y), painter.theme.text);
+    }
+
+}
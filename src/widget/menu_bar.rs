@@ -1,7 +1,7 @@
 
 use std::{cell::RefCell, marker::PhantomData};
 
-use crate::{painter::{Painter, RectBuilder}, pos, state::WidgetState, vec2, Color, Rect, Response, Vec2, Widget, WidgetNode};
+use crate::{painter::{Painter, RectBuilder}, pos, state::WidgetState, vec2, Rect, Response, Vec2, Widget, WidgetNode};
 
 use super::{dropdown::Dropdown, LayoutContext, LayoutResult};
 
@@ -42,7 +42,7 @@ impl<S: 'static> Widget<S> for MenuBarItem<S> {
     }
 
     fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, state: &mut WidgetState<S>) {
-        let color = painter.theme.bg_window.lerp(Color::BLACK, if *Self::get(state) {
+        let color = painter.theme.bg_window.darken(if *Self::get(state) {
             painter.theme.pressed_darkness
         } else if resp.hovered() {
             painter.theme.hovered_darkness
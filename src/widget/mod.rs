@@ -1,20 +1,45 @@
 
 pub mod response;
+pub mod key;
 pub mod widget_node;
 pub mod layout_node;
 
 pub mod text;
+pub mod image;
+pub mod icon;
 pub mod button;
 pub mod slider;
+pub mod drag_value;
+pub mod color_picker;
+pub mod toggle;
+pub mod canvas_2d;
 
 pub mod menu_bar;
 pub mod dropdown;
+pub mod combo_box;
+pub mod menu;
+pub mod tooltip;
+
+pub mod modal;
+pub mod dialog;
+pub mod floating_window;
+pub mod dock;
 
 pub mod margin;
 pub mod center;
+pub mod linear;
 pub mod column;
+pub mod row;
 pub mod split;
+pub mod flex;
+pub mod grid;
+pub mod stack;
+pub mod tabs;
 pub mod scroll_area;
+pub mod virtual_list;
+pub mod tree_view;
+pub mod timeline;
+pub mod curve_editor;
 
 use std::{any::{Any, TypeId}, rc::Rc};
 
@@ -44,6 +69,8 @@ impl<S> Message<S> {
 pub struct LayoutContext<'a> {
     pub text_shaper: &'a TextShaper<'a>,
     pub theme: &'a Theme,
+    // The app state the widget tree was built from, for widgets built lazily during layout
+    pub(crate) app_state: &'a dyn Any,
 
     pub(crate) curr_auto_id: usize,
 
@@ -52,8 +79,11 @@ pub struct LayoutContext<'a> {
 
 pub struct LayoutResult<'ui, S> {
     pub size: Vec2,
+    // Distance from the top to the baseline of the first line of text, used to line up rows
+    pub baseline: Option<f32>,
     children: Vec<(Vec2, LayoutNode<'ui, S>)>,
     popovers: Vec<(Vec2, LayoutNode<'ui, S>)>,
+    modals: Vec<LayoutNode<'ui, S>>,
     sensors: Vec<(Vec2, Vec2)>
 }
 
@@ -62,8 +92,10 @@ impl<'ui, S> LayoutResult<'ui, S> {
     pub fn new(size: Vec2) -> Self {
         Self {
             size,
+            baseline: None,
             children: Vec::new(),
             popovers: Vec::new(),
+            modals: Vec::new(),
             sensors: Vec::new()
         }
     }
@@ -76,6 +108,11 @@ impl<'ui, S> LayoutResult<'ui, S> {
         self.popovers.push((offset, popover));
     }
     
+    // Modals cover the whole window, are drawn above all other layers and block their input
+    pub fn add_modal(&mut self, modal: LayoutNode<'ui, S>) {
+        self.modals.push(modal);
+    }
+
     pub fn add_sensor(&mut self, offset: Vec2, size: Vec2) {
         self.sensors.push((offset, size));
     }
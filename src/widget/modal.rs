@@ -0,0 +1,67 @@
+
+use crate::{painter::{Painter, RectBuilder}, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{LayoutContext, LayoutResult};
+
+const MODAL_MARGIN: f32 = 20.0;
+
+// Covers the window, dims everything behind it and centers the dialog
+struct ModalBackdrop<S> {
+    dialog: WidgetNode<S>
+}
+
+impl<S> Widget<S> for ModalBackdrop<S> {
+
+    type State = ();
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let dialog_layout = self.dialog.layout((max_size - Vec2::splat(2.0 * MODAL_MARGIN)).max(Vec2::ZERO), ctx, state);
+        let dialog_min = (max_size - dialog_layout.size()) / 2.0;
+
+        let mut layout = LayoutResult::new(max_size);
+        layout.add_child(dialog_min, dialog_layout);
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, _resp: &Response, _state: &mut WidgetState<S>) {
+        painter.rect(RectBuilder::new(rect).fill(painter.theme.backdrop));
+    }
+
+}
+
+// Shows a dialog above the contents while it is `Some`, blocking all input to the rest of the app
+pub struct Modal<S> {
+    contents: WidgetNode<S>,
+    backdrop: Option<WidgetNode<S>>
+}
+
+impl<S: 'static> Modal<S> {
+
+    pub fn new(contents: WidgetNode<S>, dialog: Option<WidgetNode<S>>) -> WidgetNode<S> {
+        WidgetNode::new(Self {
+            contents,
+            backdrop: dialog.map(|dialog| WidgetNode::new(ModalBackdrop {
+                dialog
+            }).sense_click(true))
+        })
+    }
+
+}
+
+impl<S> Widget<S> for Modal<S> {
+
+    type State = ();
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let contents_layout = self.contents.layout(max_size, ctx, state);
+        let mut layout = LayoutResult::new(contents_layout.size());
+        layout.add_child(Vec2::ZERO, contents_layout);
+
+        if let Some(backdrop) = &self.backdrop {
+            layout.add_modal(backdrop.layout_popover(ctx, state));
+        }
+
+        layout
+    }
+
+}
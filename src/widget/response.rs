@@ -1,6 +1,8 @@
 
 use crate::{Pos, Vec2};
 
+use super::key::{Key, Modifiers};
+
 #[derive(Clone, Copy)]
 pub struct EdgedInput {
     down: bool,
@@ -43,11 +45,22 @@ pub struct Response {
     pub(crate) hover_pos: Option<Pos>,
     pub(crate) left_mouse_button: EdgedInput, 
     pub(crate) right_mouse_button: EdgedInput, 
+    pub(crate) middle_mouse_button: EdgedInput,
+    pub(crate) double_clicked: bool,
     pub(crate) scroll: Vec2,
 
     pub(crate) global_hover_pos: Option<Pos>,
+    // The pointer, unless a layer above this widget's layer took it
+    pub(crate) layer_hover_pos: Option<Pos>,
     pub(crate) global_left_mouse_button: EdgedInput, 
-    pub(crate) global_right_mouse_button: EdgedInput 
+    pub(crate) global_right_mouse_button: EdgedInput,
+    pub(crate) global_middle_mouse_button: EdgedInput,
+    pub(crate) popover_hovered: bool,
+    pub(crate) keys_pressed: Vec<Key>,
+    pub(crate) keys_down: Vec<Key>,
+    pub(crate) text_input: String,
+    pub(crate) modifiers: Modifiers,
+    pub(crate) time: f32
 }
 
 impl Response {
@@ -58,11 +71,21 @@ impl Response {
             hover_pos: None,
             left_mouse_button: EdgedInput::new(), 
             right_mouse_button: EdgedInput::new(),
+            middle_mouse_button: EdgedInput::new(),
+            double_clicked: false,
             scroll: Vec2::ZERO,
 
             global_hover_pos: None,
+            layer_hover_pos: None,
             global_left_mouse_button: EdgedInput::new(), 
-            global_right_mouse_button: EdgedInput::new()
+            global_right_mouse_button: EdgedInput::new(),
+            global_middle_mouse_button: EdgedInput::new(),
+            popover_hovered: false,
+            keys_pressed: Vec::new(),
+            keys_down: Vec::new(),
+            text_input: String::new(),
+            modifiers: Modifiers::default(),
+            time: 0.0
         }
     }
 
@@ -86,6 +109,27 @@ impl Response {
         self.left_mouse_button.down()
     }
 
+    // The second press of the left mouse button in quick succession
+    pub fn mouse_double_clicked(&self) -> bool {
+        self.double_clicked
+    }
+
+    pub fn right_mouse_clicked(&self) -> bool {
+        self.right_mouse_button.pressed()
+    }
+
+    pub fn right_mouse_down(&self) -> bool {
+        self.right_mouse_button.down()
+    }
+
+    pub fn middle_mouse_clicked(&self) -> bool {
+        self.middle_mouse_button.pressed()
+    }
+
+    pub fn middle_mouse_down(&self) -> bool {
+        self.middle_mouse_button.down()
+    }
+
     pub fn scroll(&self) -> Vec2 {
         self.scroll
     }
@@ -94,6 +138,11 @@ impl Response {
         (self.global_left_mouse_button.pressed() || self.global_right_mouse_button.pressed()) && !self.left_mouse_button.pressed()
     }
 
+    // Whether the mouse is over one of this widget's popovers, or a popover nested inside them
+    pub fn popover_hovered(&self) -> bool {
+        self.popover_hovered
+    }
+
     pub fn global_hover_pos(&self) -> Option<Pos> {
         self.global_hover_pos
     }
@@ -102,4 +151,44 @@ impl Response {
         self.global_left_mouse_button.released() 
     }
 
+    pub fn global_middle_mouse_released(&self) -> bool {
+        self.global_middle_mouse_button.released()
+    }
+
+    // Withhold the keyboard from a widget while another one has keyboard focus
+    pub(crate) fn clear_keys(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_down.clear();
+        self.text_input.clear();
+    }
+
+    // Keyboard input goes to every widget, unless one holds keyboard focus. Widgets that should only
+    // react once selected ask for it with `WidgetState::request_keyboard_focus`.
+    pub fn key_pressed(&self, key: Key) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    // Keys pressed since the last frame, in order, including key repeats
+    pub fn keys_pressed(&self) -> &[Key] {
+        &self.keys_pressed
+    }
+
+    pub fn key_down(&self, key: Key) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    // Text typed since the last frame. Like keys, it goes to every widget unless one holds keyboard focus.
+    pub fn text_input(&self) -> &str {
+        &self.text_input
+    }
+
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    // Seconds since the app started
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
 }
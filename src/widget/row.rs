@@ -0,0 +1,55 @@
+use crate::{state::WidgetState, Axis, Vec2, Widget, WidgetNode};
+
+use super::{linear::{Align, Justify, Linear}, LayoutContext, LayoutResult};
+
+pub struct Row<S> {
+    contents: Vec<WidgetNode<S>>,
+    linear: Linear
+}
+
+impl<S: 'static> Row<S> {
+
+    pub fn new(contents: Vec<WidgetNode<S>>) -> WidgetNode<S> {
+        Self::builder(contents).build()
+    }
+
+    pub fn builder(contents: Vec<WidgetNode<S>>) -> Self {
+        Self {
+            contents,
+            linear: Linear::new(Axis::X)
+        }
+    }
+
+    // Space between items. Defaults to the theme's item spacing.
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.linear.spacing = Some(spacing);
+        self
+    }
+
+    // Vertical placement of the items
+    pub fn align(mut self, align: Align) -> Self {
+        self.linear.align = align;
+        self
+    }
+
+    // Horizontal placement of the items when the row is wider than its contents
+    pub fn justify(mut self, justify: Justify) -> Self {
+        self.linear.justify = justify;
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        WidgetNode::new(self)
+    }
+
+}
+
+impl<S> Widget<S> for Row<S> {
+
+    type State = ();
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<S> {
+        self.linear.layout(&self.contents, max_size, ctx, state)
+    }
+
+}
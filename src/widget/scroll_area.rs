@@ -3,7 +3,7 @@ use crate::{painter::{Painter, RectBuilder}, vec2, Axis, Rect, Response, Vec2, W
 
 use super::{LayoutContext, LayoutResult};
 
-const SCROLLBAR_SIZE: f32 = 10.0;
+pub(crate) const SCROLLBAR_SIZE: f32 = 10.0;
 
 pub struct ScrollArea<S> {
     inner: WidgetNode<S>,
@@ -33,16 +33,13 @@ impl<S: 'static> ScrollArea<S> {
         Self::new(inner, true, true)
     } 
 
-    fn draw_scrollbar(&self, axis: Axis, widget_state: &mut WidgetState<S>, scroll_area: Rect, painter: &mut Painter, resp: &Response) {
+    // Shared with other scrolling widgets, which keep their `ScrollAreaState` wherever `state_of` finds it
+    pub(crate) fn draw_scrollbar(axis: Axis, scroll_axis: bool, widget_state: &mut WidgetState<S>, state_of: fn(&mut WidgetState<S>) -> &mut ScrollAreaState, scroll_area: Rect, painter: &mut Painter, resp: &Response) {
         let focused = widget_state.focused();
-        let state = Self::get(widget_state);
+        let state = state_of(widget_state);
         let mut focus = None;
         let mut unfocus = false;
 
-        let scroll_axis = match axis {
-            Axis::X => self.scroll_h,
-            Axis::Y => self.scroll_v,
-        };
         if scroll_axis && state.inner_size.axis(axis) > scroll_area.dimension(axis) {
             let scrollbar_area_size = axis.unit() * scroll_area.dimension(axis) + axis.other().unit() * SCROLLBAR_SIZE;
             let scrollbar_area_min = scroll_area.min() + axis.other().unit() * scroll_area.dimension(axis.other());
@@ -57,7 +54,7 @@ impl<S: 'static> ScrollArea<S> {
                 false
             };
 
-            let color = painter.theme.button.darken(if focused && state.focused_axis == axis {
+            let color = painter.theme.button.darken(if focused && state.focused_axis == Some(axis) {
                 painter.theme.pressed_darkness
             } else {
                 if hovered {
@@ -75,7 +72,7 @@ impl<S: 'static> ScrollArea<S> {
                 state.scrollbar_mouse_offset = resp.global_hover_pos().unwrap().axis(axis) - scrollbar_pos; 
             }
 
-            if focused && axis == state.focused_axis {
+            if focused && state.focused_axis == Some(axis) {
                 if let Some(hover_pos) = resp.global_hover_pos() {
                     let new_scrollbar_pos = hover_pos.axis(axis) - state.scrollbar_mouse_offset;
                     let new_scroll = -new_scrollbar_pos / scrollbar_scale;
@@ -84,14 +81,15 @@ impl<S: 'static> ScrollArea<S> {
             }
         }
 
-        if focused && axis == state.focused_axis {
+        if focused && state.focused_axis == Some(axis) {
             if resp.global_mouse_released() {
+                state.focused_axis = None;
                 unfocus = true;
             }
         }
 
         if let Some(axis) = focus {
-            state.focused_axis = axis;
+            state.focused_axis = Some(axis);
             widget_state.request_focus(); 
         }
         if unfocus {
@@ -102,9 +100,10 @@ impl<S: 'static> ScrollArea<S> {
 }
 
 pub struct ScrollAreaState {
-    scroll: Vec2,
-    inner_size: Vec2,
-    focused_axis: Axis,
+    pub(crate) scroll: Vec2,
+    pub(crate) inner_size: Vec2,
+    // The scrollbar being dragged, so widgets sharing this state can focus for their own drags
+    focused_axis: Option<Axis>,
     scrollbar_mouse_offset: f32 
 }
 
@@ -114,13 +113,34 @@ impl Default for ScrollAreaState {
         Self {
             scroll: Vec2::ZERO,
             inner_size: Vec2::ZERO,
-            focused_axis: Axis::X,
+            focused_axis: None,
             scrollbar_mouse_offset: 0.0 
         }
     }
 
 }
 
+impl ScrollAreaState {
+
+    pub(crate) fn scrollbar_dragged(&self) -> bool {
+        self.focused_axis.is_some()
+    }
+
+    pub(crate) fn clamp_scroll(&mut self, scroll_h: bool, scroll_v: bool, scroll_area_size: Vec2) {
+        if scroll_h {
+            self.scroll.x = self.scroll.x.clamp((scroll_area_size.x - self.inner_size.x).min(0.0), 0.0);
+        } else {
+            self.scroll.x = 0.0;
+        }
+        if scroll_v {
+            self.scroll.y = self.scroll.y.clamp((scroll_area_size.y - self.inner_size.y).min(0.0), 0.0);
+        } else {
+            self.scroll.y = 0.0;
+        }
+    }
+
+}
+
 impl<S: 'static> Widget<S> for ScrollArea<S> {
 
     type State = ScrollAreaState;
@@ -145,23 +165,14 @@ impl<S: 'static> Widget<S> for ScrollArea<S> {
         );
         let scroll_area = Rect::min_size(rect.min(), scroll_area_size);
 
-        self.draw_scrollbar(Axis::X, state, scroll_area, painter, resp);
-        self.draw_scrollbar(Axis::Y, state, scroll_area, painter, resp);
+        Self::draw_scrollbar(Axis::X, self.scroll_h, state, Self::get, scroll_area, painter, resp);
+        Self::draw_scrollbar(Axis::Y, self.scroll_v, state, Self::get, scroll_area, painter, resp);
         let state = Self::get(state);
         
         painter.push_clip_rect(scroll_area);
 
         state.scroll += resp.scroll();
-        if self.scroll_h {
-            state.scroll.x = state.scroll.x.clamp((scroll_area_size.x - state.inner_size.x).min(0.0), 0.0);
-        } else {
-            state.scroll.x = 0.0;
-        }
-        if self.scroll_v {
-            state.scroll.y = state.scroll.y.clamp((scroll_area_size.y - state.inner_size.y).min(0.0), 0.0);
-        } else {
-            state.scroll.y = 0.0;
-        }
+        state.clamp_scroll(self.scroll_h, self.scroll_v, scroll_area_size);
     }
 
     fn post_draw(&self, painter: &mut Painter, _rect: Rect, _resp: &Response, _state: &mut WidgetState<S>) {
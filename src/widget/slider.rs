@@ -1,28 +1,73 @@
 
-use std::{ops::RangeInclusive, rc::Rc};
+use std::{cell::RefCell, ops::RangeInclusive, rc::Rc};
 
-use crate::{painter::{Painter, RectBuilder}, pos, vec2, Color, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+use crate::{painter::{Painter, RectBuilder}, pos, vec2, Axis, Numeric, Pos, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
 
-use super::{LayoutContext, LayoutResult};
+use super::{key::Key, LayoutContext, LayoutResult};
+
+const THICKNESS: f32 = 15.0;
+const BAR_THICKNESS: f32 = 7.5;
+// Length of a vertical slider when the space above it is unbounded
+const DEFAULT_LENGTH: f32 = 150.0;
+// Keyboard increment as a fraction of the range, for sliders without a step
+const KEYBOARD_FRACTION: f64 = 0.01;
+
+type OnSet<S, N> = Rc<dyn Fn(&mut S, N)>;
+type OnFinish<S> = Rc<dyn Fn(&mut S)>;
 
 pub struct Slider<S, N> {
     value: N,
     range: RangeInclusive<N>,
-    on_set: Option<Rc<dyn Fn(&mut S, N)>>,
-    on_finish: Option<Rc<dyn Fn(&mut S)>>
+    step: Option<N>,
+    logarithmic: bool,
+    axis: Axis,
+    show_value: bool,
+    on_set: Option<OnSet<S, N>>,
+    on_finish: Option<OnFinish<S>>,
+    // The value label and its size, when shown
+    label: RefCell<(String, Vec2)>
 }
 
-impl<S: 'static, N: 'static + Into<f32> + From<f32> + Copy> Slider<S, N> {
+impl<S: 'static, N: Numeric> Slider<S, N> {
 
     pub fn new(value: N, range: RangeInclusive<N>) -> Self {
         Self {
             value,
             range,
+            step: None,
+            logarithmic: false,
+            axis: Axis::X,
+            show_value: false,
             on_set: None,
-            on_finish: None
+            on_finish: None,
+            label: RefCell::new((String::new(), Vec2::ZERO))
         }
     }
 
+    // Snap values to multiples of the step from the start of the range
+    pub fn step(mut self, step: N) -> Self {
+        self.step = Some(step);
+        self
+    }
+
+    // Space values evenly by ratio rather than difference. Only applies to ranges above zero.
+    pub fn logarithmic(mut self, logarithmic: bool) -> Self {
+        self.logarithmic = logarithmic;
+        self
+    }
+
+    // Slide up and down, with the end of the range at the top
+    pub fn vertical(mut self) -> Self {
+        self.axis = Axis::Y;
+        self
+    }
+
+    // Show the value next to the slider
+    pub fn show_value(mut self, show_value: bool) -> Self {
+        self.show_value = show_value;
+        self
+    }
+
     pub fn on_set<F>(mut self, handler: F) -> Self where F: Fn(&mut S, N) + 'static {
         self.on_set = Some(Rc::new(handler));
         self
@@ -37,72 +82,195 @@ impl<S: 'static, N: 'static + Into<f32> + From<f32> + Copy> Slider<S, N> {
         WidgetNode::new(self).sense_click(true)
     }
 
+    fn bounds(&self) -> (f64, f64) {
+        (self.range.start().to_f64(), self.range.end().to_f64())
+    }
+
+    fn is_logarithmic(&self) -> bool {
+        let (start, end) = self.bounds();
+        self.logarithmic && start > 0.0 && end > 0.0
+    }
+
+    // Position of a value along the slider, from 0 to 1
+    fn value_to_t(&self, value: f64) -> f64 {
+        let (start, end) = self.bounds();
+        let t = if self.is_logarithmic() {
+            (value / start).ln() / (end / start).ln()
+        } else {
+            (value - start) / (end - start)
+        };
+        if t.is_finite() { t.clamp(0.0, 1.0) } else { 0.0 }
+    }
+
+    fn t_to_value(&self, t: f64) -> f64 {
+        let (start, end) = self.bounds();
+        if self.is_logarithmic() {
+            start * (end / start).powf(t)
+        } else {
+            start + t * (end - start)
+        }
+    }
+
+    // Snap to the step, keep within the range and convert to the slider's type
+    fn constrain(&self, value: f64) -> N {
+        let (start, end) = self.bounds();
+        let value = match self.step.map(N::to_f64) {
+            Some(step) if step > 0.0 => start + ((value - start) / step).round() * step,
+            _ => value
+        };
+        N::from_f64(value.clamp(start.min(end), start.max(end)))
+    }
+
+    fn format_value(&self) -> String {
+        if N::INTEGRAL {
+            format!("{}", self.value.to_f64())
+        } else {
+            format!("{:.2}", self.value.to_f64())
+        }
+    }
+
+    // The part of the slider taken by the track, leaving room for the value label
+    fn track_rect(&self, rect: Rect) -> Rect {
+        let label_size = self.label.borrow().1;
+        match self.axis {
+            Axis::X => Rect::min_size(rect.min(), vec2(rect.width() - label_size.x, rect.height())),
+            Axis::Y => Rect::min_size(rect.min(), vec2(rect.width(), rect.height() - label_size.y))
+        }
+    }
+
+    fn head_pos(&self, track: Rect, head_r: f32, t: f32) -> Pos {
+        match self.axis {
+            Axis::X => pos(track.left() + head_r + t * (track.width() - 2.0 * head_r), track.center().y),
+            Axis::Y => pos(track.center().x, track.bottom() - head_r - t * (track.height() - 2.0 * head_r))
+        }
+    }
+
+    fn set(&self, value: N, state: &mut WidgetState<S>) {
+        if let Some(on_set) = self.on_set.clone() {
+            state.message(move |state| on_set(state, value));
+        }
+    }
+
+    fn finish(&self, state: &mut WidgetState<S>) {
+        if let Some(on_finish) = self.on_finish.clone() {
+            state.message(move |state| on_finish(state));
+        }
+    }
+
+    fn handle_keys(&self, resp: &Response, state: &mut WidgetState<S>) {
+        let (start, end) = self.bounds();
+        let increment = match self.step.map(N::to_f64) {
+            Some(step) if step > 0.0 => step,
+            _ if N::INTEGRAL => 1.0,
+            _ => (end - start).abs() * KEYBOARD_FRACTION
+        };
+        let value = self.value.to_f64();
+        let new_value = if resp.key_pressed(Key::Right) || resp.key_pressed(Key::Up) {
+            Some(value + increment)
+        } else if resp.key_pressed(Key::Left) || resp.key_pressed(Key::Down) {
+            Some(value - increment)
+        } else if resp.key_pressed(Key::Home) {
+            Some(start)
+        } else if resp.key_pressed(Key::End) {
+            Some(end)
+        } else {
+            None
+        };
+        if let Some(new_value) = new_value {
+            self.set(self.constrain(new_value), state);
+            self.finish(state);
+        }
+    }
+
 }
 
 impl<S, N> Widget<S> for Slider<S, N> where
     S: 'static,
-    N: Into<f32> + From<f32> + Copy + 'static { 
+    N: Numeric {
+
     type State = ();
 
-    fn layout(&self, max_size: Vec2, _ctx: &mut LayoutContext, _state: &mut WidgetState<S>) -> LayoutResult<S> {
-        LayoutResult::new(max_size.min(vec2(f32::INFINITY, 15.0)))
-    }
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, _state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let label = if self.show_value { self.format_value() } else { String::new() };
+        let mut label_size = if self.show_value { ctx.text_shaper.measure_text(ctx.theme.font_size, &label) } else { Vec2::ZERO };
 
-    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, state: &mut WidgetState<S>) {
+        let size = match self.axis {
+            Axis::X => {
+                if self.show_value {
+                    label_size.x += ctx.theme.item_spacing / 2.0;
+                }
+                vec2(max_size.x, THICKNESS.max(label_size.y))
+            },
+            Axis::Y => {
+                if self.show_value {
+                    label_size.y += ctx.theme.item_spacing / 2.0;
+                }
+                vec2(THICKNESS.max(label_size.x), if max_size.y.is_finite() { max_size.y } else { DEFAULT_LENGTH })
+            }
+        };
+        *self.label.borrow_mut() = (label, label_size);
+        LayoutResult::new(size.min(max_size))
+    }
 
-        let slider_bar_h = 7.5;
-        let slider_head_r = rect.height() / 2.0;
-        let slider_bar_rect = Rect::center_size(rect.center(), vec2(
-            rect.size().x - 2.0 * slider_head_r + slider_bar_h,
-            slider_bar_h
-        ).min(rect.size()));
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let axis = self.axis;
+        let track = self.track_rect(rect);
+        let head_r = THICKNESS / 2.0;
+        let bar_rect = Rect::center_size(track.center(),
+            (axis.unit() * (track.dimension(axis) - 2.0 * head_r + BAR_THICKNESS) + axis.other().unit() * BAR_THICKNESS).min(track.size())
+        );
+        painter.rect(RectBuilder::new(bar_rect).fill(painter.theme.bg_light).rounding(BAR_THICKNESS / 2.0));
 
-        painter.rect(RectBuilder::new(slider_bar_rect).fill(painter.theme.bg_light).rounding(slider_bar_h / 2.0));
+        let t = self.value_to_t(self.value.to_f64()) as f32;
+        let head_rect = Rect::center_size(self.head_pos(track, head_r, t), Vec2::splat(2.0 * head_r).min(track.size()));
+        let mouse_on_head = resp.hover_pos().is_some_and(|hover_pos| (hover_pos - head_rect.center()).length() <= head_r);
 
-        let t = (self.value.into() - (*self.range.start()).into()) / ((*self.range.end()).into() - (*self.range.start()).into());
-        let head_x = rect.left() + slider_head_r + t * (rect.width() - 2.0 * slider_head_r);
-        let head_rect = Rect::center_size(pos(head_x, rect.center().y), vec2(2.0 * slider_head_r, 2.0 * slider_head_r).min(rect.size()));
-        let mouse_on_head = if let Some(hover_pos) = resp.hover_pos() {
-            (hover_pos - head_rect.center()).length() <= slider_head_r
-        } else {
-            false
-        };
-        let head_color = painter.theme.button.lerp(Color::BLACK, if state.focused() { 
-            painter.theme.pressed_darkness
-        } else if mouse_on_head {
-            painter.theme.hovered_darkness
-        } else {
-            0.0
-        });
+        // Clicking anywhere on the track jumps to that value and starts a drag, then the arrow keys take over
+        if resp.mouse_clicked() && resp.hover_pos().is_some_and(|hover_pos| track.contains(hover_pos)) {
+            widget_state.request_focus();
+            widget_state.request_keyboard_focus();
+        }
 
-        if state.focused() {
+        let dragging = widget_state.focused() || widget_state.requested_focus;
+        if dragging {
             if let Some(hover_pos) = resp.global_hover_pos() {
-                let hover_x = hover_pos.x;
-                let hover_t = (hover_x - (rect.left() + slider_head_r)) / (rect.width() - 2.0 * slider_head_r);
-                let hover_t = hover_t.clamp(0.0, 1.0);
-                let new_val = (*self.range.start()).into() + hover_t * ((*self.range.end()).into() - (*self.range.start()).into()); 
-                if let Some(on_set) = &self.on_set {
-                    let on_set = on_set.clone();
-                    state.message(move |state| {
-                        on_set(state, new_val.into());
-                    })
+                let length = track.dimension(axis) - 2.0 * head_r;
+                let along = match axis {
+                    Axis::X => hover_pos.x - (track.left() + head_r),
+                    Axis::Y => (track.bottom() - head_r) - hover_pos.y
+                };
+                let hover_t = (along / length).clamp(0.0, 1.0);
+                let new_value = self.constrain(self.t_to_value(hover_t as f64));
+                if new_value != self.value {
+                    self.set(new_value, widget_state);
                 }
             }
 
             if resp.global_mouse_released() {
-                state.unfocus();
-                if let Some(on_finish) = &self.on_finish {
-                    let on_finish = on_finish.clone();
-                    state.message(move |state| {
-                        on_finish(state);
-                    });
-                }
+                widget_state.unfocus();
+                self.finish(widget_state);
             }
-        }
-        if mouse_on_head && resp.mouse_clicked() {
-            state.request_focus();
+        } else if widget_state.keyboard_focused() {
+            self.handle_keys(resp, widget_state);
         }
 
-        painter.rect(RectBuilder::new(head_rect).rounding(slider_head_r).fill(head_color).stroke(painter.theme.stroke));
+        let head_color = painter.theme.button.darken(if dragging {
+            painter.theme.pressed_darkness
+        } else if mouse_on_head {
+            painter.theme.hovered_darkness
+        } else {
+            0.0
+        });
+        painter.rect(RectBuilder::new(head_rect).rounding(head_r).fill(head_color).stroke(painter.theme.stroke));
+
+        if self.show_value {
+            let label = self.label.borrow();
+            let (label, label_size) = &*label;
+            let label_pos = match axis {
+                Axis::X => pos(rect.right() - label_size.x + painter.theme.item_spacing / 2.0, rect.center().y + label_size.y / 2.0),
+                Axis::Y => pos(rect.center().x - label_size.x / 2.0, rect.bottom())
+            };
+            painter.text(label, label_pos, painter.theme.text, painter.theme.font_size);
+        }
     }
-} 
+}
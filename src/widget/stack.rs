@@ -0,0 +1,99 @@
+
+use crate::{state::WidgetState, Axis, Vec2, Widget, WidgetNode};
+
+use super::{linear::Align, LayoutContext, LayoutResult};
+
+pub struct StackLayer<S> {
+    node: WidgetNode<S>,
+    align_x: Align,
+    align_y: Align,
+    offset: Vec2
+}
+
+impl<S> StackLayer<S> {
+
+    pub fn new(node: WidgetNode<S>) -> Self {
+        Self {
+            node,
+            align_x: Align::Start,
+            align_y: Align::Start,
+            offset: Vec2::ZERO
+        }
+    }
+
+    // Placement of the layer within the stack. Baseline alignment is treated as start.
+    pub fn align(mut self, x: Align, y: Align) -> Self {
+        self.align_x = x;
+        self.align_y = y;
+        self
+    }
+
+    // Moves the layer after it is aligned
+    pub fn offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    fn align_on(&self, axis: Axis) -> Align {
+        match axis {
+            Axis::X => self.align_x,
+            Axis::Y => self.align_y
+        }
+    }
+
+}
+
+// Layers drawn on top of each other in the same rect, the last one on top.
+// The topmost layer under the mouse gets the input.
+pub struct Stack<S> {
+    layers: Vec<StackLayer<S>>
+}
+
+impl<S: 'static> Stack<S> {
+
+    pub fn new(layers: Vec<StackLayer<S>>) -> WidgetNode<S> {
+        WidgetNode::new(Self {
+            layers
+        })
+    }
+
+}
+
+impl<S> Widget<S> for Stack<S> {
+
+    type State = ();
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let nodes: Vec<_> = self.layers.iter().map(|layer| layer.node.layout(max_size, ctx, state)).collect();
+        let size = nodes.iter().fold(Vec2::ZERO, |size, node| size.max(node.size()));
+
+        let mut layout = LayoutResult::new(size);
+        for (layer, mut node) in self.layers.iter().zip(nodes) {
+            let mut node_size = node.size();
+            let mut offset = layer.offset;
+            let mut relayout = false;
+            for axis in [Axis::X, Axis::Y] {
+                let free = size.axis(axis) - node_size.axis(axis);
+                *offset.axis_mut(axis) += match layer.align_on(axis) {
+                    Align::Center => free / 2.0,
+                    Align::End => free,
+                    Align::Stretch => {
+                        *node_size.axis_mut(axis) = size.axis(axis);
+                        relayout |= max_size.axis(axis) != size.axis(axis);
+                        0.0
+                    },
+                    Align::Start | Align::Baseline => 0.0
+                };
+            }
+            // Stretched layers are laid out again so their contents fill the stretched size
+            if relayout {
+                node = layer.node.layout_with_id(node.local_id, node_size, ctx, state);
+            }
+            node.set_size(node_size);
+            layout.add_child(offset, node);
+        }
+
+        layout
+    }
+
+}
@@ -0,0 +1,342 @@
+
+use std::{cell::RefCell, collections::hash_map::DefaultHasher, hash::{Hash, Hasher}, rc::Rc};
+
+use crate::{painter::{Painter, RectBuilder}, pos, vec2, Color, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{LayoutContext, LayoutResult};
+
+pub(crate) const TAB_HEIGHT: f32 = 24.0;
+pub(crate) const TAB_PADDING: f32 = 10.0;
+const CLOSE_BUTTON_SIZE: f32 = 14.0;
+pub(crate) const DRAG_THRESHOLD: f32 = 5.0;
+
+// Draw the strip behind the tabs drawn by `draw_tabs`, which are clipped to it.
+// Shared with the dock area's panel groups.
+pub(crate) fn draw_tab_strip<F: FnOnce(&mut Painter)>(painter: &mut Painter, strip: Rect, draw_tabs: F) {
+    painter.rect(RectBuilder::new(strip).fill(painter.theme.bg_window));
+    painter.push_clip_rect(strip);
+    draw_tabs(painter);
+    painter.pop_clip_rect();
+    painter.line(strip.bottom_left(), strip.bottom_right(), painter.theme.stroke);
+}
+
+// Returns the tab's fill, for drawing on top of it
+pub(crate) fn draw_tab(painter: &mut Painter, tab_rect: Rect, label: &str, label_size: Vec2, selected: bool, hovered: bool) -> Color {
+    let fill = if selected {
+        painter.theme.bg_light
+    } else if hovered {
+        painter.theme.bg_window.darken(painter.theme.hovered_darkness)
+    } else {
+        painter.theme.bg_window
+    };
+    painter.rect(RectBuilder::new(tab_rect).fill(fill));
+    painter.push_clip_rect(tab_rect);
+    painter.text(label, pos(tab_rect.left() + TAB_PADDING, tab_rect.center().y + label_size.y / 2.0), painter.theme.text, painter.theme.font_size);
+    painter.pop_clip_rect();
+    painter.line(tab_rect.top_right(), tab_rect.bottom_right(), painter.theme.stroke);
+    fill
+}
+
+struct TabDrag {
+    idx: usize,
+    start_x: f32,
+    dragging: bool
+}
+
+#[derive(Default)]
+pub struct TabsState {
+    // Used when the selected tab is not kept in the app state
+    selected: usize,
+    // Horizontal scroll of the tab strip when the tabs don't fit
+    scroll: f32,
+    // The tab the strip was last scrolled to, so a newly selected tab is brought into view once
+    scrolled_to: Option<usize>,
+    // Tab rects relative to the widget, with the scroll applied
+    tab_rects: Vec<Rect>,
+    drag: Option<TabDrag>
+}
+
+type OnTab<S> = Rc<dyn Fn(&mut S, usize)>;
+type OnReorder<S> = Rc<dyn Fn(&mut S, usize, usize)>;
+
+// A strip of tabs above a content area showing only the selected tab
+pub struct Tabs<S> {
+    tabs: Vec<(String, WidgetNode<S>)>,
+    label_sizes: RefCell<Vec<Vec2>>,
+    selected: Option<usize>,
+    on_select: Option<OnTab<S>>,
+    on_close: Option<OnTab<S>>,
+    on_reorder: Option<OnReorder<S>>
+}
+
+impl<S: 'static> Default for Tabs<S> {
+
+    fn default() -> Self {
+        Self::new()
+    }
+
+}
+
+impl<S: 'static> Tabs<S> {
+
+    pub fn new() -> Self {
+        Self {
+            tabs: Vec::new(),
+            label_sizes: RefCell::new(Vec::new()),
+            selected: None,
+            on_select: None,
+            on_close: None,
+            on_reorder: None
+        }
+    }
+
+    // Tab contents keep their state by label unless they are given an id, so it follows the tab when tabs move or close
+    pub fn tab<T: Into<String>>(mut self, label: T, contents: WidgetNode<S>) -> Self {
+        self.tabs.push((label.into(), contents));
+        self
+    }
+
+    // Show the given tab instead of keeping the selection in the widget state
+    pub fn selected(mut self, idx: usize) -> Self {
+        self.selected = Some(idx);
+        self
+    }
+
+    pub fn on_select<F>(mut self, handler: F) -> Self where F: Fn(&mut S, usize) + 'static {
+        self.on_select = Some(Rc::new(handler));
+        self
+    }
+
+    // Show a close button on every tab
+    pub fn on_close<F>(mut self, handler: F) -> Self where F: Fn(&mut S, usize) + 'static {
+        self.on_close = Some(Rc::new(handler));
+        self
+    }
+
+    // Allow dragging tabs within the strip. Called with the old and new index of the moved tab.
+    pub fn on_reorder<F>(mut self, handler: F) -> Self where F: Fn(&mut S, usize, usize) + 'static {
+        self.on_reorder = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn build(mut self) -> WidgetNode<S> {
+        for idx in 0..self.tabs.len() {
+            if self.tabs[idx].1.local_id.is_none() {
+                // Tabs sharing a label are told apart by how many came before them
+                let label = &self.tabs[idx].0;
+                let repeat = self.tabs[..idx].iter().filter(|(other, _)| other == label).count();
+                let mut hasher = DefaultHasher::new();
+                (label, repeat).hash(&mut hasher);
+                self.tabs[idx].1.local_id = Some(hasher.finish() as usize);
+            }
+        }
+        WidgetNode::new(self).sense_click(true)
+    }
+
+    // Where the tab at `idx` ends up after the tab at `from` is moved to `to`
+    fn moved_idx(idx: usize, from: usize, to: usize) -> usize {
+        if idx == from {
+            to
+        } else if from < idx && idx <= to {
+            idx - 1
+        } else if to <= idx && idx < from {
+            idx + 1
+        } else {
+            idx
+        }
+    }
+
+    fn selected_idx(&self, widget_state: &mut WidgetState<S>) -> usize {
+        let selected = self.selected.unwrap_or_else(|| Self::get(widget_state).selected);
+        selected.min(self.tabs.len().saturating_sub(1))
+    }
+
+    // The index a dragged tab would be moved to if dropped at the given x coordinate
+    fn drop_idx(tab_rects: &[Rect], dragged: usize, x: f32) -> usize {
+        let before = tab_rects.iter().filter(|rect| rect.center().x < x).count();
+        if before > dragged {
+            before - 1
+        } else {
+            before
+        }
+    }
+
+    fn close_rect(tab_rect: Rect) -> Rect {
+        let center = pos(tab_rect.right() - TAB_PADDING / 2.0 - CLOSE_BUTTON_SIZE / 2.0, tab_rect.center().y);
+        Rect::center_size(center, Vec2::splat(CLOSE_BUTTON_SIZE))
+    }
+
+}
+
+impl<S: 'static> Widget<S> for Tabs<S> {
+
+    type State = TabsState;
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, widget_state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let label_sizes: Vec<Vec2> = self.tabs.iter().map(|(label, _)| ctx.text_shaper.measure_text(ctx.theme.font_size, label)).collect();
+        let close_width = if self.on_close.is_some() { CLOSE_BUTTON_SIZE + TAB_PADDING / 2.0 } else { 0.0 };
+        let widths: Vec<f32> = label_sizes.iter().map(|size| size.x + 2.0 * TAB_PADDING + close_width).collect();
+        let tabs_width: f32 = widths.iter().sum();
+        *self.label_sizes.borrow_mut() = label_sizes;
+
+        let selected = self.selected_idx(widget_state);
+        let contents_max_size = vec2(max_size.x, (max_size.y - TAB_HEIGHT).max(0.0));
+        let contents_layout = self.tabs.get(selected).map(|(_, contents)| contents.layout(contents_max_size, ctx, widget_state));
+        let contents_size = contents_layout.as_ref().map(|layout| layout.size()).unwrap_or(Vec2::ZERO);
+        let width = if max_size.x.is_finite() { max_size.x } else { tabs_width.max(contents_size.x) };
+
+        let state = Self::get(widget_state);
+        if state.scrolled_to != Some(selected) {
+            let tab_left: f32 = widths[..selected.min(widths.len())].iter().sum();
+            let tab_right = tab_left + widths.get(selected).copied().unwrap_or(0.0);
+            if tab_left < state.scroll {
+                state.scroll = tab_left;
+            } else if tab_right > state.scroll + width {
+                state.scroll = tab_right - width;
+            }
+            state.scrolled_to = Some(selected);
+        }
+        state.scroll = state.scroll.min(tabs_width - width).max(0.0);
+
+        let mut layout = LayoutResult::new(vec2(width, TAB_HEIGHT + contents_size.y));
+        if let Some(contents_layout) = contents_layout {
+            layout.add_child(vec2(0.0, TAB_HEIGHT), contents_layout);
+        }
+
+        // Sensors are cut to the strip so tabs scrolled out of view can't be hovered
+        let strip = Rect::min_size(pos(0.0, 0.0), vec2(width, TAB_HEIGHT));
+        state.tab_rects.clear();
+        let mut x = -state.scroll;
+        for width in &widths {
+            let tab_rect = Rect::min_size(pos(x, 0.0), vec2(*width, TAB_HEIGHT));
+            let visible = tab_rect.intersect(strip);
+            layout.add_sensor(visible.min().to_vec(), visible.size().max(Vec2::ZERO));
+            state.tab_rects.push(tab_rect);
+            x += width;
+        }
+        // Close buttons come after the tabs so they win where they overlap
+        if self.on_close.is_some() {
+            for tab_rect in &state.tab_rects {
+                let visible = Self::close_rect(*tab_rect).intersect(strip);
+                layout.add_sensor(visible.min().to_vec(), visible.size().max(Vec2::ZERO));
+            }
+        }
+
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let focused = widget_state.focused();
+        let selected = self.selected_idx(widget_state);
+        let n_tabs = self.tabs.len();
+        let strip = Rect::min_size(rect.min(), vec2(rect.width(), TAB_HEIGHT));
+        let state = Self::get(widget_state);
+
+        let hovered_tab = resp.sensor_idx().filter(|idx| *idx < n_tabs);
+        let hovered_close = resp.sensor_idx().filter(|idx| *idx >= n_tabs).map(|idx| idx - n_tabs);
+
+        if resp.hover_pos().is_some_and(|hover_pos| strip.contains(hover_pos)) {
+            let scroll = resp.scroll();
+            let delta = if scroll.x != 0.0 { scroll.x } else { scroll.y };
+            if delta != 0.0 {
+                state.scroll -= delta;
+                painter.request_redraw();
+            }
+        }
+
+        let mut drop_marker = None;
+        if focused {
+            let mouse_x = resp.global_hover_pos().map(|mouse_pos| mouse_pos.x - rect.left());
+            let mut reorder = None;
+            if let (Some(drag), Some(mouse_x)) = (&mut state.drag, mouse_x) {
+                if (mouse_x - drag.start_x).abs() > DRAG_THRESHOLD {
+                    drag.dragging = true;
+                }
+                if drag.dragging {
+                    painter.request_redraw();
+                    let target = Self::drop_idx(&state.tab_rects, drag.idx, mouse_x);
+                    if target != drag.idx {
+                        reorder = Some((drag.idx, target));
+                    }
+                    // Mark the gap the tab would be dropped into
+                    let gap_idx = if target > drag.idx { target + 1 } else { target };
+                    drop_marker = Some(state.tab_rects.get(gap_idx).map(|tab| tab.left()).unwrap_or_else(|| state.tab_rects.last().map(|tab| tab.right()).unwrap_or(0.0)));
+                }
+            }
+            if resp.global_mouse_released() {
+                let dragging = state.drag.take().is_some_and(|drag| drag.dragging);
+                widget_state.unfocus();
+                if let (true, Some((from, to)), Some(on_reorder)) = (dragging, reorder, &self.on_reorder) {
+                    // Keep the same tab selected once it has moved
+                    let state = Self::get(widget_state);
+                    state.selected = Self::moved_idx(selected, from, to);
+                    state.scrolled_to = Some(state.selected);
+                    let on_reorder = on_reorder.clone();
+                    widget_state.message(move |state| {
+                        on_reorder(state, from, to);
+                    });
+                }
+            }
+        } else if resp.mouse_clicked() {
+            if let Some(idx) = hovered_close {
+                if let Some(on_close) = &self.on_close {
+                    // Tabs after the closed one shift down, so the selection moves with them
+                    if idx < selected {
+                        state.selected = selected - 1;
+                        state.scrolled_to = Some(state.selected);
+                    }
+                    let on_close = on_close.clone();
+                    widget_state.message(move |state| {
+                        on_close(state, idx);
+                    });
+                }
+            } else if let (Some(idx), Some(hover_pos)) = (hovered_tab, resp.hover_pos()) {
+                state.selected = idx;
+                if self.on_reorder.is_some() {
+                    state.drag = Some(TabDrag {
+                        idx,
+                        start_x: hover_pos.x - rect.left(),
+                        dragging: false
+                    });
+                    widget_state.request_focus();
+                }
+                if idx != selected {
+                    if let Some(on_select) = &self.on_select {
+                        let on_select = on_select.clone();
+                        widget_state.message(move |state| {
+                            on_select(state, idx);
+                        });
+                    }
+                }
+            }
+        }
+
+        let state = Self::get(widget_state);
+        let label_sizes = self.label_sizes.borrow();
+        draw_tab_strip(painter, strip, |painter| {
+            for (idx, ((label, _), tab_rect)) in self.tabs.iter().zip(&state.tab_rects).enumerate() {
+                let tab_rect = tab_rect.shift(rect.min().to_vec());
+                let label_size = label_sizes.get(idx).copied().unwrap_or(Vec2::ZERO);
+                let fill = draw_tab(painter, tab_rect, label, label_size, idx == selected, hovered_tab == Some(idx));
+
+                if self.on_close.is_some() {
+                    let close_rect = Self::close_rect(tab_rect);
+                    if hovered_close == Some(idx) {
+                        let darkness = if resp.mouse_down() { painter.theme.pressed_darkness } else { painter.theme.hovered_darkness };
+                        painter.rect(RectBuilder::new(close_rect)
+                            .fill(fill.darken(darkness))
+                            .rounding(painter.theme.rounding / 2.0));
+                    }
+                    let cross = Rect::center_size(close_rect.center(), Vec2::splat(CLOSE_BUTTON_SIZE / 3.0));
+                    painter.line(cross.top_left(), cross.bottom_right(), painter.theme.text);
+                    painter.line(cross.top_right(), cross.bottom_left(), painter.theme.text);
+                }
+            }
+            if let Some(marker_x) = drop_marker {
+                let x = rect.left() + marker_x;
+                painter.line(pos(x, strip.top()), pos(x, strip.bottom()), painter.theme.text);
+            }
+        });
+    }
+
+}
@@ -57,9 +57,12 @@ impl<S> Widget<S> for Text<S> {
             width = width.max(size.x);
         }
 
+        let baseline = break_data.first().map(|line| line.y);
         Self::get(state).break_data = break_data;
 
-        LayoutResult::new(vec2(width, curr_y))
+        let mut layout = LayoutResult::new(vec2(width, curr_y));
+        layout.baseline = baseline;
+        layout
     }
 
     fn draw(&self, painter: &mut Painter, rect: Rect, _resp: &Response, state: &mut WidgetState<S>) {
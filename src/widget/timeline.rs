@@ -0,0 +1,504 @@
+
+use std::{cell::Cell, collections::HashSet, ops::Range, rc::Rc};
+
+use crate::{painter::{path::PathBuilder, Painter, RectBuilder}, pos, vec2, Axis, Color, Pos, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{scroll_area::{ScrollArea, ScrollAreaState, SCROLLBAR_SIZE}, LayoutContext, LayoutResult};
+
+const ROW_HEIGHT: f32 = 24.0;
+const RULER_HEIGHT: f32 = 24.0;
+const NAME_WIDTH: f32 = 120.0;
+const NAME_PADDING: f32 = 6.0;
+const MIN_FRAME_WIDTH: f32 = 2.0;
+const MAX_FRAME_WIDTH: f32 = 64.0;
+// Zoom change per point scrolled
+const ZOOM_SPEED: f32 = 0.002;
+// Ruler labels are spaced at least this far apart
+const MIN_LABEL_SPACING: f32 = 40.0;
+// Half the width of a keyframe's diamond
+const KEYFRAME_SIZE: f32 = 5.0;
+const SPAN_PADDING: f32 = 6.0;
+
+// The animation a timeline shows, implemented by the app
+pub trait TimelineData {
+
+    // Frames run from 0 up to, but not including, the frame count
+    fn frame_count(&self) -> i32;
+    fn track_count(&self) -> usize;
+    fn track_name(&self, track: usize) -> String;
+    fn keyframes(&self, track: usize) -> Vec<i32>;
+
+    // Frame ranges drawn behind the keyframes, like how long each drawing is held
+    fn spans(&self, _track: usize) -> Vec<Range<i32>> {
+        Vec::new()
+    }
+
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimelineKey {
+    pub track: usize,
+    pub frame: i32
+}
+
+struct TimelineTrack {
+    name: String,
+    keyframes: Vec<i32>,
+    spans: Vec<Range<i32>>
+}
+
+enum TimelineDrag {
+    // Dragging the playhead. Holds the last frame sent to `on_scrub`.
+    Scrub(i32),
+    // Moving the selected keyframes, from the frame they were grabbed at
+    Move(i32),
+    // Selecting the keyframes in the box from `start` to the pointer, on top of `base`
+    Select {
+        start: Pos,
+        base: HashSet<TimelineKey>
+    }
+}
+
+pub struct TimelineState {
+    // Points per frame
+    frame_width: f32,
+    scroll: ScrollAreaState,
+    selected: HashSet<TimelineKey>,
+    drag: Option<TimelineDrag>
+}
+
+impl Default for TimelineState {
+
+    fn default() -> Self {
+        Self {
+            frame_width: 12.0,
+            scroll: ScrollAreaState::default(),
+            selected: HashSet::new(),
+            drag: None
+        }
+    }
+
+}
+
+// Where frames and tracks are on screen this frame
+struct TimelineView {
+    ruler: Rect,
+    names: Rect,
+    tracks: Rect,
+    frame_width: f32,
+    scroll: Vec2
+}
+
+impl TimelineView {
+
+    // The left edge of a frame
+    fn frame_x(&self, frame: i32) -> f32 {
+        self.tracks.left() + self.scroll.x + frame as f32 * self.frame_width
+    }
+
+    fn frame_at(&self, x: f32) -> i32 {
+        ((x - self.tracks.left() - self.scroll.x) / self.frame_width).floor() as i32
+    }
+
+    fn row_top(&self, track: usize) -> f32 {
+        self.tracks.top() + self.scroll.y + track as f32 * ROW_HEIGHT
+    }
+
+    fn track_at(&self, y: f32) -> Option<usize> {
+        let row = ((y - self.tracks.top() - self.scroll.y) / ROW_HEIGHT).floor();
+        (row >= 0.0).then_some(row as usize)
+    }
+
+    fn keyframe_center(&self, key: TimelineKey) -> Pos {
+        pos(self.frame_x(key.frame) + self.frame_width / 2.0, self.row_top(key.track) + ROW_HEIGHT / 2.0)
+    }
+
+}
+
+// The frames between ruler labels, stepping 1, 2, 5, 10, 20, 50...
+fn label_step(frame_width: f32) -> i32 {
+    let mut magnitude = 1;
+    loop {
+        for step in [magnitude, magnitude * 2, magnitude * 5] {
+            if step as f32 * frame_width >= MIN_LABEL_SPACING {
+                return step;
+            }
+        }
+        magnitude *= 10;
+    }
+}
+
+// The box between where a box selection started and the pointer, shared with the curve editor
+pub(crate) fn select_rect(start: Pos, end: Pos) -> Rect {
+    Rect::new(pos(start.x.min(end.x), start.y.min(end.y)), pos(start.x.max(end.x), start.y.max(end.y)))
+}
+
+pub(crate) fn draw_select_rect(painter: &mut Painter, start: Pos, end: Pos) {
+    painter.rect(RectBuilder::new(select_rect(start, end)).fill(painter.theme.accent.with_alpha(0.2)).stroke(painter.theme.accent));
+}
+
+// A key drawn as a diamond, shared with the curve editor
+pub(crate) fn draw_key_diamond(painter: &mut Painter, center: Pos, size: f32, color: Color) {
+    let points = [center - vec2(size, 0.0), center - vec2(0.0, size), center + vec2(size, 0.0), center + vec2(0.0, size)];
+    painter.path(PathBuilder::polygon(&points).fill(color).stroke(painter.theme.stroke));
+}
+
+type OnScrub<S> = Rc<dyn Fn(&mut S, i32)>;
+type OnSelect<S> = Rc<dyn Fn(&mut S, Vec<TimelineKey>)>;
+type OnMove<S> = Rc<dyn Fn(&mut S, Vec<TimelineKey>, i32)>;
+
+// A frame ruler over rows of keyframes, for frame-based animation.
+// Click or drag the ruler to scrub, click and drag keyframes to move them, or drag a box to select several.
+// Shift adds to the selection and ctrl+scroll zooms.
+pub struct Timeline<S> {
+    frame_count: i32,
+    tracks: Vec<TimelineTrack>,
+    frame: i32,
+    text_height: Cell<f32>,
+    on_scrub: Option<OnScrub<S>>,
+    on_select: Option<OnSelect<S>>,
+    on_move: Option<OnMove<S>>
+}
+
+impl<S: 'static> Timeline<S> {
+
+    // Show the data with the playhead at `frame`
+    pub fn new<D: TimelineData>(data: &D, frame: i32) -> Self {
+        let tracks = (0..data.track_count()).map(|track| TimelineTrack {
+            name: data.track_name(track),
+            keyframes: data.keyframes(track),
+            spans: data.spans(track)
+        }).collect();
+        Self {
+            frame_count: data.frame_count(),
+            tracks,
+            frame,
+            text_height: Cell::new(0.0),
+            on_scrub: None,
+            on_select: None,
+            on_move: None
+        }
+    }
+
+    // Called with the frame under the pointer while the ruler is dragged
+    pub fn on_scrub<F>(mut self, handler: F) -> Self where F: Fn(&mut S, i32) + 'static {
+        self.on_scrub = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn on_select<F>(mut self, handler: F) -> Self where F: Fn(&mut S, Vec<TimelineKey>) + 'static {
+        self.on_select = Some(Rc::new(handler));
+        self
+    }
+
+    // Allow dragging keyframes. Called with the keyframes that moved and how many frames they moved by.
+    pub fn on_move<F>(mut self, handler: F) -> Self where F: Fn(&mut S, Vec<TimelineKey>, i32) + 'static {
+        self.on_move = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        WidgetNode::new(self).sense_click(true)
+    }
+
+    fn scroll_state(widget_state: &mut WidgetState<S>) -> &mut ScrollAreaState {
+        &mut Self::get(widget_state).scroll
+    }
+
+    fn inner_size(&self, frame_width: f32) -> Vec2 {
+        vec2(self.frame_count as f32 * frame_width, self.tracks.len() as f32 * ROW_HEIGHT)
+    }
+
+    fn keyframes(&self) -> impl Iterator<Item = TimelineKey> + '_ {
+        self.tracks.iter().enumerate().flat_map(|(track, data)| data.keyframes.iter().map(move |frame| TimelineKey { track, frame: *frame }))
+    }
+
+    // The keyframe under the pointer, if any
+    fn keyframe_at(&self, view: &TimelineView, hover_pos: Pos) -> Option<TimelineKey> {
+        let track = view.track_at(hover_pos.y)?;
+        let reach = KEYFRAME_SIZE.max(view.frame_width / 2.0);
+        self.tracks.get(track)?.keyframes.iter()
+            .map(|frame| TimelineKey { track, frame: *frame })
+            .map(|key| (key, (view.keyframe_center(key).x - hover_pos.x).abs()))
+            .filter(|(_, dist)| *dist <= reach)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(key, _)| key)
+    }
+
+    // How far the selection moves with the pointer at `x`, keeping every keyframe within the frame range
+    fn move_delta(&self, view: &TimelineView, selected: &HashSet<TimelineKey>, grab_frame: i32, x: f32) -> i32 {
+        let first = selected.iter().map(|key| key.frame).min().unwrap_or(0);
+        let last = selected.iter().map(|key| key.frame).max().unwrap_or(0);
+        let max_delta = (self.frame_count - 1 - last).max(0);
+        // With keyframes already outside the range no delta may fit both ends, so the last frame wins
+        (view.frame_at(x) - grab_frame).clamp((-first).min(max_delta), max_delta)
+    }
+
+    fn send_selection(&self, widget_state: &mut WidgetState<S>) {
+        if let Some(on_select) = self.on_select.clone() {
+            let mut selected: Vec<TimelineKey> = Self::get(widget_state).selected.iter().copied().collect();
+            selected.sort_by_key(|key| (key.track, key.frame));
+            widget_state.message(move |state| on_select(state, selected.clone()));
+        }
+    }
+
+    fn scrub(&self, widget_state: &mut WidgetState<S>, frame: i32) {
+        if let Some(on_scrub) = self.on_scrub.clone() {
+            widget_state.message(move |state| on_scrub(state, frame));
+        }
+    }
+
+    fn start_drag(&self, view: &TimelineView, hover_pos: Pos, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let additive = resp.modifiers().shift;
+        let state = Self::get(widget_state);
+
+        if view.ruler.contains(hover_pos) {
+            let frame = view.frame_at(hover_pos.x).clamp(0, (self.frame_count - 1).max(0));
+            state.drag = Some(TimelineDrag::Scrub(frame));
+            widget_state.request_focus();
+            self.scrub(widget_state, frame);
+            return;
+        }
+        if !view.tracks.contains(hover_pos) {
+            return;
+        }
+
+        if let Some(key) = self.keyframe_at(view, hover_pos) {
+            if additive && state.selected.contains(&key) {
+                state.selected.remove(&key);
+            } else {
+                if !additive && !state.selected.contains(&key) {
+                    state.selected.clear();
+                }
+                state.selected.insert(key);
+                if self.on_move.is_some() {
+                    state.drag = Some(TimelineDrag::Move(key.frame));
+                    widget_state.request_focus();
+                }
+            }
+            self.send_selection(widget_state);
+        } else {
+            let base = if additive { state.selected.clone() } else { HashSet::new() };
+            state.selected.clone_from(&base);
+            state.drag = Some(TimelineDrag::Select {
+                start: hover_pos,
+                base
+            });
+            widget_state.request_focus();
+        }
+    }
+
+    fn update_drag(&self, view: &TimelineView, painter: &mut Painter, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let state = Self::get(widget_state);
+        let Some(drag) = &mut state.drag else { return; };
+        let released = resp.global_mouse_released();
+
+        if let Some(mouse_pos) = resp.global_hover_pos() {
+            match drag {
+                TimelineDrag::Scrub(last_frame) => {
+                    let frame = view.frame_at(mouse_pos.x).clamp(0, (self.frame_count - 1).max(0));
+                    if frame != *last_frame {
+                        *last_frame = frame;
+                        self.scrub(widget_state, frame);
+                    }
+                },
+                TimelineDrag::Move(grab_frame) => {
+                    let grab_frame = *grab_frame;
+                    if released {
+                        let delta = self.move_delta(view, &state.selected, grab_frame, mouse_pos.x);
+                        if delta != 0 {
+                            let mut moved: Vec<TimelineKey> = state.selected.iter().copied().collect();
+                            moved.sort_by_key(|key| (key.track, key.frame));
+                            state.selected = moved.iter().map(|key| TimelineKey { track: key.track, frame: key.frame + delta }).collect();
+                            if let Some(on_move) = self.on_move.clone() {
+                                widget_state.message(move |state| on_move(state, moved.clone(), delta));
+                            }
+                            self.send_selection(widget_state);
+                        }
+                    }
+                },
+                TimelineDrag::Select { start, base } => {
+                    let select_rect = select_rect(*start, mouse_pos);
+                    let mut selected = base.clone();
+                    selected.extend(self.keyframes().filter(|key| select_rect.contains(view.keyframe_center(*key))));
+                    state.selected = selected;
+                    if released {
+                        self.send_selection(widget_state);
+                    }
+                }
+            }
+            painter.request_redraw();
+        }
+
+        if released {
+            Self::get(widget_state).drag = None;
+            widget_state.unfocus();
+        }
+    }
+
+    fn draw_ruler(&self, painter: &mut Painter, view: &TimelineView, visible: Range<i32>) {
+        painter.rect(RectBuilder::new(view.ruler).fill(painter.theme.bg_light));
+        painter.push_clip_rect(view.ruler);
+        let step = label_step(view.frame_width);
+        for frame in visible {
+            let x = view.frame_x(frame);
+            if frame % step == 0 {
+                painter.line(pos(x, view.ruler.top() + RULER_HEIGHT / 3.0), pos(x, view.ruler.bottom()), painter.theme.text);
+                painter.text(&frame.to_string(), pos(x + 3.0, view.ruler.top() + RULER_HEIGHT / 3.0 + self.text_height.get()), painter.theme.text, painter.theme.font_size);
+            } else if view.frame_width >= 4.0 {
+                painter.line(pos(x, view.ruler.bottom() - RULER_HEIGHT / 4.0), pos(x, view.ruler.bottom()), painter.theme.text);
+            }
+        }
+        painter.pop_clip_rect();
+        painter.line(view.ruler.bottom_left(), view.ruler.bottom_right(), painter.theme.stroke);
+    }
+
+    fn draw_names(&self, painter: &mut Painter, view: &TimelineView) {
+        painter.rect(RectBuilder::new(view.names).fill(painter.theme.bg_light));
+        painter.push_clip_rect(view.names);
+        for (track, data) in self.tracks.iter().enumerate() {
+            let top = view.row_top(track);
+            painter.text(&data.name, pos(view.names.left() + NAME_PADDING, top + ROW_HEIGHT / 2.0 + self.text_height.get() / 2.0), painter.theme.text, painter.theme.font_size);
+            painter.line(pos(view.names.left(), top + ROW_HEIGHT), pos(view.names.right(), top + ROW_HEIGHT), painter.theme.stroke);
+        }
+        painter.pop_clip_rect();
+        painter.line(view.names.top_right(), view.names.bottom_right(), painter.theme.stroke);
+    }
+
+    fn draw_tracks(&self, painter: &mut Painter, view: &TimelineView, visible: Range<i32>, widget_state: &mut WidgetState<S>, hover_pos: Option<Pos>) {
+        let state = Self::get(widget_state);
+        painter.rect(RectBuilder::new(view.tracks).fill(painter.theme.bg_dark));
+        painter.push_clip_rect(view.tracks);
+
+        // Frames past the end of the animation
+        let end_x = view.frame_x(self.frame_count);
+        if end_x < view.tracks.right() {
+            painter.rect(RectBuilder::new(Rect::new(pos(end_x.max(view.tracks.left()), view.tracks.top()), view.tracks.max())).fill(painter.theme.stroke));
+        }
+
+        let step = label_step(view.frame_width);
+        for frame in visible.filter(|frame| frame % step == 0) {
+            let x = view.frame_x(frame);
+            painter.line(pos(x, view.tracks.top()), pos(x, view.tracks.bottom()), painter.theme.bg_light);
+        }
+
+        // Where the selection would land if released now
+        let delta = match (&state.drag, hover_pos) {
+            (Some(TimelineDrag::Move(grab_frame)), Some(hover_pos)) => self.move_delta(view, &state.selected, *grab_frame, hover_pos.x),
+            _ => 0
+        };
+        let hovered = hover_pos.filter(|_| state.drag.is_none()).and_then(|hover_pos| self.keyframe_at(view, hover_pos));
+
+        for (track, data) in self.tracks.iter().enumerate() {
+            let top = view.row_top(track);
+            if top + ROW_HEIGHT < view.tracks.top() || top > view.tracks.bottom() {
+                continue;
+            }
+            painter.line(pos(view.tracks.left(), top + ROW_HEIGHT), pos(view.tracks.right(), top + ROW_HEIGHT), painter.theme.stroke);
+
+            for span in &data.spans {
+                let span_rect = Rect::new(pos(view.frame_x(span.start), top + SPAN_PADDING), pos(view.frame_x(span.end), top + ROW_HEIGHT - SPAN_PADDING));
+                painter.rect(RectBuilder::new(span_rect).fill(painter.theme.button).rounding(3.0));
+            }
+
+            for frame in &data.keyframes {
+                let key = TimelineKey { track, frame: *frame };
+                let selected = state.selected.contains(&key);
+                let shown = if selected { TimelineKey { track, frame: key.frame + delta } } else { key };
+                let center = view.keyframe_center(shown);
+                let color = if selected {
+                    painter.theme.accent
+                } else if hovered == Some(key) {
+                    painter.theme.text.darken(painter.theme.hovered_darkness)
+                } else {
+                    painter.theme.text
+                };
+                draw_key_diamond(painter, center, KEYFRAME_SIZE, color);
+            }
+        }
+
+        if let (Some(TimelineDrag::Select { start, .. }), Some(hover_pos)) = (&state.drag, hover_pos) {
+            draw_select_rect(painter, *start, hover_pos);
+        }
+
+        painter.pop_clip_rect();
+    }
+
+    fn draw_playhead(&self, painter: &mut Painter, view: &TimelineView) {
+        let area = Rect::new(view.ruler.top_left(), view.tracks.bottom_right());
+        painter.push_clip_rect(area);
+        let x = view.frame_x(self.frame) + view.frame_width / 2.0;
+        painter.line(pos(x, view.ruler.top()), pos(x, view.tracks.bottom()), painter.theme.accent);
+        let handle_width = view.frame_width.max(8.0);
+        let handle = Rect::min_size(pos(x - handle_width / 2.0, view.ruler.top() + 2.0), vec2(handle_width, RULER_HEIGHT / 3.0 - 2.0));
+        painter.rect(RectBuilder::new(handle).fill(painter.theme.accent).rounding(2.0));
+        painter.pop_clip_rect();
+    }
+
+}
+
+impl<S: 'static> Widget<S> for Timeline<S> {
+
+    type State = TimelineState;
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, widget_state: &mut WidgetState<S>) -> LayoutResult<S> {
+        self.text_height.set(ctx.text_shaper.measure_text(ctx.theme.font_size, "0").y);
+        let state = Self::get(widget_state);
+        state.scroll.inner_size = self.inner_size(state.frame_width);
+        // Inside a scroll area or unbounded stack, show the ruler and a handful of tracks
+        LayoutResult::new(vec2(
+            if max_size.x.is_finite() { max_size.x } else { 600.0 },
+            if max_size.y.is_finite() { max_size.y } else { RULER_HEIGHT + 8.0 * ROW_HEIGHT + SCROLLBAR_SIZE }
+        ))
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let focused = widget_state.focused();
+        let content_size = vec2(rect.width() - NAME_WIDTH - SCROLLBAR_SIZE, rect.height() - RULER_HEIGHT - SCROLLBAR_SIZE).max(Vec2::ZERO);
+        let tracks = Rect::min_size(rect.min() + vec2(NAME_WIDTH, RULER_HEIGHT), content_size);
+
+        ScrollArea::<S>::draw_scrollbar(Axis::X, true, widget_state, Self::scroll_state, tracks, painter, resp);
+        ScrollArea::<S>::draw_scrollbar(Axis::Y, true, widget_state, Self::scroll_state, tracks, painter, resp);
+        let state = Self::get(widget_state);
+
+        // Zoom around the frame under the pointer, or scroll
+        let scroll = if resp.modifiers().shift { vec2(resp.scroll().y, resp.scroll().x) } else { resp.scroll() };
+        match resp.hover_pos() {
+            Some(hover_pos) if resp.modifiers().ctrl && scroll.y != 0.0 => {
+                let anchor = hover_pos.x - tracks.left();
+                let frame = (anchor - state.scroll.scroll.x) / state.frame_width;
+                state.frame_width = (state.frame_width * (1.0 + scroll.y * ZOOM_SPEED).max(0.1)).clamp(MIN_FRAME_WIDTH, MAX_FRAME_WIDTH);
+                state.scroll.scroll.x = anchor - frame * state.frame_width;
+            },
+            _ => state.scroll.scroll += scroll
+        }
+        state.scroll.inner_size = self.inner_size(state.frame_width);
+        state.scroll.clamp_scroll(true, true, content_size);
+
+        let view = TimelineView {
+            ruler: Rect::min_size(rect.min() + vec2(NAME_WIDTH, 0.0), vec2(content_size.x, RULER_HEIGHT)),
+            names: Rect::min_size(rect.min() + vec2(0.0, RULER_HEIGHT), vec2(NAME_WIDTH, content_size.y)),
+            tracks,
+            frame_width: state.frame_width,
+            scroll: state.scroll.scroll
+        };
+
+        if !state.scroll.scrollbar_dragged() {
+            if focused && state.drag.is_some() {
+                self.update_drag(&view, painter, resp, widget_state);
+            } else if let (true, Some(hover_pos)) = (resp.mouse_clicked(), resp.hover_pos()) {
+                self.start_drag(&view, hover_pos, resp, widget_state);
+                painter.request_redraw();
+            }
+        }
+
+        let first = view.frame_at(tracks.left()).max(0);
+        let last = (view.frame_at(tracks.right()) + 1).min(self.frame_count);
+        let pointer = if focused { resp.global_hover_pos() } else { resp.hover_pos() };
+        self.draw_ruler(painter, &view, first..last);
+        self.draw_names(painter, &view);
+        self.draw_tracks(painter, &view, first..last, widget_state, pointer);
+        self.draw_playhead(painter, &view);
+    }
+
+}
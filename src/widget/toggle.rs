@@ -0,0 +1,223 @@
+
+use std::rc::Rc;
+
+use crate::{painter::{Painter, RectBuilder}, pos, vec2, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{button::interaction_color, column::Column, text::Text, LayoutContext, LayoutResult};
+
+const INDICATOR_SIZE: f32 = 16.0;
+const SWITCH_WIDTH: f32 = 30.0;
+const KNOB_PADDING: f32 = 2.0;
+const RADIO_DOT_SIZE: f32 = 6.0;
+
+#[derive(Clone, Copy)]
+enum Indicator {
+    Check,
+    Radio,
+    Switch
+}
+
+impl Indicator {
+
+    fn size(&self) -> Vec2 {
+        match self {
+            Indicator::Check | Indicator::Radio => vec2(INDICATOR_SIZE, INDICATOR_SIZE),
+            Indicator::Switch => vec2(SWITCH_WIDTH, INDICATOR_SIZE)
+        }
+    }
+
+}
+
+// An on/off indicator followed by a label. The whole widget senses clicks, so the label is clickable too.
+struct LabeledIndicator<S> {
+    indicator: Indicator,
+    on: bool,
+    label: WidgetNode<S>
+}
+
+impl<S: 'static> LabeledIndicator<S> {
+
+    fn new(indicator: Indicator, on: bool, label: String) -> WidgetNode<S> {
+        WidgetNode::new(Self {
+            indicator,
+            on,
+            label: Text::new(label)
+        }).sense_click(true)
+    }
+
+}
+
+impl<S> Widget<S> for LabeledIndicator<S> {
+
+    type State = ();
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let indicator_size = self.indicator.size();
+        let label_x = indicator_size.x + ctx.theme.item_spacing / 2.0;
+        let label = self.label.layout(vec2((max_size.x - label_x).max(0.0), max_size.y), ctx, state);
+        let height = indicator_size.y.max(label.size().y);
+
+        let mut layout = LayoutResult::new(vec2(label_x + label.size().x, height).min(max_size));
+        layout.add_child(vec2(label_x, (height - label.size().y) / 2.0), label);
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, _state: &mut WidgetState<S>) {
+        let size = self.indicator.size();
+        let indicator = Rect::min_size(rect.min() + vec2(0.0, (rect.height() - size.y) / 2.0), size);
+        let theme = painter.theme;
+
+        match self.indicator {
+            Indicator::Check => {
+                let fill = if self.on { theme.button } else { theme.bg_light };
+                painter.rect(RectBuilder::new(indicator).fill(interaction_color(fill, resp, theme)).stroke(theme.stroke).rounding(theme.rounding / 2.0));
+                if self.on {
+                    let at = |x: f32, y: f32| pos(indicator.left() + x * size.x, indicator.top() + y * size.y);
+                    painter.line(at(0.25, 0.5), at(0.43, 0.7), theme.text);
+                    painter.line(at(0.43, 0.7), at(0.75, 0.3), theme.text);
+                }
+            },
+            Indicator::Radio => {
+                painter.rect(RectBuilder::new(indicator).fill(interaction_color(theme.bg_light, resp, theme)).stroke(theme.stroke).rounding(size.y / 2.0));
+                if self.on {
+                    let dot = Rect::center_size(indicator.center(), Vec2::splat(RADIO_DOT_SIZE));
+                    painter.rect(RectBuilder::new(dot).fill(theme.text).rounding(RADIO_DOT_SIZE / 2.0));
+                }
+            },
+            Indicator::Switch => {
+                let fill = if self.on { theme.button } else { theme.bg_light };
+                painter.rect(RectBuilder::new(indicator).fill(interaction_color(fill, resp, theme)).stroke(theme.stroke).rounding(size.y / 2.0));
+                let knob_size = size.y - 2.0 * KNOB_PADDING;
+                let knob_x = if self.on { indicator.right() - KNOB_PADDING - knob_size } else { indicator.left() + KNOB_PADDING };
+                let knob = Rect::min_size(pos(knob_x, indicator.top() + KNOB_PADDING), Vec2::splat(knob_size));
+                painter.rect(RectBuilder::new(knob).fill(theme.text).stroke(theme.stroke).rounding(knob_size / 2.0));
+            }
+        }
+    }
+
+}
+
+type OnToggle<S> = Rc<dyn Fn(&mut S, bool)>;
+type OnSelect<S, V> = Rc<dyn Fn(&mut S, V)>;
+
+pub struct Checkbox<S> {
+    checked: bool,
+    label: String,
+    on_toggle: Option<OnToggle<S>>
+}
+
+impl<S: 'static> Checkbox<S> {
+
+    pub fn new<T: Into<String>>(checked: bool, label: T) -> Self {
+        Self {
+            checked,
+            label: label.into(),
+            on_toggle: None
+        }
+    }
+
+    // Called with the new checked state when the checkbox is clicked
+    pub fn on_toggle<F>(mut self, handler: F) -> Self where F: Fn(&mut S, bool) + 'static {
+        self.on_toggle = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        let node = LabeledIndicator::new(Indicator::Check, self.checked, self.label);
+        match self.on_toggle {
+            Some(on_toggle) => {
+                let checked = self.checked;
+                node.on_click(move |state| on_toggle(state, !checked))
+            },
+            None => node
+        }
+    }
+
+}
+
+// An on/off switch, the same as a checkbox in behaviour
+pub struct Toggle<S> {
+    on: bool,
+    label: String,
+    on_toggle: Option<OnToggle<S>>
+}
+
+impl<S: 'static> Toggle<S> {
+
+    pub fn new<T: Into<String>>(on: bool, label: T) -> Self {
+        Self {
+            on,
+            label: label.into(),
+            on_toggle: None
+        }
+    }
+
+    // Called with the new state when the switch is clicked
+    pub fn on_toggle<F>(mut self, handler: F) -> Self where F: Fn(&mut S, bool) + 'static {
+        self.on_toggle = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        let node = LabeledIndicator::new(Indicator::Switch, self.on, self.label);
+        match self.on_toggle {
+            Some(on_toggle) => {
+                let on = self.on;
+                node.on_click(move |state| on_toggle(state, !on))
+            },
+            None => node
+        }
+    }
+
+}
+
+pub struct RadioButton;
+
+impl RadioButton {
+
+    // A single radio button. Use `on_click` on the returned node to select its option.
+    pub fn new<S: 'static, T: Into<String>>(selected: bool, label: T) -> WidgetNode<S> {
+        LabeledIndicator::new(Indicator::Radio, selected, label.into())
+    }
+
+}
+
+// A column of radio buttons, one for each option of `value`'s type
+pub struct RadioGroup<S, V> {
+    value: V,
+    options: Vec<(V, String)>,
+    on_select: Option<OnSelect<S, V>>
+}
+
+impl<S: 'static, V: PartialEq + Clone + 'static> RadioGroup<S, V> {
+
+    pub fn new(value: V) -> Self {
+        Self {
+            value,
+            options: Vec::new(),
+            on_select: None
+        }
+    }
+
+    pub fn option<T: Into<String>>(mut self, value: V, label: T) -> Self {
+        self.options.push((value, label.into()));
+        self
+    }
+
+    pub fn on_select<F>(mut self, handler: F) -> Self where F: Fn(&mut S, V) + 'static {
+        self.on_select = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        let buttons = self.options.into_iter().map(|(value, label)| {
+            let button = RadioButton::new(value == self.value, label);
+            match self.on_select.clone() {
+                Some(on_select) => button.on_click(move |state| on_select(state, value.clone())),
+                None => button
+            }
+        }).collect();
+        Column::new(buttons)
+    }
+
+}
@@ -0,0 +1,83 @@
+
+use crate::{painter::{Painter, RectBuilder}, vec2, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{margin::Margin, LayoutContext, LayoutResult};
+
+const TOOLTIP_MAX_WIDTH: f32 = 300.0;
+const TOOLTIP_CURSOR_OFFSET: Vec2 = vec2(10.0, 18.0);
+
+// Per-node hover tracking for widgets with a tooltip
+#[derive(Default)]
+pub(crate) struct TooltipState {
+    hover_start: Option<f32>,
+    dismissed: bool,
+    // Offset of the tooltip from the top left of the widget, if it is showing
+    pub(crate) pos: Option<Vec2>
+}
+
+impl TooltipState {
+
+    pub(crate) fn update(&mut self, painter: &mut Painter, rect: Rect, resp: &Response, time: f32, delay: f32) {
+        // Widgets with tooltips don't need to sense the pointer themselves, and their children may take it,
+        // so hovering is anywhere over the widget that isn't covered by a layer above
+        let hover_pos = if let Some(hover_pos) = resp.layer_hover_pos.filter(|hover_pos| rect.contains(*hover_pos)) {
+            hover_pos
+        } else {
+            *self = Self::default();
+            return;
+        };
+
+        // Clicking hides the tooltip until the pointer leaves the widget
+        if resp.global_left_mouse_button.pressed() || resp.global_right_mouse_button.pressed() {
+            self.dismissed = true;
+            self.pos = None;
+        }
+        if self.dismissed || self.pos.is_some() {
+            return;
+        }
+
+        let hover_start = *self.hover_start.get_or_insert(time);
+        let remaining = delay - (time - hover_start);
+        if remaining <= 0.0 {
+            self.pos = Some(hover_pos - rect.min() + TOOLTIP_CURSOR_OFFSET);
+            painter.request_redraw();
+        } else {
+            painter.request_redraw_after(remaining);
+        }
+    }
+
+}
+
+pub struct Tooltip<S> {
+    contents: WidgetNode<S>
+}
+
+impl<S: 'static> Tooltip<S> {
+
+    pub fn new(contents: WidgetNode<S>) -> WidgetNode<S> {
+        WidgetNode::new(Self {
+            contents: Margin::new_with_margin(contents, 5.0)
+        })
+    }
+
+}
+
+impl<S> Widget<S> for Tooltip<S> {
+
+    type State = ();
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let inner_layout = self.contents.layout(vec2(max_size.x.min(TOOLTIP_MAX_WIDTH), max_size.y), ctx, state);
+        let mut layout = LayoutResult::new(inner_layout.size());
+        layout.add_child(Vec2::ZERO, inner_layout);
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, _resp: &Response, _state: &mut WidgetState<S>) {
+        painter.rect(RectBuilder::new(rect)
+            .fill(painter.theme.bg_window)
+            .stroke(painter.theme.stroke)
+            .rounding(painter.theme.rounding / 2.0));
+    }
+
+}
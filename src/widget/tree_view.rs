@@ -0,0 +1,479 @@
+use std::{cell::{OnceCell, RefCell}, collections::{hash_map::DefaultHasher, HashSet}, hash::{Hash, Hasher}, rc::Rc};
+
+use crate::{painter::{Painter, RectBuilder}, pos, vec2, Pos, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{key::Key, text::Text, virtual_list::VirtualList, LayoutContext, LayoutResult};
+
+const ROW_HEIGHT: f32 = 22.0;
+const INDENT: f32 = 16.0;
+const ARROW_SIZE: f32 = 8.0;
+const DRAG_THRESHOLD: f32 = 5.0;
+// Local id of the list showing the rows
+const LIST_ID: usize = 0;
+
+pub struct TreeItem<K> {
+    pub key: K,
+    pub label: String,
+    pub children: Vec<TreeItem<K>>
+}
+
+impl<K> TreeItem<K> {
+
+    pub fn new<T: Into<String>>(key: K, label: T) -> Self {
+        Self {
+            key,
+            label: label.into(),
+            children: Vec::new()
+        }
+    }
+
+    pub fn with_children(mut self, children: Vec<TreeItem<K>>) -> Self {
+        self.children = children;
+        self
+    }
+
+}
+
+// Where a dragged item was dropped, relative to another item
+#[derive(Clone, Debug, PartialEq)]
+pub enum TreeDrop<K> {
+    Before(K),
+    After(K),
+    Into(K)
+}
+
+struct TreeDrag<K> {
+    key: K,
+    start: Pos,
+    dragging: bool
+}
+
+pub struct TreeViewState<K> {
+    // Expanded items by key, so they stay open when the tree changes around them
+    expanded: HashSet<K>,
+    // Used when the selection is not kept in the app state
+    selected: Option<K>,
+    drag: Option<TreeDrag<K>>
+}
+
+impl<K> Default for TreeViewState<K> {
+
+    fn default() -> Self {
+        Self {
+            expanded: HashSet::new(),
+            selected: None,
+            drag: None
+        }
+    }
+
+}
+
+// A visible row of the flattened tree
+struct TreeRow<K> {
+    key: K,
+    // Indices leading from the top level items down to the row's item
+    path: Vec<usize>,
+    depth: usize,
+    parent: Option<usize>,
+    expanded: bool,
+    has_children: bool
+}
+
+// What the rows tell the tree while drawing. Rows draw after the tree but before its `post_draw`.
+#[derive(Default)]
+struct RowReports {
+    selected_row: Option<usize>,
+    // Where each drawn row is on screen
+    rects: Vec<(usize, Rect)>,
+    // The row clicked this frame and where
+    clicked: Option<(usize, Pos)>
+}
+
+// The indentation, expand arrow and highlight around the contents of a row
+struct TreeViewRow<S> {
+    idx: usize,
+    depth: usize,
+    expanded: bool,
+    has_children: bool,
+    contents: WidgetNode<S>,
+    reports: Rc<RefCell<RowReports>>
+}
+
+impl<S> TreeViewRow<S> {
+
+    fn draw_arrow(painter: &mut Painter, center: Pos, expanded: bool) {
+        let h = ARROW_SIZE / 2.0;
+        let (a, tip, b) = if expanded {
+            (center + vec2(-h, -h / 2.0), center + vec2(0.0, h / 2.0), center + vec2(h, -h / 2.0))
+        } else {
+            (center + vec2(-h / 2.0, -h), center + vec2(h / 2.0, 0.0), center + vec2(-h / 2.0, h))
+        };
+        painter.line(a, tip, painter.theme.text);
+        painter.line(tip, b, painter.theme.text);
+    }
+
+}
+
+impl<S> Widget<S> for TreeViewRow<S> {
+
+    type State = ();
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let indent = (self.depth + 1) as f32 * INDENT;
+        let contents = self.contents.layout(vec2((max_size.x - indent).max(0.0), ROW_HEIGHT), ctx, state);
+        let offset = vec2(indent, ((ROW_HEIGHT - contents.size().y) / 2.0).max(0.0));
+        let mut layout = LayoutResult::new(vec2(max_size.x, ROW_HEIGHT));
+        layout.add_child(offset, contents);
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, _state: &mut WidgetState<S>) {
+        let mut reports = self.reports.borrow_mut();
+        reports.rects.push((self.idx, rect));
+        if resp.mouse_clicked() {
+            reports.clicked = resp.hover_pos().map(|hover_pos| (self.idx, hover_pos));
+        }
+
+        if reports.selected_row == Some(self.idx) {
+            painter.rect(RectBuilder::new(rect).fill(painter.theme.button));
+        } else if resp.hovered() {
+            painter.rect(RectBuilder::new(rect).fill(painter.theme.bg_light.darken(painter.theme.hovered_darkness)));
+        }
+
+        // Indentation guides
+        for depth in 0..self.depth {
+            let x = rect.left() + depth as f32 * INDENT + INDENT / 2.0;
+            painter.line(pos(x, rect.top()), pos(x, rect.bottom()), painter.theme.stroke);
+        }
+
+        if self.has_children {
+            let indent = rect.left() + self.depth as f32 * INDENT;
+            Self::draw_arrow(painter, pos(indent + INDENT / 2.0, rect.center().y), self.expanded);
+        }
+    }
+
+}
+
+type BuildRow<S, K> = Rc<dyn Fn(&TreeItem<K>) -> WidgetNode<S>>;
+type OnSelect<S, K> = Rc<dyn Fn(&mut S, K)>;
+type OnMove<S, K> = Rc<dyn Fn(&mut S, K, TreeDrop<K>)>;
+
+// A scrolling hierarchy of items that can be expanded, selected and dragged onto each other.
+// The tree is flattened into rows shown by a `VirtualList`, so only the rows in view are built.
+pub struct TreeView<S, K> {
+    items: Rc<Vec<TreeItem<K>>>,
+    build_row: BuildRow<S, K>,
+    selected: Option<Option<K>>,
+    on_select: Option<OnSelect<S, K>>,
+    on_move: Option<OnMove<S, K>>,
+    // The visible rows and the list showing them, built by the first layout pass of each frame
+    rows: OnceCell<Rc<Vec<TreeRow<K>>>>,
+    list: OnceCell<WidgetNode<S>>,
+    reports: Rc<RefCell<RowReports>>
+}
+
+impl<S, K> TreeView<S, K> where S: 'static, K: Clone + Hash + Eq + 'static {
+
+    pub fn new(items: Vec<TreeItem<K>>) -> Self {
+        Self {
+            items: Rc::new(items),
+            build_row: Rc::new(|item| Text::new(item.label.clone())),
+            selected: None,
+            on_select: None,
+            on_move: None,
+            rows: OnceCell::new(),
+            list: OnceCell::new(),
+            reports: Rc::new(RefCell::new(RowReports::default()))
+        }
+    }
+
+    // Build the contents of each row from its item, instead of showing its label
+    pub fn row_builder<F>(mut self, build_row: F) -> Self where F: Fn(&TreeItem<K>) -> WidgetNode<S> + 'static {
+        self.build_row = Rc::new(build_row);
+        self
+    }
+
+    // Highlight the given item instead of keeping the selection in the widget state
+    pub fn selected(mut self, selected: Option<K>) -> Self {
+        self.selected = Some(selected);
+        self
+    }
+
+    pub fn on_select<F>(mut self, handler: F) -> Self where F: Fn(&mut S, K) + 'static {
+        self.on_select = Some(Rc::new(handler));
+        self
+    }
+
+    // Allow dragging items onto other items. Called with the dragged item and where it was dropped.
+    pub fn on_move<F>(mut self, handler: F) -> Self where F: Fn(&mut S, K, TreeDrop<K>) + 'static {
+        self.on_move = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn build(self) -> WidgetNode<S> {
+        WidgetNode::new(self)
+    }
+
+    fn flatten(items: &[TreeItem<K>], expanded: &HashSet<K>, depth: usize, parent: Option<usize>, path: &mut Vec<usize>, rows: &mut Vec<TreeRow<K>>) {
+        for (item_idx, item) in items.iter().enumerate() {
+            let is_expanded = expanded.contains(&item.key);
+            let idx = rows.len();
+            path.push(item_idx);
+            rows.push(TreeRow {
+                key: item.key.clone(),
+                path: path.clone(),
+                depth,
+                parent,
+                expanded: is_expanded,
+                has_children: !item.children.is_empty()
+            });
+            if is_expanded {
+                Self::flatten(&item.children, expanded, depth + 1, Some(idx), path, rows);
+            }
+            path.pop();
+        }
+    }
+
+    fn item<'a>(items: &'a [TreeItem<K>], path: &[usize]) -> &'a TreeItem<K> {
+        let item = &items[path[0]];
+        path[1..].iter().fold(item, |item, idx| &item.children[*idx])
+    }
+
+    // Flatten the expanded part of the tree
+    fn build_rows(&self, expanded: &HashSet<K>) -> Rc<Vec<TreeRow<K>>> {
+        let mut rows = Vec::new();
+        Self::flatten(&self.items, expanded, 0, None, &mut Vec::new(), &mut rows);
+        Rc::new(rows)
+    }
+
+    fn build_list(&self, rows: &Rc<Vec<TreeRow<K>>>) -> WidgetNode<S> {
+        let list_rows = rows.clone();
+        let items = self.items.clone();
+        let build_row = self.build_row.clone();
+        let reports = self.reports.clone();
+        VirtualList::new(rows.len(), ROW_HEIGHT, move |idx| {
+            let row = &list_rows[idx];
+            let item = Self::item(&items, &row.path);
+            // Keyed by item, so row state stays put when rows above are expanded, collapsed or moved
+            let mut hasher = DefaultHasher::new();
+            item.key.hash(&mut hasher);
+            WidgetNode::new(TreeViewRow {
+                idx,
+                depth: row.depth,
+                expanded: row.expanded,
+                has_children: row.has_children,
+                contents: build_row(item),
+                reports: reports.clone()
+            }).sense_click(true).id(hasher.finish() as usize)
+        }).id(LIST_ID)
+    }
+
+    fn rows(&self) -> &[TreeRow<K>] {
+        self.rows.get().map(|rows| rows.as_slice()).unwrap_or_default()
+    }
+
+    fn select(&self, widget_state: &mut WidgetState<S>, key: K) {
+        Self::get(widget_state).selected = Some(key.clone());
+        if let Some(on_select) = &self.on_select {
+            let on_select = on_select.clone();
+            widget_state.message(move |state| {
+                on_select(state, key.clone());
+            });
+        }
+    }
+
+    fn selected_row(&self, widget_state: &mut WidgetState<S>) -> Option<usize> {
+        let selected = match &self.selected {
+            Some(selected) => selected.clone(),
+            None => Self::get(widget_state).selected.clone()
+        };
+        selected.and_then(|selected| self.rows().iter().position(|row| row.key == selected))
+    }
+
+    // The row under a position on screen, and how far down the row the position is
+    fn row_at(&self, pos: Pos) -> Option<(usize, f32)> {
+        let reports = self.reports.borrow();
+        reports.rects.iter().find(|(_, rect)| rect.contains(pos)).map(|(idx, rect)| (*idx, pos.y - rect.top()))
+    }
+
+    // The drop position for a drag over the given row. The middle of a row drops into it.
+    fn drop_target(rows: &[TreeRow<K>], dragged: &K, row_idx: usize, y_in_row: f32) -> Option<TreeDrop<K>> {
+        let row = rows.get(row_idx)?;
+        // Items can't be dropped into themselves or their descendants
+        let mut ancestor = Some(row_idx);
+        while let Some(idx) = ancestor {
+            if rows[idx].key == *dragged {
+                return None;
+            }
+            ancestor = rows[idx].parent;
+        }
+        let key = row.key.clone();
+        Some(if y_in_row < ROW_HEIGHT / 4.0 {
+            TreeDrop::Before(key)
+        } else if y_in_row > ROW_HEIGHT * 3.0 / 4.0 {
+            TreeDrop::After(key)
+        } else {
+            TreeDrop::Into(key)
+        })
+    }
+
+    // Returns the newly selected row
+    fn handle_keyboard(&self, selected_row: Option<usize>, resp: &Response, widget_state: &mut WidgetState<S>) -> Option<usize> {
+        let rows = self.rows();
+        let state = Self::get(widget_state);
+        let mut new_selection = None;
+        if resp.key_pressed(Key::Down) {
+            new_selection = Some(selected_row.map(|idx| (idx + 1).min(rows.len() - 1)).unwrap_or(0));
+        }
+        if resp.key_pressed(Key::Up) {
+            new_selection = Some(selected_row.map(|idx| idx.saturating_sub(1)).unwrap_or(0));
+        }
+        if let Some(idx) = selected_row {
+            let row = &rows[idx];
+            if resp.key_pressed(Key::Left) {
+                if row.expanded {
+                    state.expanded.remove(&row.key);
+                } else if row.parent.is_some() {
+                    new_selection = row.parent;
+                }
+            }
+            if resp.key_pressed(Key::Right) && row.has_children {
+                if row.expanded {
+                    new_selection = Some(idx + 1);
+                } else {
+                    state.expanded.insert(row.key.clone());
+                }
+            }
+        }
+
+        match new_selection {
+            Some(idx) if Some(idx) != selected_row => {
+                self.select(widget_state, rows[idx].key.clone());
+                Some(idx)
+            },
+            _ => None
+        }
+    }
+
+    fn toggle_expanded(state: &mut TreeViewState<K>, row: &TreeRow<K>) {
+        if row.expanded {
+            state.expanded.remove(&row.key);
+        } else {
+            state.expanded.insert(row.key.clone());
+        }
+    }
+
+    fn handle_click(&self, idx: usize, click_pos: Pos, rect: Rect, selected_row: Option<usize>, widget_state: &mut WidgetState<S>) {
+        let row = &self.rows()[idx];
+        let arrow_left = rect.left() + row.depth as f32 * INDENT;
+        if row.has_children && click_pos.x < arrow_left + INDENT {
+            Self::toggle_expanded(Self::get(widget_state), row);
+            return;
+        }
+        if self.on_move.is_some() {
+            Self::get(widget_state).drag = Some(TreeDrag {
+                key: row.key.clone(),
+                start: click_pos,
+                dragging: false
+            });
+            widget_state.request_focus();
+        }
+        if selected_row != Some(idx) {
+            self.select(widget_state, row.key.clone());
+        }
+    }
+
+    // Follow a dragged item, returning where it would drop
+    fn handle_drag(&self, painter: &mut Painter, resp: &Response, widget_state: &mut WidgetState<S>) -> Option<(usize, TreeDrop<K>)> {
+        let mut drop = None;
+        let state = Self::get(widget_state);
+        if let Some(drag) = &mut state.drag {
+            let mouse_pos = resp.global_hover_pos();
+            if mouse_pos.is_some_and(|mouse_pos| (mouse_pos - drag.start).length() > DRAG_THRESHOLD) {
+                drag.dragging = true;
+            }
+            if drag.dragging {
+                drop = mouse_pos.and_then(|pos| self.row_at(pos)).and_then(|(idx, y)| Self::drop_target(self.rows(), &drag.key, idx, y).map(|target| (idx, target)));
+                painter.request_redraw();
+            }
+        }
+        if resp.global_mouse_released() {
+            let drag = state.drag.take();
+            widget_state.unfocus();
+            if let (Some(drag), Some((_, target)), Some(on_move)) = (drag, drop.clone(), &self.on_move) {
+                if drag.dragging {
+                    let on_move = on_move.clone();
+                    widget_state.message(move |state| {
+                        on_move(state, drag.key.clone(), target.clone());
+                    });
+                }
+            }
+        }
+        drop
+    }
+
+}
+
+impl<S, K> Widget<S> for TreeView<S, K> where S: 'static, K: Clone + Hash + Eq + 'static {
+
+    type State = TreeViewState<K>;
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, widget_state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let rows = self.rows.get_or_init(|| self.build_rows(&Self::get(widget_state).expanded));
+        let list = self.list.get_or_init(|| self.build_list(rows));
+        let list = list.layout(max_size, ctx, widget_state);
+        let mut layout = LayoutResult::new(list.size());
+        layout.add_child(Vec2::ZERO, list);
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let mut selected_row = self.selected_row(widget_state);
+
+        // The arrow keys move the selection once a row has been clicked
+        if widget_state.keyboard_focused() && !self.rows().is_empty() {
+            if let Some(idx) = self.handle_keyboard(selected_row, resp, widget_state) {
+                selected_row = Some(idx);
+                let list_state = VirtualList::<S>::get(widget_state.get_child(LIST_ID));
+                list_state.scroll_into_view(idx as f32 * ROW_HEIGHT, ROW_HEIGHT, rect.height());
+            }
+            if !resp.keys_pressed().is_empty() {
+                // Expanding and collapsing only shows once the tree is flattened again
+                painter.request_redraw();
+            }
+        }
+
+        let mut reports = self.reports.borrow_mut();
+        reports.selected_row = selected_row;
+        reports.rects.clear();
+        reports.clicked = None;
+    }
+
+    fn post_draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let drop = if widget_state.focused() {
+            self.handle_drag(painter, resp, widget_state)
+        } else {
+            let clicked = self.reports.borrow().clicked;
+            if let Some((idx, click_pos)) = clicked {
+                let selected_row = self.reports.borrow().selected_row;
+                self.handle_click(idx, click_pos, rect, selected_row, widget_state);
+                widget_state.request_keyboard_focus();
+                painter.request_redraw();
+            }
+            None
+        };
+
+        // Show where the dragged item would land
+        let reports = self.reports.borrow();
+        if let Some((row_rect, target)) = drop.and_then(|(drop_idx, target)| reports.rects.iter().find(|(idx, _)| *idx == drop_idx).map(|(_, rect)| (*rect, target))) {
+            painter.push_clip_rect(rect);
+            match target {
+                TreeDrop::Before(_) => painter.line(row_rect.top_left(), row_rect.top_right(), painter.theme.text),
+                TreeDrop::After(_) => painter.line(row_rect.bottom_left(), row_rect.bottom_right(), painter.theme.text),
+                TreeDrop::Into(_) => painter.rect(RectBuilder::new(row_rect).stroke(painter.theme.text))
+            }
+            painter.pop_clip_rect();
+        }
+    }
+
+}
@@ -0,0 +1,163 @@
+
+use std::cell::OnceCell;
+
+use crate::{painter::Painter, vec2, Axis, Rect, Response, Vec2, Widget, WidgetNode, WidgetState};
+
+use super::{scroll_area::{ScrollArea, ScrollAreaState, SCROLLBAR_SIZE}, LayoutContext, LayoutResult};
+
+// Height of the viewport when the list is given unbounded space
+const DEFAULT_HEIGHT: f32 = 300.0;
+// Number of rows whose slots are allocated together, the first time one of them is shown
+const CHUNK_SIZE: usize = 64;
+
+// Slots for `CHUNK_SIZE` rows, each filled when its row is first laid out
+type RowChunk<S> = OnceCell<Box<[OnceCell<WidgetNode<S>>]>>;
+
+#[derive(Default)]
+pub struct VirtualListState {
+    scroll: ScrollAreaState,
+    // Measured heights of rows that have been shown, when rows don't have a fixed height
+    heights: Vec<Option<f32>>
+}
+
+impl VirtualListState {
+
+    fn row_height(&self, idx: usize, estimate: f32) -> f32 {
+        self.heights.get(idx).copied().flatten().unwrap_or(estimate)
+    }
+
+    // Scroll just far enough to show the span of content from `top` down by `height`
+    pub(crate) fn scroll_into_view(&mut self, top: f32, height: f32, view_height: f32) {
+        let scroll = &mut self.scroll.scroll.y;
+        if top + *scroll < 0.0 {
+            *scroll = -top;
+        } else if top + height + *scroll > view_height {
+            *scroll = view_height - top - height;
+        }
+    }
+
+}
+
+// A vertically scrolling list that only builds and lays out the rows in view
+pub struct VirtualList<S> {
+    count: usize,
+    row_height: f32,
+    // Whether `row_height` is only an estimate to be replaced by the measured height of each row
+    estimated: bool,
+    build_row: Box<dyn Fn(usize) -> WidgetNode<S>>,
+    // The rows built for this frame. Each layout pass builds whichever rows in its viewport are still missing.
+    rows: Vec<RowChunk<S>>
+}
+
+impl<S: 'static> VirtualList<S> {
+
+    // Every row is exactly `row_height` tall.
+    // Rows keep their state by index unless `build_row` gives them an id, which should then stay with the same row as others come and go.
+    pub fn new<F>(count: usize, row_height: f32, build_row: F) -> WidgetNode<S> where F: Fn(usize) -> WidgetNode<S> + 'static {
+        Self::build(count, row_height, false, build_row)
+    }
+
+    // Rows take their natural height, which is guessed as `estimated_height` until they have been shown
+    pub fn estimated<F>(count: usize, estimated_height: f32, build_row: F) -> WidgetNode<S> where F: Fn(usize) -> WidgetNode<S> + 'static {
+        Self::build(count, estimated_height, true, build_row)
+    }
+
+    fn build<F>(count: usize, row_height: f32, estimated: bool, build_row: F) -> WidgetNode<S> where F: Fn(usize) -> WidgetNode<S> + 'static {
+        WidgetNode::new(Self {
+            count,
+            row_height,
+            estimated,
+            build_row: Box::new(build_row),
+            rows: (0..count.div_ceil(CHUNK_SIZE)).map(|_| OnceCell::new()).collect()
+        }).sense_click(true)
+    }
+
+    fn row(&self, idx: usize) -> &WidgetNode<S> {
+        let chunk = self.rows[idx / CHUNK_SIZE].get_or_init(|| (0..CHUNK_SIZE).map(|_| OnceCell::new()).collect());
+        chunk[idx % CHUNK_SIZE].get_or_init(|| {
+            let row = (self.build_row)(idx);
+            if row.local_id.is_some() { row } else { row.id(idx) }
+        })
+    }
+
+    fn scroll_state(widget_state: &mut WidgetState<S>) -> &mut ScrollAreaState {
+        &mut Self::get(widget_state).scroll
+    }
+
+}
+
+impl<S: 'static> Widget<S> for VirtualList<S> {
+
+    type State = VirtualListState;
+
+    fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, widget_state: &mut WidgetState<S>) -> LayoutResult<S> {
+        let size = vec2(max_size.x, if max_size.y.is_finite() { max_size.y } else { DEFAULT_HEIGHT });
+        let width = size.x - SCROLLBAR_SIZE;
+        let state = Self::get(widget_state);
+        state.heights.resize(self.count, None);
+
+        // Find the rows overlapping the viewport
+        let view_top = -state.scroll.scroll.y;
+        let view_bottom = view_top + size.y;
+        let mut first = self.count;
+        let mut y = 0.0;
+        let mut last = self.count;
+        for idx in 0..self.count {
+            let height = state.row_height(idx, self.row_height);
+            if first == self.count && y + height > view_top {
+                first = idx;
+            }
+            if y >= view_bottom {
+                last = idx;
+                break;
+            }
+            y += height;
+        }
+
+        let mut layout = LayoutResult::new(size);
+        let state = Self::get(widget_state);
+        let mut row_top: f32 = (0..first).map(|idx| state.row_height(idx, self.row_height)).sum();
+        for idx in first..last {
+            let mut row_layout = self.row(idx).layout(vec2(width, if self.estimated { f32::INFINITY } else { self.row_height }), ctx, widget_state);
+            let state = Self::get(widget_state);
+            let height = if self.estimated {
+                state.heights[idx] = Some(row_layout.size().y);
+                row_layout.size().y
+            } else {
+                row_layout.set_size(vec2(row_layout.size().x, self.row_height));
+                self.row_height
+            };
+            layout.add_child(vec2(0.0, row_top + state.scroll.scroll.y), row_layout);
+            row_top += height;
+        }
+
+        let state = Self::get(widget_state);
+        let total_height = (0..self.count).map(|idx| state.row_height(idx, self.row_height)).sum();
+        state.scroll.inner_size = vec2(width, total_height);
+
+        layout
+    }
+
+    fn draw(&self, painter: &mut Painter, rect: Rect, resp: &Response, widget_state: &mut WidgetState<S>) {
+        let scroll_area_size = vec2(rect.width() - SCROLLBAR_SIZE, rect.height());
+        let scroll_area = Rect::min_size(rect.min(), scroll_area_size);
+
+        let prev_scroll = Self::scroll_state(widget_state).scroll;
+        ScrollArea::<S>::draw_scrollbar(Axis::Y, true, widget_state, Self::scroll_state, scroll_area, painter, resp);
+        let state = Self::scroll_state(widget_state);
+
+        painter.push_clip_rect(scroll_area);
+
+        state.scroll += resp.scroll();
+        state.clamp_scroll(false, true, scroll_area_size);
+        // Rows scrolled into view have not been built yet
+        if state.scroll != prev_scroll {
+            painter.request_redraw();
+        }
+    }
+
+    fn post_draw(&self, painter: &mut Painter, _rect: Rect, _resp: &Response, _state: &mut WidgetState<S>) {
+        painter.pop_clip_rect();
+    }
+
+}
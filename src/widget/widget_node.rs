@@ -1,7 +1,12 @@
 
+use std::{any::Any, cell::OnceCell};
+
 use crate::{state::WidgetState, LayoutNode, Vec2};
 
-use super::{LayoutContext, LayoutResult, Message, Widget, WidgetDyn};
+use super::{dropdown::Dropdown, text::Text, tooltip::Tooltip, LayoutContext, LayoutResult, Message, Widget, WidgetDyn};
+
+// Builds a context menu from the app state, once it is opened
+type ContextMenuBuilder<S> = Box<dyn Fn(&dyn Any) -> WidgetNode<S>>;
 
 // A node in the widget tree
 pub struct WidgetNode<S> {
@@ -10,7 +15,13 @@ pub struct WidgetNode<S> {
 
     // Whether the widget captures mouse clicks and hovering
     pub(crate) sense_click: bool,
-    pub(crate) click_message: Option<Message<S>>
+    pub(crate) click_message: Option<Message<S>>,
+
+    pub(crate) tooltip: Option<Box<WidgetNode<S>>>,
+    pub(crate) tooltip_delay: Option<f32>,
+
+    pub(crate) context_menu: Option<ContextMenuBuilder<S>>,
+    context_menu_node: OnceCell<Box<WidgetNode<S>>>
 }
 
 impl<S> WidgetNode<S> {
@@ -20,7 +31,11 @@ impl<S> WidgetNode<S> {
             widget: Box::new(widget),
             local_id: None,
             sense_click: false,
-            click_message: None 
+            click_message: None,
+            tooltip: None,
+            tooltip_delay: None,
+            context_menu: None,
+            context_menu_node: OnceCell::new()
         }
     }
 
@@ -31,7 +46,7 @@ impl<S> WidgetNode<S> {
 
     pub fn sense_click(mut self, sense_click: bool) -> Self {
         self.sense_click = sense_click;
-        self 
+        self
     }
 
     pub fn on_click<F>(mut self, handler: F) -> Self where F: Fn(&mut S) + 'static {
@@ -40,23 +55,55 @@ impl<S> WidgetNode<S> {
         self
     }
 
+    // Seconds to wait before showing the tooltip. Defaults to the theme's tooltip delay.
+    pub fn tooltip_delay(mut self, delay: f32) -> Self {
+        self.tooltip_delay = Some(delay);
+        self
+    }
+
     pub fn layout(&self, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutNode<S> {
-        let local_id = if let Some(id) = self.local_id {
+        let local_id = self.take_id(ctx);
+        self.layout_with_id(local_id, max_size, ctx, state)
+    }
+
+    // The id the widget's state is kept under, taking the next automatic id if it wasn't given one
+    pub(crate) fn take_id(&self, ctx: &mut LayoutContext) -> usize {
+        if let Some(id) = self.local_id {
             id
         } else {
             ctx.curr_auto_id += 1;
             ctx.curr_auto_id - 1
-        };
+        }
+    }
 
+    // Lay the widget out under an id it already took, like when a container measures it before its final layout
+    pub(crate) fn layout_with_id(&self, local_id: usize, max_size: Vec2, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutNode<S> {
         let mut child_ctx = LayoutContext {
             text_shaper: ctx.text_shaper,
             theme: ctx.theme,
+            app_state: ctx.app_state,
             curr_auto_id: 0,
             window_size: ctx.window_size
         };
 
-        let LayoutResult {size, children, popovers, sensors} = self.widget.layout(max_size, &mut child_ctx, state.get_child(local_id));
-        LayoutNode::new(local_id, size, self, children, popovers, sensors)
+        let node_state = state.get_child(local_id);
+        let LayoutResult {size, baseline, children, mut popovers, modals, sensors} = self.widget.layout(max_size, &mut child_ctx, node_state);
+        if let (Some(tooltip), Some(tooltip_pos)) = (&self.tooltip, node_state.tooltip.pos) {
+            popovers.push((tooltip_pos, tooltip.layout_popover(&mut child_ctx, node_state)));
+        }
+        if let (Some(build_menu), Some(menu_pos)) = (&self.context_menu, node_state.context_menu.as_ref().map(|menu| menu.pos)) {
+            let menu = self.context_menu_node.get_or_init(|| Box::new(build_menu(ctx.app_state)));
+            let menu_node = menu.layout_popover(&mut child_ctx, node_state);
+            if let Some(menu_state) = &mut node_state.context_menu {
+                menu_state.menu_id = Some(menu_node.local_id);
+            }
+            popovers.push((menu_pos, menu_node));
+        }
+        // Widgets without text of their own take the baseline of their first child
+        let baseline = baseline.or_else(|| children.first().and_then(|(offset, child)| child.baseline.map(|baseline| offset.y + baseline)));
+        let mut node = LayoutNode::new(local_id, size, self, children, popovers, modals, sensors);
+        node.baseline = baseline;
+        node
     }
 
     pub fn layout_popover(&self, ctx: &mut LayoutContext, state: &mut WidgetState<S>) -> LayoutNode<S> {
@@ -64,3 +111,28 @@ impl<S> WidgetNode<S> {
     }
 
 }
+
+impl<S: 'static> WidgetNode<S> {
+
+    // Show a popover next to the cursor after it rests on this widget
+    pub fn tooltip(mut self, contents: WidgetNode<S>) -> Self {
+        self.tooltip = Some(Box::new(Tooltip::new(contents)));
+        self
+    }
+
+    pub fn tooltip_text<T: Into<String>>(self, text: T) -> Self {
+        self.tooltip(Text::new(text))
+    }
+
+    // Open a dropdown at the cursor when the widget is right-clicked, built from the app state when it opens.
+    // The menu closes on a click outside of it or once anything in it sends a message.
+    pub fn context_menu<F>(mut self, menu: F) -> Self where F: Fn(&S) -> WidgetNode<S> + 'static {
+        self.sense_click = true;
+        self.context_menu = Some(Box::new(move |app_state| {
+            let app_state = app_state.downcast_ref::<S>().expect("layout is given the app state");
+            Dropdown::new(menu(app_state))
+        }));
+        self
+    }
+
+}